@@ -1,14 +1,157 @@
-use std::{env, path::PathBuf};
+use std::{
+	env,
+	path::{Path, PathBuf},
+	process::Command
+};
+
+fn archive_name(target_family: &str) -> &'static str {
+	if target_family == "windows" { "zsb.lib" } else { "libzsb.a" }
+}
+
+fn go_os(target_os: &str) -> Option<&'static str> {
+	Some(match target_os {
+		"linux" => "linux",
+		"macos" => "darwin",
+		"ios" => "ios",
+		"windows" => "windows",
+		"android" => "android",
+		"freebsd" => "freebsd",
+		"openbsd" => "openbsd",
+		"netbsd" => "netbsd",
+		"illumos" => "illumos",
+		_ => return None
+	})
+}
+
+fn go_arch(target_arch: &str, target_endian: &str) -> Option<&'static str> {
+	Some(match target_arch {
+		"x86_64" => "amd64",
+		"x86" => "386",
+		"aarch64" => "arm64",
+		"arm" => "arm",
+		"riscv64" => "riscv64",
+		"powerpc64" if target_endian == "big" => "ppc64",
+		"powerpc64" => "ppc64le",
+		"s390x" => "s390x",
+		"loongarch64" => "loong64",
+		_ => return None
+	})
+}
+
+fn go_binary() -> PathBuf {
+	env::var_os("ZSB_GO")
+		.or_else(|| env::var_os("GO"))
+		.map(PathBuf::from)
+		.unwrap_or_else(|| PathBuf::from("go"))
+}
+
+fn has_go(go: &Path) -> bool {
+	Command::new(go).arg("version").output().map(|o| o.status.success()).unwrap_or(false)
+}
+
+/// Builds the Go archive into `out_dir`, returning an error message if `go build` fails.
+fn build_go_archive(go: &Path, native: &Path, out_dir: &Path, archive: &str) -> Result<(), String> {
+	let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+	let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+	let target_endian = env::var("CARGO_CFG_TARGET_ENDIAN").unwrap();
+
+	let mut command = Command::new(go);
+	command
+		.current_dir(native)
+		.env("CGO_ENABLED", "1")
+		.args(["build", "-buildmode=c-archive", "-ldflags", "-s -w", "-o"])
+		.arg(out_dir.join(archive));
+	if let Some(goos) = go_os(&target_os) {
+		command.env("GOOS", goos);
+	}
+	if let Some(goarch) = go_arch(&target_arch, &target_endian) {
+		command.env("GOARCH", goarch);
+	}
+	// cgo needs a C compiler for the target; reuse the one `cc` would pick for Cargo, if configured.
+	let target = env::var("TARGET").unwrap();
+	if let Some(cc) = env::var_os(format!("CC_{}", target.replace('-', "_"))).or_else(|| env::var_os("TARGET_CC")) {
+		command.env("CC", cc);
+	}
+
+	let output = command.output().map_err(|e| format!("failed to run `{} build`: {e}", go.display()))?;
+	if !output.status.success() {
+		return Err(format!("`{} build` failed:\n{}", go.display(), String::from_utf8_lossy(&output.stderr)));
+	}
+	Ok(())
+}
+
+/// Whether `archive` is older than any of the Go sources or C headers in `native` that it is built from.
+fn is_stale(archive: &Path, native: &Path) -> bool {
+	let Ok(built) = archive.metadata().and_then(|m| m.modified()) else {
+		return true;
+	};
+	native.read_dir().into_iter().flatten().flatten().any(|entry| {
+		let path = entry.path();
+		matches!(path.extension().and_then(|ext| ext.to_str()), Some("go" | "h"))
+			&& entry.metadata().and_then(|m| m.modified()).is_ok_and(|modified| modified > built)
+	})
+}
 
 fn main() {
 	let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
-	let native_out_path = manifest.join("native").join("out");
-	println!("cargo:rustc-link-search={}", native_out_path.to_string_lossy());
-	println!("cargo:rustc-link-lib=static=zsb");
+	let native = manifest.join("native");
+	let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
 
 	let target_family = env::var("CARGO_CFG_TARGET_FAMILY").unwrap();
 	let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
 	let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap_or_default();
+	let archive = archive_name(&target_family);
+
+	println!("cargo:rerun-if-env-changed=ZSB_LIB_DIR");
+	println!("cargo:rerun-if-env-changed=ZSB_GO");
+	println!("cargo:rerun-if-env-changed=GO");
+	for entry in native.read_dir().unwrap().flatten() {
+		let path = entry.path();
+		if path.is_file() {
+			println!("cargo:rerun-if-changed={}", path.to_string_lossy());
+		}
+	}
+	println!("cargo:rerun-if-changed={}", native.join("out").join(archive).to_string_lossy());
+
+	// 1. An explicitly provided prebuilt archive always wins.
+	// 2. Otherwise, build the archive from `native/` with the Go toolchain.
+	// 3. Otherwise, fall back to an archive previously built with `make` in `native/`.
+	let lib_dir = if let Some(lib_dir) = env::var_os("ZSB_LIB_DIR").map(PathBuf::from) {
+		if !lib_dir.join(archive).exists() {
+			panic!(
+				"`ZSB_LIB_DIR` is set to `{}`, but it does not contain `{archive}`. Point it at the directory containing the archive built by `go build -buildmode=c-archive` in `native/`.",
+				lib_dir.display()
+			);
+		}
+		lib_dir
+	} else {
+		let go = go_binary();
+		let prebuilt_dir = native.join("out");
+		if has_go(&go) {
+			if let Err(e) = build_go_archive(&go, &native, &out_dir, archive) {
+				panic!(
+					"{e}\nIf the Go module cache is unavailable (e.g. no network access), vendor it with `go mod vendor` in `native/`, or set `ZSB_LIB_DIR` to a directory containing a prebuilt `{archive}`."
+				);
+			}
+			out_dir
+		} else if prebuilt_dir.join(archive).exists() && !is_stale(&prebuilt_dir.join(archive), &native) {
+			prebuilt_dir
+		} else {
+			// A stale archive lacks the symbols of newer Go exports and would only fail later with undefined symbols.
+			panic!(
+				"Could not find a Go toolchain to build the zsbuild native library, and no up-to-date prebuilt `{archive}` was found (`native/out/{archive}` is missing or older than the sources in `native/`).\n\
+				 Do one of the following:\n\
+				 - install Go 1.21+ and make sure `go` is in your PATH (or set `ZSB_GO` to the `go` binary),\n\
+				 - run `make` in `{}` to produce `native/out/{archive}`,\n\
+				 - or set `ZSB_LIB_DIR` to a directory containing a prebuilt `{archive}`.",
+				native.display()
+			);
+		}
+	};
+
+	println!("cargo:rustc-link-search=native={}", lib_dir.to_string_lossy());
+	println!("cargo:rustc-link-lib=static=zsb");
+
 	if target_family == "windows" {
 		// println!("cargo:rustc-link-lib=static=win32rt_patch");
 		if target_env == "msvc" {
@@ -32,8 +175,8 @@ fn main() {
 			.allowlist_function("Zsb_.+")
 			.allowlist_recursively(true)
 			.layout_tests(false)
-			.clang_arg(format!("-I{}", manifest.join("native").to_string_lossy()))
-			.header(native_out_path.join("zsb.h").to_string_lossy())
+			.clang_arg(format!("-I{}", native.to_string_lossy()))
+			.header(lib_dir.join("zsb.h").to_string_lossy())
 			.generate()
 			.unwrap();
 
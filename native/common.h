@@ -51,6 +51,25 @@ struct OutputFile {
 	size_t contents_len;
 };
 
+struct MangleCacheEntry {
+	const char *key;
+	size_t key_len;
+	// NULL if the property is reserved (`false` in esbuild's cache)
+	const char *value;
+	size_t value_len;
+};
+
+struct PluginTiming {
+	const char *plugin;
+	size_t plugin_len;
+	uint8_t hook;
+	const char *filter;
+	size_t filter_len;
+	uint64_t calls;
+	uint64_t total_ns;
+	uint64_t max_ns;
+};
+
 struct BuildResult {
 	struct OutputFile *output_files;
 	size_t output_files_len;
@@ -58,11 +77,83 @@ struct BuildResult {
 	size_t errors_len;
 	struct Message *warnings;
 	size_t warnings_len;
+	const char *metafile;
+	size_t metafile_len;
+	bool cancelled;
+	bool has_mangle_cache;
+	struct MangleCacheEntry *mangle_cache;
+	size_t mangle_cache_len;
+	struct PluginTiming *plugin_timings;
+	size_t plugin_timings_len;
+	// wall-clock duration of the build in nanoseconds
+	int64_t duration_ns;
+};
+
+struct Error {
+	char *message;
+	size_t message_len;
+};
+
+struct ServeOptions {
+	const char *host;
+	size_t host_len;
+	uint16_t port;
+	const char *servedir;
+	size_t servedir_len;
+	const char *keyfile;
+	size_t keyfile_len;
+	const char *certfile;
+	size_t certfile_len;
+	const char *fallback;
+	size_t fallback_len;
+};
+
+struct ServeResult {
+	char *host;
+	size_t host_len;
+	uint16_t port;
+};
+
+struct TransformOptions {
+	uint8_t loader;
+	const char *sourcefile;
+	size_t sourcefile_len;
+	uint8_t format;
+	uint8_t sourcemap;
+	bool minify_whitespace;
+	bool minify_identifiers;
+	bool minify_syntax;
+	const char *tsconfig_raw;
+	size_t tsconfig_raw_len;
+	uint8_t jsx;
+	const char *jsx_factory;
+	size_t jsx_factory_len;
+	const char *jsx_fragment;
+	size_t jsx_fragment_len;
+	const char *jsx_import_source;
+	size_t jsx_import_source_len;
+	bool jsx_dev;
+	bool jsx_side_effects;
+};
+
+struct TransformResult {
+	const char *code;
+	size_t code_len;
+	// NULL unless the source map is external
+	const char *source_map;
+	size_t source_map_len;
+	struct Message *errors;
+	size_t errors_len;
+	struct Message *warnings;
+	size_t warnings_len;
 };
 
 typedef void (*BuildAsyncCallback)(struct BuildResult *result, void *data);
 void Zsb_BuildAsyncCallback_Dispatch(BuildAsyncCallback callback, struct BuildResult *result, void *data);
 
+typedef void (*LogCallback)(uint8_t level, struct Message *message);
+void Zsb_LogCallback_Dispatch(LogCallback callback, uint8_t level, struct Message *message);
+
 typedef void (*PluginBuildCallback)(uint64_t handle, void *data);
 void Zsb_PluginBuildCallback_Dispatch(PluginBuildCallback callback, uint64_t handle, void *data);
 typedef void (*PluginDestructor)(void *data);
@@ -79,4 +170,83 @@ void Zsb_PluginOnStartResult_Destroy(struct PluginOnStartResult *res);
 typedef struct PluginOnStartResult *(*PluginCallbackOnStart)(void *data);
 struct PluginOnStartResult *Zsb_PluginCallbackOnStart_Dispatch(PluginCallbackOnStart callback, void *data);
 
+struct PluginOnResolveArgs {
+	const char *path;
+	size_t path_len;
+	const char *importer;
+	size_t importer_len;
+	const char *namespace_;
+	size_t namespace_len;
+	const char *resolve_dir;
+	size_t resolve_dir_len;
+	uint8_t kind;
+	// owned by Go; only valid for the duration of the callback
+	void *plugin_data;
+};
+
+struct PluginOnResolveResult {
+	const char *path;
+	size_t path_len;
+	const char *namespace_;
+	size_t namespace_len;
+	const char *suffix;
+	size_t suffix_len;
+	bool external;
+	bool no_side_effects;
+	// ownership is transferred to Go, which calls the destructor once the build ends
+	void *plugin_data;
+	PluginDestructor plugin_data_destructor;
+	struct Message *errors;
+	size_t errors_len;
+	struct Message *warnings;
+	size_t warnings_len;
+};
+void Zsb_PluginOnResolveResult_Destroy(struct PluginOnResolveResult *res);
+
+typedef struct PluginOnResolveResult *(*PluginCallbackOnResolve)(struct PluginOnResolveArgs *args, void *data);
+struct PluginOnResolveResult *Zsb_PluginCallbackOnResolve_Dispatch(PluginCallbackOnResolve callback, struct PluginOnResolveArgs *args, void *data);
+
+struct PluginOnLoadArgs {
+	const char *path;
+	size_t path_len;
+	const char *namespace_;
+	size_t namespace_len;
+	const char *suffix;
+	size_t suffix_len;
+	// owned by Go; only valid for the duration of the callback
+	void *plugin_data;
+};
+
+struct PluginOnLoadResult {
+	const char *contents;
+	size_t contents_len;
+	bool has_contents;
+	const char *resolve_dir;
+	size_t resolve_dir_len;
+	bool has_loader;
+	uint8_t loader;
+	// ownership is transferred to Go, which calls the destructor once the build ends
+	void *plugin_data;
+	PluginDestructor plugin_data_destructor;
+	struct Message *errors;
+	size_t errors_len;
+	struct Message *warnings;
+	size_t warnings_len;
+};
+void Zsb_PluginOnLoadResult_Destroy(struct PluginOnLoadResult *res);
+
+typedef struct PluginOnLoadResult *(*PluginCallbackOnLoad)(struct PluginOnLoadArgs *args, void *data);
+struct PluginOnLoadResult *Zsb_PluginCallbackOnLoad_Dispatch(PluginCallbackOnLoad callback, struct PluginOnLoadArgs *args, void *data);
+
+struct PluginOnEndResult {
+	struct Message *errors;
+	size_t errors_len;
+	struct Message *warnings;
+	size_t warnings_len;
+};
+void Zsb_PluginOnEndResult_Destroy(struct PluginOnEndResult *res);
+
+typedef struct PluginOnEndResult *(*PluginCallbackOnEnd)(struct BuildResult *result, void *data);
+struct PluginOnEndResult *Zsb_PluginCallbackOnEnd_Dispatch(PluginCallbackOnEnd callback, struct BuildResult *result, void *data);
+
 #endif
@@ -146,6 +146,18 @@ func deserializeMessage(message *C.struct_Message) esbuild.Message {
 	return deserialized
 }
 
+func deserializeMessages(messages *C.struct_Message, messagesLen C.size_t) []esbuild.Message {
+	if messagesLen == 0 {
+		return nil
+	}
+	serialized := unsafe.Slice(messages, int(messagesLen))
+	deserialized := make([]esbuild.Message, len(serialized))
+	for i := range serialized {
+		deserialized[i] = deserializeMessage(&serialized[i])
+	}
+	return deserialized
+}
+
 func destroyMessage(message *C.struct_Message) {
 	// free(message.id)
 	// free(message.plugin_name)
@@ -160,3 +172,17 @@ func destroyMessage(message *C.struct_Message) {
 		free(message.notes)
 	}
 }
+
+func newError(err error) *C.struct_Error {
+	message := err.Error()
+	serialized := alloc(C.struct_Error{})
+	serialized.message = C.CString(message)
+	serialized.message_len = C.size_t(len(message))
+	return serialized
+}
+
+//export Zsb_Error_Destroy
+func Zsb_Error_Destroy(err *C.struct_Error) {
+	C.free(unsafe.Pointer(err.message))
+	free(err)
+}
@@ -0,0 +1,165 @@
+package main
+
+// #include "common.h"
+import "C"
+import (
+	"runtime"
+	"sync"
+
+	esbuild "github.com/evanw/esbuild/pkg/api"
+)
+
+// Indexed by the discriminant of the Rust `LogLevel` enum.
+var logLevels = [...]esbuild.LogLevel{
+	esbuild.LogLevelVerbose,
+	esbuild.LogLevelDebug,
+	esbuild.LogLevelInfo,
+	esbuild.LogLevelWarning,
+	esbuild.LogLevelError,
+	esbuild.LogLevelSilent,
+}
+
+const (
+	logLevelIndexInfo    = 2
+	logLevelIndexWarning = 3
+	logLevelIndexError   = 4
+)
+
+// Forwards a notice when each build starts, and the warnings and errors of each build, to a callback. esbuild has no
+// hook for its own log output, so this is driven by an `OnStart` and an `OnEnd` plugin and only sees the messages that
+// end up in the build result.
+type logForwarder struct {
+	callback C.LogCallback
+	// index into `logLevels`; messages less severe than this are not forwarded
+	level uint8
+	// maximum number of messages forwarded per build; 0 means no limit
+	limit int
+}
+
+var (
+	// map[uint64]*logForwarder, keyed by build options handle
+	logForwarders = sync.Map{}
+)
+
+func (f *logForwarder) dispatch(level uint8, message *esbuild.Message) {
+	if level < f.level {
+		return
+	}
+	pinner := new(runtime.Pinner)
+	cMessage := alloc(C.struct_Message{})
+	*cMessage = serializeMessage(message, pinner)
+	C.Zsb_LogCallback_Dispatch(f.callback, C.uint8_t(level), cMessage)
+	destroyMessage(cMessage)
+	free(cMessage)
+	pinner.Unpin()
+}
+
+func (f *logForwarder) plugin() esbuild.Plugin {
+	return esbuild.Plugin{
+		Name: "zsbuild:log",
+		Setup: func(build esbuild.PluginBuild) {
+			// `Setup` runs once per build or context, so every build after the first one is a rebuild (e.g. in watch mode)
+			rebuild := false
+			build.OnStart(func() (esbuild.OnStartResult, error) {
+				text := "build started"
+				if rebuild {
+					text = "rebuild started"
+				}
+				rebuild = true
+				f.dispatch(logLevelIndexInfo, &esbuild.Message{Text: text})
+				return esbuild.OnStartResult{}, nil
+			})
+			build.OnEnd(func(result *esbuild.BuildResult) (esbuild.OnEndResult, error) {
+				forwarded := 0
+				forward := func(level uint8, messages []esbuild.Message) {
+					for i := range messages {
+						if f.limit > 0 && forwarded >= f.limit {
+							return
+						}
+						f.dispatch(level, &messages[i])
+						forwarded++
+					}
+				}
+				forward(logLevelIndexError, result.Errors)
+				if f.level <= logLevelIndexWarning {
+					forward(logLevelIndexWarning, result.Warnings)
+				}
+				return esbuild.OnEndResult{}, nil
+			})
+		},
+	}
+}
+
+//export Zsb_BuildOptions_LogLevel
+func Zsb_BuildOptions_LogLevel(handle uint64, level uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(level) >= len(logLevels) {
+		return 1
+	}
+	if f, ok := logForwarders.Load(handle); ok {
+		// esbuild itself stays silent; the level only filters what gets forwarded.
+		f.(*logForwarder).level = level
+	} else {
+		v.(*esbuild.BuildOptions).LogLevel = logLevels[level]
+	}
+	return 0
+}
+
+//export Zsb_BuildOptions_LogLimit
+func Zsb_BuildOptions_LogLimit(handle uint64, limit int32) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if f, ok := logForwarders.Load(handle); ok {
+		f.(*logForwarder).limit = int(limit)
+	}
+	v.(*esbuild.BuildOptions).LogLimit = int(limit)
+	return 0
+}
+
+//export Zsb_BuildOptions_LogOverride
+func Zsb_BuildOptions_LogOverride(handle uint64, id *C.char, idLen C.size_t, level uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(level) >= len(logLevels) {
+		return 1
+	}
+	options := v.(*esbuild.BuildOptions)
+	if options.LogOverride == nil {
+		options.LogOverride = map[string]esbuild.LogLevel{}
+	}
+	options.LogOverride[C.GoStringN(id, C.int(idLen))] = logLevels[level]
+	return 0
+}
+
+//export Zsb_BuildOptions_LogCallback
+func Zsb_BuildOptions_LogCallback(handle uint64, callback C.LogCallback) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if f, ok := logForwarders.Load(handle); ok {
+		// enabling forwarding twice is fine, but only to the same callback
+		if f.(*logForwarder).callback != callback {
+			return 1
+		}
+		return 0
+	}
+	options := v.(*esbuild.BuildOptions)
+	forwarder := &logForwarder{callback: callback, level: logLevelIndexWarning, limit: options.LogLimit}
+	// carry over a level that was configured before forwarding was enabled
+	for i, level := range logLevels {
+		if level == options.LogLevel && level != esbuild.LogLevelSilent {
+			forwarder.level = uint8(i)
+		}
+	}
+	logForwarders.Store(handle, forwarder)
+	options.LogLevel = esbuild.LogLevelSilent
+	return 0
+}
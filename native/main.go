@@ -6,29 +6,140 @@ import (
 	"runtime"
 	"sync"
 	"sync/atomic"
+	"time"
 	"unsafe"
 
 	esbuild "github.com/evanw/esbuild/pkg/api"
 )
 
 var (
-	// map[uint64]esbuild.BuildContext
+	// map[uint64]*buildContext
 	contextHandles       = sync.Map{}
 	contextHandleAcc     atomic.Uint64
 	contextResultPinners = sync.Map{} // map[*C.struct_ContextResult]*runtime.Pinner
 	buildResultPinners   = sync.Map{} // map[*C.struct_BuildResult]*runtime.Pinner
 )
 
+// buildContext wraps an esbuild.BuildContext so that individual builds can be cancelled. esbuild itself only knows how
+// to cancel "the current build", so builds started through zsbuild are serialized and tagged with an ID allocated by
+// the Rust side.
+type buildContext struct {
+	ctx esbuild.BuildContext
+	// held for the entire duration of a tracked build
+	buildMu sync.Mutex
+	// guards `current` & `builds`
+	stateMu sync.Mutex
+	current uint64
+	// map of queued or in-flight build IDs to whether they have been cancelled
+	builds map[uint64]bool
+	// profiles of the plugins the context was created with
+	profiles []*pluginProfile
+}
+
+func (c *buildContext) prepare(buildId uint64) {
+	c.stateMu.Lock()
+	if _, ok := c.builds[buildId]; !ok {
+		c.builds[buildId] = false
+	}
+	c.stateMu.Unlock()
+}
+
+func (c *buildContext) rebuild(buildId uint64, timeoutMs int64) *C.struct_BuildResult {
+	c.prepare(buildId)
+
+	if timeoutMs > 0 {
+		timer := time.AfterFunc(time.Duration(timeoutMs)*time.Millisecond, func() { c.cancel(buildId) })
+		defer timer.Stop()
+	}
+
+	c.buildMu.Lock()
+	c.stateMu.Lock()
+	cancelled := c.builds[buildId]
+	c.current = buildId
+	c.stateMu.Unlock()
+
+	var goRes esbuild.BuildResult
+	start := time.Now()
+	if !cancelled {
+		goRes = c.ctx.Rebuild()
+	}
+	duration := time.Since(start)
+
+	c.stateMu.Lock()
+	cancelled = c.builds[buildId]
+	delete(c.builds, buildId)
+	c.current = 0
+	c.stateMu.Unlock()
+	c.buildMu.Unlock()
+
+	pinner := new(runtime.Pinner)
+	cRes := serializeBuildResult(&goRes, pinner)
+	serializePluginTimings(cRes, c.profiles, pinner)
+	cRes.cancelled = C.bool(cancelled)
+	cRes.duration_ns = C.int64_t(duration)
+	buildResultPinners.Store(cRes, pinner)
+	return cRes
+}
+
+func (c *buildContext) cancel(buildId uint64) {
+	c.stateMu.Lock()
+	defer c.stateMu.Unlock()
+	if _, ok := c.builds[buildId]; !ok {
+		return
+	}
+	c.builds[buildId] = true
+	if c.current == buildId {
+		// `Cancel()` blocks until the build has stopped; don't make the caller wait for that. By the time the goroutine
+		// runs, the build may have finished and the next queued one started, so check it is still current (and keep it
+		// current until it has been cancelled) before cancelling.
+		go func() {
+			c.stateMu.Lock()
+			defer c.stateMu.Unlock()
+			if c.current == buildId {
+				c.ctx.Cancel()
+			}
+		}()
+	}
+}
+
+func (c *buildContext) cancelAll() {
+	c.stateMu.Lock()
+	for buildId := range c.builds {
+		c.builds[buildId] = true
+	}
+	c.stateMu.Unlock()
+	c.ctx.Cancel()
+}
+
+// instantiateBuildOptions returns the options to create a build or context with, along with the profiles its plugins
+// record their timings into. esbuild sets plugins up once per build or context, so each one gets plugins (and profiles)
+// of its own. The log forwarder, if enabled, is added last so that it also sees the messages added by the `OnEnd`
+// callbacks of every other plugin.
+func instantiateBuildOptions(handle uint64, options *esbuild.BuildOptions) (esbuild.BuildOptions, []*pluginProfile) {
+	opts := *options
+	descriptors := optionsPluginDescriptors(handle)
+	opts.Plugins = make([]esbuild.Plugin, 0, len(descriptors)+1)
+	profiles := make([]*pluginProfile, len(descriptors))
+	for i, descriptor := range descriptors {
+		profiles[i] = &pluginProfile{name: descriptor.name}
+		opts.Plugins = append(opts.Plugins, descriptor.plugin(profiles[i]))
+	}
+	if f, ok := logForwarders.Load(handle); ok {
+		opts.Plugins = append(opts.Plugins, f.(*logForwarder).plugin())
+	}
+	return opts, profiles
+}
+
 //export Zsb_Context_Create
 func Zsb_Context_Create(optionsHandle uint64, outHandle *uint64) *C.struct_ContextResult {
 	v, ok := buildOptions.Load(optionsHandle)
 	if !ok {
 		panic("bad build options handle")
 	}
-	options := v.(*esbuild.BuildOptions)
+	options, profiles := instantiateBuildOptions(optionsHandle, v.(*esbuild.BuildOptions))
 
 	*outHandle = contextHandleAcc.Add(1)
-	context, err := esbuild.Context(*options)
+	context, err := esbuild.Context(options)
 	if err != nil {
 		numMessages := len(err.Errors)
 		result := alloc(C.struct_ContextResult{})
@@ -45,7 +156,7 @@ func Zsb_Context_Create(optionsHandle uint64, outHandle *uint64) *C.struct_Conte
 		return result
 	}
 
-	contextHandles.Store(*outHandle, context)
+	contextHandles.Store(*outHandle, &buildContext{ctx: context, builds: map[uint64]bool{}, profiles: profiles})
 	return nil
 }
 
@@ -67,17 +178,25 @@ func Zsb_ContextResult_Destroy(res *C.struct_ContextResult) {
 	}
 }
 
+// Zsb_Context_PrepareBuild registers a build ID ahead of time, so that a cancellation which races with the start of
+// the build is not lost.
+//
+//export Zsb_Context_PrepareBuild
+func Zsb_Context_PrepareBuild(handle uint64, buildId uint64) {
+	v, ok := contextHandles.Load(handle)
+	if !ok {
+		panic("bad context handle")
+	}
+	v.(*buildContext).prepare(buildId)
+}
+
 //export Zsb_Context_Build
-func Zsb_Context_Build(handle uint64) *C.struct_BuildResult {
+func Zsb_Context_Build(handle uint64, buildId uint64, timeoutMs int64) *C.struct_BuildResult {
 	v, ok := contextHandles.Load(handle)
 	if !ok {
 		panic("bad context handle")
 	}
-	goRes := v.(esbuild.BuildContext).Rebuild()
-	pinner := new(runtime.Pinner)
-	cRes := serializeBuildResult(&goRes, pinner)
-	buildResultPinners.Store(cRes, pinner)
-	return cRes
+	return v.(*buildContext).rebuild(buildId, timeoutMs)
 }
 
 //export Zsb_Build
@@ -86,37 +205,43 @@ func Zsb_Build(optionsHandle uint64) *C.struct_BuildResult {
 	if !ok {
 		panic("bad build options handle")
 	}
-	goRes := esbuild.Build(*v.(*esbuild.BuildOptions))
+	options, profiles := instantiateBuildOptions(optionsHandle, v.(*esbuild.BuildOptions))
+	start := time.Now()
+	goRes := esbuild.Build(options)
+	duration := time.Since(start)
 	pinner := new(runtime.Pinner)
 	cRes := serializeBuildResult(&goRes, pinner)
+	serializePluginTimings(cRes, profiles, pinner)
+	cRes.duration_ns = C.int64_t(duration)
 	buildResultPinners.Store(cRes, pinner)
 	return cRes
 }
 
-func buildContextAsyncInner(ctx esbuild.BuildContext, callback C.BuildAsyncCallback, data unsafe.Pointer) {
-	res := ctx.Rebuild()
-	pinner := new(runtime.Pinner)
-	cRes := serializeBuildResult(&res, pinner)
-	buildResultPinners.Store(cRes, pinner)
+func buildContextAsyncInner(ctx *buildContext, buildId uint64, timeoutMs int64, callback C.BuildAsyncCallback, data unsafe.Pointer) {
+	cRes := ctx.rebuild(buildId, timeoutMs)
 	C.Zsb_BuildAsyncCallback_Dispatch(callback, cRes, data)
 }
 
-func buildAsyncInner(options *esbuild.BuildOptions, callback C.BuildAsyncCallback, data unsafe.Pointer) {
-	res := esbuild.Build(*options)
+func buildAsyncInner(options esbuild.BuildOptions, profiles []*pluginProfile, callback C.BuildAsyncCallback, data unsafe.Pointer) {
+	start := time.Now()
+	res := esbuild.Build(options)
+	duration := time.Since(start)
 	pinner := new(runtime.Pinner)
 	cRes := serializeBuildResult(&res, pinner)
+	serializePluginTimings(cRes, profiles, pinner)
+	cRes.duration_ns = C.int64_t(duration)
 	buildResultPinners.Store(cRes, pinner)
 	C.Zsb_BuildAsyncCallback_Dispatch(callback, cRes, data)
 }
 
 //export Zsb_Context_BuildAsync
-func Zsb_Context_BuildAsync(handle uint64, callback C.BuildAsyncCallback, data *C.void) {
+func Zsb_Context_BuildAsync(handle uint64, buildId uint64, timeoutMs int64, callback C.BuildAsyncCallback, data *C.void) {
 	v, ok := contextHandles.Load(handle)
 	if !ok {
 		panic("bad context handle")
 	}
-	ctx := v.(esbuild.BuildContext)
-	go buildContextAsyncInner(ctx, callback, unsafe.Pointer(data))
+	ctx := v.(*buildContext)
+	go buildContextAsyncInner(ctx, buildId, timeoutMs, callback, unsafe.Pointer(data))
 }
 
 //export Zsb_BuildAsync
@@ -125,7 +250,8 @@ func Zsb_BuildAsync(optionsHandle uint64, callback C.BuildAsyncCallback, data *C
 	if !ok {
 		panic("bad build options handle")
 	}
-	go buildAsyncInner(v.(*esbuild.BuildOptions), callback, unsafe.Pointer(data))
+	options, profiles := instantiateBuildOptions(optionsHandle, v.(*esbuild.BuildOptions))
+	go buildAsyncInner(options, profiles, callback, unsafe.Pointer(data))
 }
 
 func serializeOutputFile(file *esbuild.OutputFile, pinner *runtime.Pinner) C.struct_OutputFile {
@@ -172,6 +298,31 @@ func serializeBuildResult(goRes *esbuild.BuildResult, pinner *runtime.Pinner) *C
 		cRes.warnings = warnings
 	}
 
+	if len(goRes.Metafile) != 0 {
+		cRes.metafile = pinnedString(goRes.Metafile, pinner)
+		cRes.metafile_len = C.size_t(len(goRes.Metafile))
+	}
+
+	if goRes.MangleCache != nil {
+		cRes.has_mangle_cache = true
+		cRes.mangle_cache_len = C.size_t(len(goRes.MangleCache))
+		if cRes.mangle_cache_len > 0 {
+			entriesSlice, entries := allocSlice(cRes.mangle_cache_len, C.struct_MangleCacheEntry{})
+			i := 0
+			for key, value := range goRes.MangleCache {
+				entry := C.struct_MangleCacheEntry{key: pinnedString(key, pinner), key_len: C.size_t(len(key))}
+				// values are either the mangled name, or `false` for reserved names
+				if mangled, ok := value.(string); ok {
+					entry.value = pinnedString(mangled, pinner)
+					entry.value_len = C.size_t(len(mangled))
+				}
+				entriesSlice[i] = entry
+				i++
+			}
+			cRes.mangle_cache = entries
+		}
+	}
+
 	return cRes
 }
 
@@ -201,6 +352,12 @@ func Zsb_BuildResult_Destroy(c *C.struct_BuildResult) {
 		}
 		free(c.output_files)
 	}
+	if c.mangle_cache_len > 0 {
+		free(c.mangle_cache)
+	}
+	if c.plugin_timings_len > 0 {
+		free(c.plugin_timings)
+	}
 	free(c)
 
 	pinner, ok := buildResultPinners.LoadAndDelete(c)
@@ -222,7 +379,67 @@ func Zsb_Context_Cancel(handle uint64) {
 	if !ok {
 		panic("bad context handle")
 	}
-	v.(esbuild.BuildContext).Cancel()
+	v.(*buildContext).cancelAll()
+}
+
+//export Zsb_Context_CancelBuild
+func Zsb_Context_CancelBuild(handle uint64, buildId uint64) {
+	v, ok := contextHandles.Load(handle)
+	if !ok {
+		return
+	}
+	v.(*buildContext).cancel(buildId)
+}
+
+//export Zsb_Context_Watch
+func Zsb_Context_Watch(handle uint64) *C.struct_Error {
+	v, ok := contextHandles.Load(handle)
+	if !ok {
+		panic("bad context handle")
+	}
+	if err := v.(*buildContext).ctx.Watch(esbuild.WatchOptions{}); err != nil {
+		return newError(err)
+	}
+	return nil
+}
+
+//export Zsb_Context_Serve
+func Zsb_Context_Serve(handle uint64, options *C.struct_ServeOptions, outResult *C.struct_ServeResult) *C.struct_Error {
+	v, ok := contextHandles.Load(handle)
+	if !ok {
+		panic("bad context handle")
+	}
+	serveOptions := esbuild.ServeOptions{Port: uint16(options.port)}
+	if options.host_len > 0 {
+		serveOptions.Host = C.GoStringN(options.host, C.int(options.host_len))
+	}
+	if options.servedir_len > 0 {
+		serveOptions.Servedir = C.GoStringN(options.servedir, C.int(options.servedir_len))
+	}
+	if options.keyfile_len > 0 {
+		serveOptions.Keyfile = C.GoStringN(options.keyfile, C.int(options.keyfile_len))
+	}
+	if options.certfile_len > 0 {
+		serveOptions.Certfile = C.GoStringN(options.certfile, C.int(options.certfile_len))
+	}
+	if options.fallback_len > 0 {
+		serveOptions.Fallback = C.GoStringN(options.fallback, C.int(options.fallback_len))
+	}
+	res, err := v.(*buildContext).ctx.Serve(serveOptions)
+	if err != nil {
+		return newError(err)
+	}
+	outResult.port = C.uint16_t(res.Port)
+	outResult.host = C.CString(res.Host)
+	outResult.host_len = C.size_t(len(res.Host))
+	return nil
+}
+
+//export Zsb_ServeResult_Destroy
+func Zsb_ServeResult_Destroy(res *C.struct_ServeResult) {
+	C.free(unsafe.Pointer(res.host))
+	res.host = nil
+	res.host_len = 0
 }
 
 //export Zsb_Context_Destroy
@@ -231,7 +448,7 @@ func Zsb_Context_Destroy(handle uint64) {
 	if !ok {
 		return
 	}
-	v.(esbuild.BuildContext).Dispose()
+	v.(*buildContext).ctx.Dispose()
 }
 
 func main() {}
@@ -8,7 +8,8 @@ import (
 )
 
 func alloc[T any](empty T) (ptr *T) {
-	ptr = (*T)(C.malloc(C.size_t(unsafe.Sizeof(empty))))
+	// zeroed, so that optional fields which are never assigned read as null/0 on the Rust side
+	ptr = (*T)(C.calloc(1, C.size_t(unsafe.Sizeof(empty))))
 	return ptr
 }
 
@@ -6,6 +6,7 @@ import (
 	esbuild "github.com/evanw/esbuild/pkg/api"
 	"sync"
 	"sync/atomic"
+	"unsafe"
 )
 
 var (
@@ -31,6 +32,568 @@ func Zsb_BuildOptions_Bundle(handle uint64, enable bool) uint16 {
 	return 0
 }
 
+var (
+	formats = [...]esbuild.Format{
+		esbuild.FormatDefault,
+		esbuild.FormatIIFE,
+		esbuild.FormatCommonJS,
+		esbuild.FormatESModule,
+	}
+	sourceMaps = [...]esbuild.SourceMap{
+		esbuild.SourceMapNone,
+		esbuild.SourceMapLinked,
+		esbuild.SourceMapInline,
+		esbuild.SourceMapExternal,
+		esbuild.SourceMapInlineAndExternal,
+	}
+	legalCommentModes = [...]esbuild.LegalComments{
+		esbuild.LegalCommentsDefault,
+		esbuild.LegalCommentsNone,
+		esbuild.LegalCommentsInline,
+		esbuild.LegalCommentsEndOfFile,
+		esbuild.LegalCommentsLinked,
+		esbuild.LegalCommentsExternal,
+	}
+	jsxModes = [...]esbuild.JSX{
+		esbuild.JSXTransform,
+		esbuild.JSXPreserve,
+		esbuild.JSXAutomatic,
+	}
+	packagesModes = [...]esbuild.Packages{
+		esbuild.PackagesDefault,
+		esbuild.PackagesExternal,
+	}
+	loaders = [...]esbuild.Loader{
+		esbuild.LoaderJS,
+		esbuild.LoaderJSX,
+		esbuild.LoaderTS,
+		esbuild.LoaderTSX,
+		esbuild.LoaderCSS,
+		esbuild.LoaderLocalCSS,
+		esbuild.LoaderGlobalCSS,
+		esbuild.LoaderJSON,
+		esbuild.LoaderText,
+		esbuild.LoaderBase64,
+		esbuild.LoaderDataURL,
+		esbuild.LoaderFile,
+		esbuild.LoaderBinary,
+		esbuild.LoaderCopy,
+		esbuild.LoaderEmpty,
+		esbuild.LoaderDefault,
+	}
+	platforms = [...]esbuild.Platform{
+		esbuild.PlatformBrowser,
+		esbuild.PlatformNode,
+		esbuild.PlatformNeutral,
+	}
+	esVersions = [...]esbuild.Target{
+		esbuild.ES5,
+		esbuild.ES2015,
+		esbuild.ES2016,
+		esbuild.ES2017,
+		esbuild.ES2018,
+		esbuild.ES2019,
+		esbuild.ES2020,
+		esbuild.ES2021,
+		esbuild.ES2022,
+		esbuild.ES2023,
+		esbuild.ESNext,
+	}
+	engineNames = [...]esbuild.EngineName{
+		esbuild.EngineChrome,
+		esbuild.EngineDeno,
+		esbuild.EngineEdge,
+		esbuild.EngineFirefox,
+		esbuild.EngineHermes,
+		esbuild.EngineIE,
+		esbuild.EngineIOS,
+		esbuild.EngineNode,
+		esbuild.EngineOpera,
+		esbuild.EngineRhino,
+		esbuild.EngineSafari,
+	}
+)
+
+//export Zsb_BuildOptions_Splitting
+func Zsb_BuildOptions_Splitting(handle uint64, enable bool) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).Splitting = enable
+	return 0
+}
+
+//export Zsb_BuildOptions_Write
+func Zsb_BuildOptions_Write(handle uint64, enable bool) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).Write = enable
+	return 0
+}
+
+//export Zsb_BuildOptions_Metafile
+func Zsb_BuildOptions_Metafile(handle uint64, enable bool) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).Metafile = enable
+	return 0
+}
+
+//export Zsb_BuildOptions_Minify
+func Zsb_BuildOptions_Minify(handle uint64, whitespace bool, identifiers bool, syntax bool) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	options.MinifyWhitespace = whitespace
+	options.MinifyIdentifiers = identifiers
+	options.MinifySyntax = syntax
+	return 0
+}
+
+//export Zsb_BuildOptions_Format
+func Zsb_BuildOptions_Format(handle uint64, format uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(format) >= len(formats) {
+		return 1
+	}
+	v.(*esbuild.BuildOptions).Format = formats[format]
+	return 0
+}
+
+//export Zsb_BuildOptions_Sourcemap
+func Zsb_BuildOptions_Sourcemap(handle uint64, sourceMap uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(sourceMap) >= len(sourceMaps) {
+		return 1
+	}
+	v.(*esbuild.BuildOptions).Sourcemap = sourceMaps[sourceMap]
+	return 0
+}
+
+//export Zsb_BuildOptions_LegalComments
+func Zsb_BuildOptions_LegalComments(handle uint64, legalComments uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(legalComments) >= len(legalCommentModes) {
+		return 1
+	}
+	v.(*esbuild.BuildOptions).LegalComments = legalCommentModes[legalComments]
+	return 0
+}
+
+//export Zsb_BuildOptions_Loader
+func Zsb_BuildOptions_Loader(handle uint64, ext *C.char, extLen C.size_t, loader uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(loader) >= len(loaders) {
+		return 1
+	}
+	options := v.(*esbuild.BuildOptions)
+	if options.Loader == nil {
+		options.Loader = map[string]esbuild.Loader{}
+	}
+	options.Loader[C.GoStringN(ext, C.int(extLen))] = loaders[loader]
+	return 0
+}
+
+//export Zsb_BuildOptions_Platform
+func Zsb_BuildOptions_Platform(handle uint64, platform uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(platform) >= len(platforms) {
+		return 1
+	}
+	v.(*esbuild.BuildOptions).Platform = platforms[platform]
+	return 0
+}
+
+// Zsb_BuildOptions_Target sets the language target and clears any engine targets, which are then re-added one by one
+// through Zsb_BuildOptions_AddEngine.
+//
+//export Zsb_BuildOptions_Target
+func Zsb_BuildOptions_Target(handle uint64, esVersion uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(esVersion) >= len(esVersions) {
+		return 1
+	}
+	options := v.(*esbuild.BuildOptions)
+	options.Target = esVersions[esVersion]
+	options.Engines = nil
+	return 0
+}
+
+//export Zsb_BuildOptions_AddEngine
+func Zsb_BuildOptions_AddEngine(handle uint64, engine uint8, version *C.char, versionLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(engine) >= len(engineNames) {
+		return 1
+	}
+	options := v.(*esbuild.BuildOptions)
+	options.Engines = append(options.Engines, esbuild.Engine{
+		Name:    engineNames[engine],
+		Version: C.GoStringN(version, C.int(versionLen)),
+	})
+	return 0
+}
+
+//export Zsb_BuildOptions_Define
+func Zsb_BuildOptions_Define(handle uint64, key *C.char, keyLen C.size_t, value *C.char, valueLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	if options.Define == nil {
+		options.Define = map[string]string{}
+	}
+	options.Define[C.GoStringN(key, C.int(keyLen))] = C.GoStringN(value, C.int(valueLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_MangleProps
+func Zsb_BuildOptions_MangleProps(handle uint64, regex *C.char, regexLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	options.MangleProps = C.GoStringN(regex, C.int(regexLen))
+	// esbuild only reports the resulting cache if one was provided
+	if options.MangleCache == nil {
+		options.MangleCache = map[string]interface{}{}
+	}
+	return 0
+}
+
+//export Zsb_BuildOptions_ReserveProps
+func Zsb_BuildOptions_ReserveProps(handle uint64, regex *C.char, regexLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).ReserveProps = C.GoStringN(regex, C.int(regexLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_MangleQuoted
+func Zsb_BuildOptions_MangleQuoted(handle uint64, enable bool) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if enable {
+		v.(*esbuild.BuildOptions).MangleQuoted = esbuild.MangleQuotedTrue
+	} else {
+		v.(*esbuild.BuildOptions).MangleQuoted = esbuild.MangleQuotedFalse
+	}
+	return 0
+}
+
+//export Zsb_BuildOptions_MangleCache
+func Zsb_BuildOptions_MangleCache(handle uint64, entries *C.struct_MangleCacheEntry, entriesLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	cache := make(map[string]interface{}, int(entriesLen))
+	if entriesLen > 0 {
+		for _, entry := range unsafe.Slice(entries, int(entriesLen)) {
+			key := C.GoStringN(entry.key, C.int(entry.key_len))
+			if entry.value == nil {
+				cache[key] = false
+			} else {
+				cache[key] = C.GoStringN(entry.value, C.int(entry.value_len))
+			}
+		}
+	}
+	v.(*esbuild.BuildOptions).MangleCache = cache
+	return 0
+}
+
+//export Zsb_BuildOptions_Outfile
+func Zsb_BuildOptions_Outfile(handle uint64, path *C.char, pathLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).Outfile = C.GoStringN(path, C.int(pathLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_Outdir
+func Zsb_BuildOptions_Outdir(handle uint64, path *C.char, pathLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).Outdir = C.GoStringN(path, C.int(pathLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_Outbase
+func Zsb_BuildOptions_Outbase(handle uint64, path *C.char, pathLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).Outbase = C.GoStringN(path, C.int(pathLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_EntryNames
+func Zsb_BuildOptions_EntryNames(handle uint64, template *C.char, templateLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).EntryNames = C.GoStringN(template, C.int(templateLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_ChunkNames
+func Zsb_BuildOptions_ChunkNames(handle uint64, template *C.char, templateLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).ChunkNames = C.GoStringN(template, C.int(templateLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_AssetNames
+func Zsb_BuildOptions_AssetNames(handle uint64, template *C.char, templateLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).AssetNames = C.GoStringN(template, C.int(templateLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_PublicPath
+func Zsb_BuildOptions_PublicPath(handle uint64, path *C.char, pathLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).PublicPath = C.GoStringN(path, C.int(pathLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_OutExtension
+func Zsb_BuildOptions_OutExtension(handle uint64, ext *C.char, extLen C.size_t, replacement *C.char, replacementLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	if options.OutExtension == nil {
+		options.OutExtension = map[string]string{}
+	}
+	options.OutExtension[C.GoStringN(ext, C.int(extLen))] = C.GoStringN(replacement, C.int(replacementLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_AppendMainField
+func Zsb_BuildOptions_AppendMainField(handle uint64, value *C.char, valueLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	options.MainFields = append(options.MainFields, C.GoStringN(value, C.int(valueLen)))
+	return 0
+}
+
+//export Zsb_BuildOptions_AppendCondition
+func Zsb_BuildOptions_AppendCondition(handle uint64, value *C.char, valueLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	options.Conditions = append(options.Conditions, C.GoStringN(value, C.int(valueLen)))
+	return 0
+}
+
+//export Zsb_BuildOptions_AppendNodePath
+func Zsb_BuildOptions_AppendNodePath(handle uint64, value *C.char, valueLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	options.NodePaths = append(options.NodePaths, C.GoStringN(value, C.int(valueLen)))
+	return 0
+}
+
+//export Zsb_BuildOptions_AppendResolveExtension
+func Zsb_BuildOptions_AppendResolveExtension(handle uint64, value *C.char, valueLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	options.ResolveExtensions = append(options.ResolveExtensions, C.GoStringN(value, C.int(valueLen)))
+	return 0
+}
+
+//export Zsb_BuildOptions_AppendExternal
+func Zsb_BuildOptions_AppendExternal(handle uint64, value *C.char, valueLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	options.External = append(options.External, C.GoStringN(value, C.int(valueLen)))
+	return 0
+}
+
+//export Zsb_BuildOptions_Alias
+func Zsb_BuildOptions_Alias(handle uint64, from *C.char, fromLen C.size_t, to *C.char, toLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	options := v.(*esbuild.BuildOptions)
+	if options.Alias == nil {
+		options.Alias = map[string]string{}
+	}
+	options.Alias[C.GoStringN(from, C.int(fromLen))] = C.GoStringN(to, C.int(toLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_PreserveSymlinks
+func Zsb_BuildOptions_PreserveSymlinks(handle uint64, enable bool) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).PreserveSymlinks = enable
+	return 0
+}
+
+//export Zsb_BuildOptions_Packages
+func Zsb_BuildOptions_Packages(handle uint64, mode uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(mode) >= len(packagesModes) {
+		return 1
+	}
+	v.(*esbuild.BuildOptions).Packages = packagesModes[mode]
+	return 0
+}
+
+//export Zsb_BuildOptions_Tsconfig
+func Zsb_BuildOptions_Tsconfig(handle uint64, path *C.char, pathLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).Tsconfig = C.GoStringN(path, C.int(pathLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_TsconfigRaw
+func Zsb_BuildOptions_TsconfigRaw(handle uint64, json *C.char, jsonLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).TsconfigRaw = C.GoStringN(json, C.int(jsonLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_Jsx
+func Zsb_BuildOptions_Jsx(handle uint64, mode uint8) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	if int(mode) >= len(jsxModes) {
+		return 1
+	}
+	v.(*esbuild.BuildOptions).JSX = jsxModes[mode]
+	return 0
+}
+
+//export Zsb_BuildOptions_JsxFactory
+func Zsb_BuildOptions_JsxFactory(handle uint64, factory *C.char, factoryLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).JSXFactory = C.GoStringN(factory, C.int(factoryLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_JsxFragment
+func Zsb_BuildOptions_JsxFragment(handle uint64, fragment *C.char, fragmentLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).JSXFragment = C.GoStringN(fragment, C.int(fragmentLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_JsxImportSource
+func Zsb_BuildOptions_JsxImportSource(handle uint64, source *C.char, sourceLen C.size_t) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).JSXImportSource = C.GoStringN(source, C.int(sourceLen))
+	return 0
+}
+
+//export Zsb_BuildOptions_JsxDev
+func Zsb_BuildOptions_JsxDev(handle uint64, enable bool) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).JSXDev = enable
+	return 0
+}
+
+//export Zsb_BuildOptions_JsxSideEffects
+func Zsb_BuildOptions_JsxSideEffects(handle uint64, enable bool) uint16 {
+	v, ok := buildOptions.Load(handle)
+	if !ok {
+		panic("bad build options handle")
+	}
+	v.(*esbuild.BuildOptions).JSXSideEffects = enable
+	return 0
+}
+
 //export Zsb_BuildOptions_AppendEntryPoint
 func Zsb_BuildOptions_AppendEntryPoint(handle uint64, input *C.char, inputLen C.size_t, output *C.char, outputLen C.size_t) uint16 {
 	v, ok := buildOptions.Load(handle)
@@ -47,20 +610,21 @@ func Zsb_BuildOptions_AppendEntryPoint(handle uint64, input *C.char, inputLen C.
 
 //export Zsb_BuildOptions_AddPlugin
 func Zsb_BuildOptions_AddPlugin(handle uint64, pluginHandle uint64) uint64 {
-	v, ok := buildOptions.Load(handle)
-	if !ok {
+	if _, ok := buildOptions.Load(handle); !ok {
 		panic("bad build options handle")
 	}
-	options := v.(*esbuild.BuildOptions)
 	plugin, ok := pluginDescriptorHandles.Load(pluginHandle)
 	if !ok {
 		panic("bad plugin handle")
 	}
-	options.Plugins = append(options.Plugins, plugin.(PluginDescriptor).plugin)
+	// the esbuild plugins are only created along with each build or context, see `instantiateBuildOptions`
+	optionsPlugins.Store(handle, append(optionsPluginDescriptors(handle), plugin.(*PluginDescriptor)))
 	return 0
 }
 
 //export Zsb_BuildOptions_Destroy
 func Zsb_BuildOptions_Destroy(handle uint64) {
 	buildOptions.Delete(handle)
+	logForwarders.Delete(handle)
+	optionsPlugins.Delete(handle)
 }
@@ -3,43 +3,155 @@ package main
 // #include "common.h"
 import "C"
 import (
+	"runtime"
 	"sync"
 	"sync/atomic"
+	"time"
 	"unsafe"
 
 	esbuild "github.com/evanw/esbuild/pkg/api"
 )
 
 type PluginDescriptor struct {
+	name       string
 	callback   C.PluginBuildCallback
 	data       *C.void
 	destructor C.PluginDestructor
 	destroyed  bool
-	plugin     esbuild.Plugin
 }
 
 type PluginBuilder struct {
 	descriptor *PluginDescriptor
 	build      esbuild.PluginBuild
+	data       *pluginDataStore
+	// where the callbacks registered for this build or context record their timings
+	profile    *pluginProfile
+	// unix timestamp (in nanoseconds) of when the current build started
+	started    *atomic.Int64
+}
+
+// pluginData is what esbuild carries around as `PluginData` for zsbuild plugins: a handle to a Rust
+// `Box<dyn Any + Send + Sync>`.
+type pluginData struct {
+	ptr        unsafe.Pointer
+	destructor C.PluginDestructor
+}
+
+// pluginDataStore keeps the plugin data returned by a plugin's callbacks alive until the build ends.
+type pluginDataStore struct {
+	mu    sync.Mutex
+	items []*pluginData
+}
+
+func (s *pluginDataStore) wrap(ptr unsafe.Pointer, destructor C.PluginDestructor) interface{} {
+	if ptr == nil {
+		return nil
+	}
+	data := &pluginData{ptr: ptr, destructor: destructor}
+	s.mu.Lock()
+	s.items = append(s.items, data)
+	s.mu.Unlock()
+	return data
+}
+
+func (s *pluginDataStore) release() {
+	s.mu.Lock()
+	items := s.items
+	s.items = nil
+	s.mu.Unlock()
+	for _, data := range items {
+		C.Zsb_PluginDestructor_Dispatch(data.destructor, data.ptr)
+	}
+}
+
+func unwrapPluginData(v interface{}) unsafe.Pointer {
+	// data set by other (non-zsbuild) plugins is invisible to Rust
+	if data, ok := v.(*pluginData); ok {
+		return data.ptr
+	}
+	return nil
+}
+
+// Indexed by the discriminant of the Rust `ResolveKind` enum.
+var resolveKinds = [...]esbuild.ResolveKind{
+	esbuild.ResolveNone,
+	esbuild.ResolveEntryPoint,
+	esbuild.ResolveJSImportStatement,
+	esbuild.ResolveJSRequireCall,
+	esbuild.ResolveJSDynamicImport,
+	esbuild.ResolveJSRequireResolve,
+	esbuild.ResolveCSSImportRule,
+	esbuild.ResolveCSSComposesFrom,
+	esbuild.ResolveCSSURLToken,
+}
+
+func resolveKindIndex(kind esbuild.ResolveKind) uint8 {
+	for i, k := range resolveKinds {
+		if k == kind {
+			return uint8(i)
+		}
+	}
+	return 0
+}
+
+func pinnedStringN(str string, pinner *runtime.Pinner) (*C.char, C.size_t) {
+	if len(str) == 0 {
+		return nil, 0
+	}
+	return pinnedString(str, pinner), C.size_t(len(str))
 }
 
 var (
-	// map[uint64]PluginDescriptor
+	// map[uint64]*PluginDescriptor
 	pluginDescriptorHandles = sync.Map{}
 	pluginDescriptorAcc     atomic.Uint64
 	// map[uint64]PluginBuilder
 	pluginBuildHandles = sync.Map{}
 	pluginBuildAcc     atomic.Uint64
+	// map[uint64][]*PluginDescriptor, keyed by build options handle
+	optionsPlugins = sync.Map{}
 )
 
-func (b *PluginDescriptor) Callback(build esbuild.PluginBuild) {
+func optionsPluginDescriptors(optionsHandle uint64) []*PluginDescriptor {
+	if v, ok := optionsPlugins.Load(optionsHandle); ok {
+		return v.([]*PluginDescriptor)
+	}
+	return nil
+}
+
+// plugin returns the esbuild plugin for a single build or context, whose callbacks record their timings into `profile`.
+func (b *PluginDescriptor) plugin(profile *pluginProfile) esbuild.Plugin {
+	return esbuild.Plugin{
+		Name: b.name,
+		Setup: func(build esbuild.PluginBuild) {
+			b.Callback(build, profile)
+		},
+	}
+}
+
+func (b *PluginDescriptor) Callback(build esbuild.PluginBuild, profile *pluginProfile) {
 	buildHandle := pluginBuildAcc.Add(1)
 
-	pluginBuildHandles.Store(buildHandle, PluginBuilder{descriptor: b, build: build})
+	data := &pluginDataStore{}
+	started := new(atomic.Int64)
+	pluginBuildHandles.Store(buildHandle, PluginBuilder{descriptor: b, build: build, data: data, profile: profile, started: started})
+
+	// so `on_end` callbacks can see how long the build took, even for rebuilds triggered by watch mode
+	build.OnStart(func() (esbuild.OnStartResult, error) {
+		started.Store(time.Now().UnixNano())
+		return esbuild.OnStartResult{}, nil
+	})
+
+	// plugin data only flows from resolve/load callbacks into other callbacks of the same build
+	build.OnEnd(func(result *esbuild.BuildResult) (esbuild.OnEndResult, error) {
+		data.release()
+		return esbuild.OnEndResult{}, nil
+	})
 
 	C.Zsb_PluginBuildCallback_Dispatch(b.callback, C.uint64_t(buildHandle), unsafe.Pointer(b.data))
 
 	build.OnDispose(func() {
+		data.release()
 		if !b.destroyed {
 			C.Zsb_PluginDestructor_Dispatch(b.destructor, unsafe.Pointer(b.data))
 			b.destroyed = true
@@ -58,29 +170,15 @@ func Zsb_PluginBuilder_OnStart(handle uint64, cb C.PluginCallbackOnStart, data *
 	build := v.(PluginBuilder)
 	build.build.OnStart(func() (esbuild.OnStartResult, error) {
 		if !build.descriptor.destroyed {
+			defer build.profile.record(hookOnStart, "", time.Now())
 			cRes := C.Zsb_PluginCallbackOnStart_Dispatch(cb, unsafe.Pointer(data))
 			if cRes == nil {
 				return esbuild.OnStartResult{}, nil
 			}
 
-			res := esbuild.OnStartResult{}
-			if cRes.errors_len > 0 {
-				serializedErrors := unsafe.Slice(cRes.errors, cRes.errors_len)
-				errorsLen := int(cRes.errors_len)
-				errors := make([]esbuild.Message, errorsLen)
-				for i := 0; i < errorsLen; i++ {
-					errors[i] = deserializeMessage(&serializedErrors[i])
-				}
-				res.Errors = errors
-			}
-			if cRes.warnings_len > 0 {
-				serializedWarnings := unsafe.Slice(cRes.warnings, cRes.warnings_len)
-				warningsLen := int(cRes.warnings_len)
-				warnings := make([]esbuild.Message, warningsLen)
-				for i := 0; i < warningsLen; i++ {
-					warnings[i] = deserializeMessage(&serializedWarnings[i])
-				}
-				res.Warnings = warnings
+			res := esbuild.OnStartResult{
+				Errors:   deserializeMessages(cRes.errors, cRes.errors_len),
+				Warnings: deserializeMessages(cRes.warnings, cRes.warnings_len),
 			}
 			C.Zsb_PluginOnStartResult_Destroy(cRes)
 			return res, nil
@@ -89,11 +187,138 @@ func Zsb_PluginBuilder_OnStart(handle uint64, cb C.PluginCallbackOnStart, data *
 	})
 }
 
+//export Zsb_PluginBuilder_OnResolve
+func Zsb_PluginBuilder_OnResolve(handle uint64, filter *C.char, filterLen C.size_t, namespace *C.char, namespaceLen C.size_t, cb C.PluginCallbackOnResolve, data *C.void) {
+	v, ok := pluginBuildHandles.Load(handle)
+	if !ok {
+		panic("bad plugin build handle")
+	}
+	build := v.(PluginBuilder)
+	options := esbuild.OnResolveOptions{
+		Filter:    C.GoStringN(filter, C.int(filterLen)),
+		Namespace: C.GoStringN(namespace, C.int(namespaceLen)),
+	}
+	build.build.OnResolve(options, func(args esbuild.OnResolveArgs) (esbuild.OnResolveResult, error) {
+		if build.descriptor.destroyed {
+			return esbuild.OnResolveResult{}, nil
+		}
+		defer build.profile.record(hookOnResolve, options.Filter, time.Now())
+
+		pinner := new(runtime.Pinner)
+		defer pinner.Unpin()
+		cArgs := alloc(C.struct_PluginOnResolveArgs{})
+		defer free(cArgs)
+		cArgs.path, cArgs.path_len = pinnedStringN(args.Path, pinner)
+		cArgs.importer, cArgs.importer_len = pinnedStringN(args.Importer, pinner)
+		cArgs.namespace_, cArgs.namespace_len = pinnedStringN(args.Namespace, pinner)
+		cArgs.resolve_dir, cArgs.resolve_dir_len = pinnedStringN(args.ResolveDir, pinner)
+		cArgs.kind = C.uint8_t(resolveKindIndex(args.Kind))
+		cArgs.plugin_data = unwrapPluginData(args.PluginData)
+
+		cRes := C.Zsb_PluginCallbackOnResolve_Dispatch(cb, cArgs, unsafe.Pointer(data))
+		if cRes == nil {
+			return esbuild.OnResolveResult{}, nil
+		}
+		res := esbuild.OnResolveResult{
+			Path:       C.GoStringN(cRes.path, C.int(cRes.path_len)),
+			Namespace:  C.GoStringN(cRes.namespace_, C.int(cRes.namespace_len)),
+			Suffix:     C.GoStringN(cRes.suffix, C.int(cRes.suffix_len)),
+			External:   bool(cRes.external),
+			PluginData: build.data.wrap(cRes.plugin_data, cRes.plugin_data_destructor),
+			Errors:     deserializeMessages(cRes.errors, cRes.errors_len),
+			Warnings:   deserializeMessages(cRes.warnings, cRes.warnings_len),
+		}
+		if cRes.no_side_effects {
+			res.SideEffects = esbuild.SideEffectsFalse
+		}
+		C.Zsb_PluginOnResolveResult_Destroy(cRes)
+		return res, nil
+	})
+}
+
+//export Zsb_PluginBuilder_OnLoad
+func Zsb_PluginBuilder_OnLoad(handle uint64, filter *C.char, filterLen C.size_t, namespace *C.char, namespaceLen C.size_t, cb C.PluginCallbackOnLoad, data *C.void) {
+	v, ok := pluginBuildHandles.Load(handle)
+	if !ok {
+		panic("bad plugin build handle")
+	}
+	build := v.(PluginBuilder)
+	options := esbuild.OnLoadOptions{
+		Filter:    C.GoStringN(filter, C.int(filterLen)),
+		Namespace: C.GoStringN(namespace, C.int(namespaceLen)),
+	}
+	build.build.OnLoad(options, func(args esbuild.OnLoadArgs) (esbuild.OnLoadResult, error) {
+		if build.descriptor.destroyed {
+			return esbuild.OnLoadResult{}, nil
+		}
+		defer build.profile.record(hookOnLoad, options.Filter, time.Now())
+
+		pinner := new(runtime.Pinner)
+		defer pinner.Unpin()
+		cArgs := alloc(C.struct_PluginOnLoadArgs{})
+		defer free(cArgs)
+		cArgs.path, cArgs.path_len = pinnedStringN(args.Path, pinner)
+		cArgs.namespace_, cArgs.namespace_len = pinnedStringN(args.Namespace, pinner)
+		cArgs.suffix, cArgs.suffix_len = pinnedStringN(args.Suffix, pinner)
+		cArgs.plugin_data = unwrapPluginData(args.PluginData)
+
+		cRes := C.Zsb_PluginCallbackOnLoad_Dispatch(cb, cArgs, unsafe.Pointer(data))
+		if cRes == nil {
+			return esbuild.OnLoadResult{}, nil
+		}
+		res := esbuild.OnLoadResult{
+			ResolveDir: C.GoStringN(cRes.resolve_dir, C.int(cRes.resolve_dir_len)),
+			PluginData: build.data.wrap(cRes.plugin_data, cRes.plugin_data_destructor),
+			Errors:     deserializeMessages(cRes.errors, cRes.errors_len),
+			Warnings:   deserializeMessages(cRes.warnings, cRes.warnings_len),
+		}
+		if cRes.has_contents {
+			contents := C.GoStringN(cRes.contents, C.int(cRes.contents_len))
+			res.Contents = &contents
+		}
+		if cRes.has_loader && int(cRes.loader) < len(loaders) {
+			res.Loader = loaders[cRes.loader]
+		}
+		C.Zsb_PluginOnLoadResult_Destroy(cRes)
+		return res, nil
+	})
+}
+
+//export Zsb_PluginBuilder_OnEnd
+func Zsb_PluginBuilder_OnEnd(handle uint64, cb C.PluginCallbackOnEnd, data *C.void) {
+	v, ok := pluginBuildHandles.Load(handle)
+	if !ok {
+		panic("bad plugin build handle")
+	}
+	build := v.(PluginBuilder)
+	build.build.OnEnd(func(result *esbuild.BuildResult) (esbuild.OnEndResult, error) {
+		if build.descriptor.destroyed {
+			return esbuild.OnEndResult{}, nil
+		}
+		defer build.profile.record(hookOnEnd, "", time.Now())
+
+		pinner := new(runtime.Pinner)
+		cResult := serializeBuildResult(result, pinner)
+		cResult.duration_ns = C.int64_t(time.Now().UnixNano() - build.started.Load())
+		buildResultPinners.Store(cResult, pinner)
+		cRes := C.Zsb_PluginCallbackOnEnd_Dispatch(cb, cResult, unsafe.Pointer(data))
+		Zsb_BuildResult_Destroy(cResult)
+		if cRes == nil {
+			return esbuild.OnEndResult{}, nil
+		}
+		res := esbuild.OnEndResult{
+			Errors:   deserializeMessages(cRes.errors, cRes.errors_len),
+			Warnings: deserializeMessages(cRes.warnings, cRes.warnings_len),
+		}
+		C.Zsb_PluginOnEndResult_Destroy(cRes)
+		return res, nil
+	})
+}
+
 //export Zsb_Plugin_Create
 func Zsb_Plugin_Create(name *C.char, nameLen C.size_t, callback C.PluginBuildCallback, data *C.void, destructor C.PluginDestructor) uint64 {
 	outHandle := pluginDescriptorAcc.Add(1)
-	builder := PluginDescriptor{callback: callback, data: data, destructor: destructor, destroyed: false}
-	builder.plugin = esbuild.Plugin{Name: C.GoStringN(name, C.int(nameLen)), Setup: builder.Callback}
+	builder := &PluginDescriptor{name: C.GoStringN(name, C.int(nameLen)), callback: callback, data: data, destructor: destructor, destroyed: false}
 	pluginDescriptorHandles.Store(outHandle, builder)
 	return outHandle
 }
@@ -0,0 +1,85 @@
+package main
+
+// #include "common.h"
+import "C"
+import (
+	"runtime"
+	"sync"
+	"time"
+)
+
+// Indexed by the discriminant of the Rust `PluginHook` enum.
+const (
+	hookOnStart uint8 = iota
+	hookOnResolve
+	hookOnLoad
+	hookOnEnd
+)
+
+type hookTiming struct {
+	hook   uint8
+	filter string
+	calls  uint64
+	total  time.Duration
+	max    time.Duration
+}
+
+// pluginProfile accumulates how long a plugin's callbacks took in one build or context until it is drained into a build
+// result. Each build or context gets profiles of its own, so builds sharing the same options don't mix up their timings.
+type pluginProfile struct {
+	name    string
+	mu      sync.Mutex
+	timings []hookTiming
+}
+
+// record is meant to be deferred, i.e. `defer profile.record(hookOnLoad, filter, time.Now())`.
+func (p *pluginProfile) record(hook uint8, filter string, start time.Time) {
+	elapsed := time.Since(start)
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	for i := range p.timings {
+		timing := &p.timings[i]
+		if timing.hook == hook && timing.filter == filter {
+			timing.calls++
+			timing.total += elapsed
+			if elapsed > timing.max {
+				timing.max = elapsed
+			}
+			return
+		}
+	}
+	p.timings = append(p.timings, hookTiming{hook: hook, filter: filter, calls: 1, total: elapsed, max: elapsed})
+}
+
+func (p *pluginProfile) take() []hookTiming {
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	timings := p.timings
+	p.timings = nil
+	return timings
+}
+
+// serializePluginTimings drains the profiles of the plugins used by a build into its result.
+func serializePluginTimings(cRes *C.struct_BuildResult, profiles []*pluginProfile, pinner *runtime.Pinner) {
+	var timings []C.struct_PluginTiming
+	for _, profile := range profiles {
+		for _, timing := range profile.take() {
+			cTiming := C.struct_PluginTiming{
+				hook:     C.uint8_t(timing.hook),
+				calls:    C.uint64_t(timing.calls),
+				total_ns: C.uint64_t(timing.total.Nanoseconds()),
+				max_ns:   C.uint64_t(timing.max.Nanoseconds()),
+			}
+			cTiming.plugin, cTiming.plugin_len = pinnedStringN(profile.name, pinner)
+			cTiming.filter, cTiming.filter_len = pinnedStringN(timing.filter, pinner)
+			timings = append(timings, cTiming)
+		}
+	}
+	if len(timings) == 0 {
+		return
+	}
+	cRes.plugin_timings_len = C.size_t(len(timings))
+	timingsSlice, cTimings := allocSlice(cRes.plugin_timings_len, C.struct_PluginTiming{})
+	copy(timingsSlice, timings)
+	cRes.plugin_timings = cTimings
+}
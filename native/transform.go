@@ -0,0 +1,117 @@
+package main
+
+// #include "common.h"
+import "C"
+import (
+	"runtime"
+	"sync"
+	"unsafe"
+
+	esbuild "github.com/evanw/esbuild/pkg/api"
+)
+
+var transformResultPinners = sync.Map{} // map[*C.struct_TransformResult]*runtime.Pinner
+
+func deserializeTransformOptions(options *C.struct_TransformOptions) (esbuild.TransformOptions, bool) {
+	if int(options.loader) >= len(loaders) || int(options.format) >= len(formats) || int(options.sourcemap) >= len(sourceMaps) || int(options.jsx) >= len(jsxModes) {
+		return esbuild.TransformOptions{}, false
+	}
+	deserialized := esbuild.TransformOptions{
+		LogLevel:          esbuild.LogLevelSilent,
+		Loader:            loaders[options.loader],
+		Format:            formats[options.format],
+		Sourcemap:         sourceMaps[options.sourcemap],
+		MinifyWhitespace:  bool(options.minify_whitespace),
+		MinifyIdentifiers: bool(options.minify_identifiers),
+		MinifySyntax:      bool(options.minify_syntax),
+		JSX:               jsxModes[options.jsx],
+		JSXDev:            bool(options.jsx_dev),
+		JSXSideEffects:    bool(options.jsx_side_effects),
+	}
+	if options.sourcefile_len > 0 {
+		deserialized.Sourcefile = C.GoStringN(options.sourcefile, C.int(options.sourcefile_len))
+	}
+	if options.tsconfig_raw_len > 0 {
+		deserialized.TsconfigRaw = C.GoStringN(options.tsconfig_raw, C.int(options.tsconfig_raw_len))
+	}
+	if options.jsx_factory_len > 0 {
+		deserialized.JSXFactory = C.GoStringN(options.jsx_factory, C.int(options.jsx_factory_len))
+	}
+	if options.jsx_fragment_len > 0 {
+		deserialized.JSXFragment = C.GoStringN(options.jsx_fragment, C.int(options.jsx_fragment_len))
+	}
+	if options.jsx_import_source_len > 0 {
+		deserialized.JSXImportSource = C.GoStringN(options.jsx_import_source, C.int(options.jsx_import_source_len))
+	}
+	return deserialized, true
+}
+
+//export Zsb_Transform
+func Zsb_Transform(code *C.char, codeLen C.size_t, options *C.struct_TransformOptions) *C.struct_TransformResult {
+	goOptions, ok := deserializeTransformOptions(options)
+	if !ok {
+		return nil
+	}
+	goRes := esbuild.Transform(C.GoStringN(code, C.int(codeLen)), goOptions)
+
+	pinner := new(runtime.Pinner)
+	cRes := alloc(C.struct_TransformResult{})
+	if len(goRes.Code) > 0 {
+		cRes.code = (*C.char)(pinnedSlice(goRes.Code, pinner))
+		cRes.code_len = C.size_t(len(goRes.Code))
+	}
+	if len(goRes.Map) > 0 {
+		cRes.source_map = (*C.char)(pinnedSlice(goRes.Map, pinner))
+		cRes.source_map_len = C.size_t(len(goRes.Map))
+	}
+
+	numErrors := len(goRes.Errors)
+	cRes.errors_len = C.size_t(numErrors)
+	if cRes.errors_len > 0 {
+		errorsSlice, errors := allocSlice(cRes.errors_len, C.struct_Message{})
+		for i, msg := range goRes.Errors {
+			errorsSlice[i] = serializeMessage(&msg, pinner)
+		}
+		cRes.errors = errors
+	}
+
+	numWarnings := len(goRes.Warnings)
+	cRes.warnings_len = C.size_t(numWarnings)
+	if cRes.warnings_len > 0 {
+		warningsSlice, warnings := allocSlice(cRes.warnings_len, C.struct_Message{})
+		for i, msg := range goRes.Warnings {
+			warningsSlice[i] = serializeMessage(&msg, pinner)
+		}
+		cRes.warnings = warnings
+	}
+
+	transformResultPinners.Store(cRes, pinner)
+	return cRes
+}
+
+//export Zsb_TransformResult_Destroy
+func Zsb_TransformResult_Destroy(c *C.struct_TransformResult) {
+	numErrors := int(c.errors_len)
+	if numErrors > 0 {
+		errors := unsafe.Slice(c.errors, numErrors)
+		for _, err := range errors {
+			destroyMessage(&err)
+		}
+		free(c.errors)
+	}
+	numWarnings := int(c.warnings_len)
+	if numWarnings > 0 {
+		warnings := unsafe.Slice(c.warnings, numWarnings)
+		for _, warning := range warnings {
+			destroyMessage(&warning)
+		}
+		free(c.warnings)
+	}
+	free(c)
+
+	pinner, ok := transformResultPinners.LoadAndDelete(c)
+	if !ok {
+		panic("bad transform result pinner addr")
+	}
+	pinner.(*runtime.Pinner).Unpin()
+}
@@ -0,0 +1,636 @@
+use std::fmt::{self, Display};
+
+use zsbuild::{
+	Alias, BuildOptions, ExternalPattern, Format, Jsx, LegalComments, Loader, LogLevel, Packages, PathTemplate, Platform, ResolveExtension, ServeOptions,
+	SourceMap, Target
+};
+
+#[derive(Debug, Clone)]
+pub struct ArgsError(String);
+
+impl Display for ArgsError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+	Auto,
+	Always,
+	Never
+}
+
+#[derive(Debug)]
+pub enum Mode {
+	Build,
+	Watch { forever: bool },
+	Serve(ServeOptions)
+}
+
+#[derive(Debug)]
+pub enum Command {
+	Help,
+	Version,
+	Run(Box<Args>)
+}
+
+#[derive(Debug)]
+pub struct Args {
+	pub entry_points: Vec<(String, Option<String>)>,
+	pub bundle: bool,
+	pub splitting: bool,
+	pub minify_whitespace: bool,
+	pub minify_identifiers: bool,
+	pub minify_syntax: bool,
+	pub format: Option<Format>,
+	pub platform: Option<Platform>,
+	pub target: Option<Vec<Target>>,
+	pub loaders: Vec<(String, Loader)>,
+	pub defines: Vec<(String, String)>,
+	pub main_fields: Vec<String>,
+	pub conditions: Vec<String>,
+	pub resolve_extensions: Vec<ResolveExtension>,
+	pub preserve_symlinks: bool,
+	pub packages: Option<Packages>,
+	pub aliases: Vec<Alias>,
+	pub externals: Vec<ExternalPattern>,
+	pub sourcemap: Option<SourceMap>,
+	pub legal_comments: Option<LegalComments>,
+	pub jsx: Option<Jsx>,
+	pub jsx_factory: Option<String>,
+	pub jsx_fragment: Option<String>,
+	pub jsx_import_source: Option<String>,
+	pub jsx_dev: bool,
+	pub jsx_side_effects: bool,
+	pub outfile: Option<String>,
+	pub outdir: Option<String>,
+	pub outbase: Option<String>,
+	pub entry_names: Option<PathTemplate>,
+	pub chunk_names: Option<PathTemplate>,
+	pub asset_names: Option<PathTemplate>,
+	pub public_path: Option<String>,
+	pub out_extensions: Vec<(String, String)>,
+	pub tsconfig: Option<String>,
+	pub metafile: Option<String>,
+	pub mangle_props: Option<String>,
+	pub reserve_props: Option<String>,
+	pub mangle_quoted: bool,
+	pub mangle_cache: Option<String>,
+	pub color: Color,
+	pub log_level: LogLevel,
+	pub log_limit: usize,
+	pub log_overrides: Vec<(String, LogLevel)>,
+	pub mode: Mode
+}
+
+impl Args {
+	pub fn to_build_options(&self) -> BuildOptions {
+		let mut options = BuildOptions::new()
+			.bundle(self.bundle)
+			.splitting(self.splitting)
+			.minify_each(self.minify_whitespace, self.minify_identifiers, self.minify_syntax)
+			.metafile(self.metafile.is_some())
+			// esbuild's serve mode keeps outputs in memory; everything else writes to disk like the esbuild CLI.
+			.write(!matches!(self.mode, Mode::Serve(_)));
+		for (id, level) in &self.log_overrides {
+			options = options.log_override(id, *level);
+		}
+		if !matches!(self.mode, Mode::Build) {
+			// rebuilds happen inside esbuild, so let it report them itself.
+			options = options.log_level(self.log_level).log_limit(self.log_limit.try_into().unwrap_or(u32::MAX));
+		}
+		for (in_path, out_path) in &self.entry_points {
+			options = options.entry_point(in_path, out_path.as_deref().unwrap_or(""));
+		}
+		if let Some(format) = self.format {
+			options = options.format(format);
+		}
+		if let Some(platform) = self.platform {
+			options = options.platform(platform);
+		}
+		if let Some(target) = &self.target {
+			options = options.target(target.iter().copied());
+		}
+		for (ext, loader) in &self.loaders {
+			options = options.loader(ext, *loader);
+		}
+		for (key, expression) in &self.defines {
+			options = options.define(key, expression);
+		}
+		options = options
+			.main_fields(&self.main_fields)
+			.conditions(&self.conditions)
+			.resolve_extensions(self.resolve_extensions.iter().cloned());
+		if self.preserve_symlinks {
+			options = options.preserve_symlinks(true);
+		}
+		if let Some(packages) = self.packages {
+			options = options.packages(packages);
+		}
+		for alias in &self.aliases {
+			options = options.alias(alias.clone());
+		}
+		for pattern in &self.externals {
+			options = options.external(pattern.clone());
+		}
+		if let Some(sourcemap) = self.sourcemap {
+			options = options.sourcemap(sourcemap);
+		}
+		if let Some(legal_comments) = self.legal_comments {
+			options = options.legal_comments(legal_comments);
+		}
+		if let Some(jsx) = self.jsx {
+			options = options.jsx(jsx);
+		}
+		if let Some(factory) = &self.jsx_factory {
+			options = options.jsx_factory(factory);
+		}
+		if let Some(fragment) = &self.jsx_fragment {
+			options = options.jsx_fragment(fragment);
+		}
+		if let Some(source) = &self.jsx_import_source {
+			options = options.jsx_import_source(source);
+		}
+		if self.jsx_dev {
+			options = options.jsx_dev(true);
+		}
+		if self.jsx_side_effects {
+			options = options.jsx_side_effects(true);
+		}
+		if let Some(outfile) = &self.outfile {
+			options = options.outfile(outfile);
+		}
+		if let Some(outdir) = &self.outdir {
+			options = options.outdir(outdir);
+		}
+		if let Some(outbase) = &self.outbase {
+			options = options.outbase(outbase);
+		}
+		if let Some(template) = &self.entry_names {
+			options = options.entry_names(template.clone());
+		}
+		if let Some(template) = &self.chunk_names {
+			options = options.chunk_names(template.clone());
+		}
+		if let Some(template) = &self.asset_names {
+			options = options.asset_names(template.clone());
+		}
+		if let Some(public_path) = &self.public_path {
+			options = options.public_path(public_path);
+		}
+		for (ext, replacement) in &self.out_extensions {
+			options = options.out_extension(ext, replacement);
+		}
+		if let Some(tsconfig) = &self.tsconfig {
+			options = options.tsconfig(tsconfig);
+		}
+		if let Some(regex) = &self.mangle_props {
+			options = options.mangle_props(regex);
+		}
+		if let Some(regex) = &self.reserve_props {
+			options = options.reserve_props(regex);
+		}
+		if self.mangle_quoted {
+			options = options.mangle_quoted(true);
+		}
+		options
+	}
+}
+
+fn parse_format(value: &str) -> Result<Format, ArgsError> {
+	Format::from_name(value).ok_or_else(|| ArgsError(format!("Invalid value {value:?} in \"--format={value}\" (valid: iife, cjs, esm)")))
+}
+
+fn parse_platform(value: &str) -> Result<Platform, ArgsError> {
+	Platform::from_name(value).ok_or_else(|| ArgsError(format!("Invalid value {value:?} in \"--platform={value}\" (valid: browser, node, neutral)")))
+}
+
+fn parse_sourcemap(value: Option<&str>) -> Result<SourceMap, ArgsError> {
+	match value {
+		None | Some("linked") => Ok(SourceMap::Linked),
+		Some("inline") => Ok(SourceMap::Inline),
+		Some("external") => Ok(SourceMap::External),
+		Some("both") => Ok(SourceMap::InlineAndExternal),
+		Some(value) => Err(ArgsError(format!("Invalid value {value:?} in \"--sourcemap={value}\" (valid: linked, inline, external, both)")))
+	}
+}
+
+fn parse_path_template(value: &str) -> Result<PathTemplate, ArgsError> {
+	value.parse().map_err(|e| ArgsError(format!("{e}")))
+}
+
+fn parse_jsx(value: &str) -> Result<Jsx, ArgsError> {
+	Jsx::from_name(value).ok_or_else(|| ArgsError(format!("Invalid value {value:?} in \"--jsx={value}\" (valid: transform, preserve, automatic)")))
+}
+
+fn parse_legal_comments(value: &str) -> Result<LegalComments, ArgsError> {
+	LegalComments::from_name(value)
+		.ok_or_else(|| ArgsError(format!("Invalid value {value:?} in \"--legal-comments={value}\" (valid: none, inline, eof, linked, external)")))
+}
+
+fn parse_log_level(flag: &str, value: &str) -> Result<LogLevel, ArgsError> {
+	match value {
+		"verbose" => Ok(LogLevel::Verbose),
+		"debug" => Ok(LogLevel::Debug),
+		"info" => Ok(LogLevel::Info),
+		"warning" => Ok(LogLevel::Warning),
+		"error" => Ok(LogLevel::Error),
+		"silent" => Ok(LogLevel::Silent),
+		_ => Err(ArgsError(format!("Invalid value {value:?} in \"{flag}\" (valid: verbose, debug, info, warning, error, silent)")))
+	}
+}
+
+fn parse_serve(value: Option<&str>) -> Result<ServeOptions, ArgsError> {
+	let options = ServeOptions::new();
+	let Some(value) = value else {
+		return Ok(options);
+	};
+	let (host, port) = match value.rsplit_once(':') {
+		Some((host, port)) => (Some(host), port),
+		None => (None, value)
+	};
+	let port = port
+		.parse::<u16>()
+		.map_err(|_| ArgsError(format!("Invalid port {port:?} in \"--serve={value}\"")))?;
+	let options = options.with_port(port);
+	Ok(match host {
+		Some(host) if !host.is_empty() => options.with_host(host),
+		_ => options
+	})
+}
+
+fn require_value<'a>(flag: &str, value: Option<&'a str>) -> Result<&'a str, ArgsError> {
+	match value {
+		Some(value) if !value.is_empty() => Ok(value),
+		_ => Err(ArgsError(format!("Missing value for \"{flag}=...\"")))
+	}
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, ArgsError> {
+	let mut parsed = Args {
+		entry_points: Vec::new(),
+		bundle: false,
+		splitting: false,
+		minify_whitespace: false,
+		minify_identifiers: false,
+		minify_syntax: false,
+		format: None,
+		platform: None,
+		target: None,
+		loaders: Vec::new(),
+		defines: Vec::new(),
+		main_fields: Vec::new(),
+		conditions: Vec::new(),
+		resolve_extensions: Vec::new(),
+		preserve_symlinks: false,
+		packages: None,
+		aliases: Vec::new(),
+		externals: Vec::new(),
+		sourcemap: None,
+		legal_comments: None,
+		jsx: None,
+		jsx_factory: None,
+		jsx_fragment: None,
+		jsx_import_source: None,
+		jsx_dev: false,
+		jsx_side_effects: false,
+		outfile: None,
+		outdir: None,
+		outbase: None,
+		entry_names: None,
+		chunk_names: None,
+		asset_names: None,
+		public_path: None,
+		out_extensions: Vec::new(),
+		tsconfig: None,
+		metafile: None,
+		mangle_props: None,
+		reserve_props: None,
+		mangle_quoted: false,
+		mangle_cache: None,
+		color: Color::Auto,
+		log_level: LogLevel::Info,
+		log_limit: 6,
+		log_overrides: Vec::new(),
+		mode: Mode::Build
+	};
+	let mut serve = None;
+	let mut servedir = None;
+	let mut tls: (Option<String>, Option<String>) = (None, None);
+	let mut fallback = None;
+
+	for arg in args {
+		if !arg.starts_with('-') {
+			// esbuild accepts `out=in` to name the output of an individual entry point.
+			match arg.split_once('=') {
+				Some((out_path, in_path)) if !out_path.is_empty() && !in_path.is_empty() => {
+					parsed.entry_points.push((in_path.to_string(), Some(out_path.to_string())))
+				}
+				_ => parsed.entry_points.push((arg, None))
+			}
+			continue;
+		}
+
+		let (flag, value) = match arg.split_once('=') {
+			Some((flag, value)) => (flag, Some(value)),
+			None => (arg.as_str(), None)
+		};
+		match flag {
+			"-h" | "--help" => return Ok(Command::Help),
+			"--version" => return Ok(Command::Version),
+			"--bundle" => parsed.bundle = true,
+			"--splitting" => parsed.splitting = true,
+			"--minify" => {
+				parsed.minify_whitespace = true;
+				parsed.minify_identifiers = true;
+				parsed.minify_syntax = true;
+			}
+			"--minify-whitespace" => parsed.minify_whitespace = true,
+			"--minify-identifiers" => parsed.minify_identifiers = true,
+			"--minify-syntax" => parsed.minify_syntax = true,
+			"--format" => parsed.format = Some(parse_format(require_value(flag, value)?)?),
+			"--platform" => parsed.platform = Some(parse_platform(require_value(flag, value)?)?),
+			"--target" => {
+				let value = require_value(flag, value)?;
+				parsed.target = Some(Target::parse_list(value).map_err(|e| ArgsError(format!("Invalid value {value:?} in \"--target={value}\": {e}")))?);
+			}
+			_ if flag.starts_with("--loader:") => {
+				let ext = &flag["--loader:".len()..];
+				let value = require_value(flag, value)?;
+				let loader = Loader::from_name(value).ok_or_else(|| ArgsError(format!("Invalid loader value: {value:?}")))?;
+				parsed.loaders.push((ext.to_string(), loader));
+			}
+			_ if flag.starts_with("--define:") => {
+				let key = &flag["--define:".len()..];
+				parsed.defines.push((key.to_string(), require_value(flag, value)?.to_string()));
+			}
+			"--main-fields" => parsed.main_fields = require_value(flag, value)?.split(',').map(str::to_string).collect(),
+			"--conditions" => {
+				parsed.conditions = value
+					.unwrap_or_default()
+					.split(',')
+					.filter(|c| !c.is_empty())
+					.map(str::to_string)
+					.collect()
+			}
+			"--resolve-extensions" => {
+				parsed.resolve_extensions = require_value(flag, value)?
+					.split(',')
+					.map(|ext| ext.parse().map_err(|e| ArgsError(format!("{e}"))))
+					.collect::<Result<_, _>>()?;
+			}
+			"--preserve-symlinks" => parsed.preserve_symlinks = true,
+			"--packages" => {
+				let value = require_value(flag, value)?;
+				parsed.packages = Some(
+					Packages::from_name(value)
+						.ok_or_else(|| ArgsError(format!("Invalid value {value:?} in \"--packages={value}\" (valid: bundle, external)")))?
+				);
+			}
+			_ if flag.starts_with("--alias:") => {
+				let from = &flag["--alias:".len()..];
+				parsed
+					.aliases
+					.push(Alias::new(from, value.unwrap_or_default()).map_err(|e| ArgsError(format!("{e}")))?);
+			}
+			_ if flag.starts_with("--external:") => {
+				// the pattern itself may contain `=`
+				let pattern = &arg["--external:".len()..];
+				parsed.externals.push(pattern.parse().map_err(|e| ArgsError(format!("{e}")))?);
+			}
+			"--sourcemap" => parsed.sourcemap = Some(parse_sourcemap(value)?),
+			"--legal-comments" => parsed.legal_comments = Some(parse_legal_comments(require_value(flag, value)?)?),
+			"--jsx" => parsed.jsx = Some(parse_jsx(require_value(flag, value)?)?),
+			"--jsx-factory" => parsed.jsx_factory = Some(require_value(flag, value)?.to_string()),
+			"--jsx-fragment" => parsed.jsx_fragment = Some(require_value(flag, value)?.to_string()),
+			"--jsx-import-source" => parsed.jsx_import_source = Some(require_value(flag, value)?.to_string()),
+			"--jsx-dev" => parsed.jsx_dev = true,
+			"--jsx-side-effects" => parsed.jsx_side_effects = true,
+			"--outfile" => parsed.outfile = Some(require_value(flag, value)?.to_string()),
+			"--outdir" => parsed.outdir = Some(require_value(flag, value)?.to_string()),
+			"--outbase" => parsed.outbase = Some(require_value(flag, value)?.to_string()),
+			"--entry-names" => parsed.entry_names = Some(parse_path_template(require_value(flag, value)?)?),
+			"--chunk-names" => parsed.chunk_names = Some(parse_path_template(require_value(flag, value)?)?),
+			"--asset-names" => parsed.asset_names = Some(parse_path_template(require_value(flag, value)?)?),
+			"--public-path" => parsed.public_path = Some(require_value(flag, value)?.to_string()),
+			_ if flag.starts_with("--out-extension:") => {
+				let ext = &flag["--out-extension:".len()..];
+				parsed.out_extensions.push((ext.to_string(), require_value(flag, value)?.to_string()));
+			}
+			"--tsconfig" => parsed.tsconfig = Some(require_value(flag, value)?.to_string()),
+			"--metafile" => parsed.metafile = Some(require_value(flag, value)?.to_string()),
+			"--mangle-props" => parsed.mangle_props = Some(require_value(flag, value)?.to_string()),
+			"--reserve-props" => parsed.reserve_props = Some(require_value(flag, value)?.to_string()),
+			"--mangle-quoted" => {
+				parsed.mangle_quoted = match value {
+					None | Some("true") => true,
+					Some("false") => false,
+					Some(value) => return Err(ArgsError(format!("Invalid value {value:?} in \"--mangle-quoted={value}\" (valid: true, false)")))
+				}
+			}
+			"--mangle-cache" => parsed.mangle_cache = Some(require_value(flag, value)?.to_string()),
+			"--color" => {
+				parsed.color = match value {
+					None | Some("true") => Color::Always,
+					Some("false") => Color::Never,
+					Some(value) => return Err(ArgsError(format!("Invalid value {value:?} in \"--color={value}\" (valid: true, false)")))
+				}
+			}
+			"--log-level" => parsed.log_level = parse_log_level(&arg, require_value(flag, value)?)?,
+			"--log-limit" => {
+				let value = require_value(flag, value)?;
+				parsed.log_limit = value
+					.parse()
+					.map_err(|_| ArgsError(format!("Invalid value {value:?} in \"--log-limit={value}\"")))?;
+			}
+			_ if flag.starts_with("--log-override:") => {
+				let id = &flag["--log-override:".len()..];
+				let level = parse_log_level(&arg, require_value(flag, value)?)?;
+				parsed.log_overrides.push((id.to_string(), level));
+			}
+			"--watch" => {
+				parsed.mode = match value {
+					None => Mode::Watch { forever: false },
+					Some("forever") => Mode::Watch { forever: true },
+					Some(value) => return Err(ArgsError(format!("Invalid value {value:?} in \"--watch={value}\" (valid: forever)")))
+				}
+			}
+			"--serve" => serve = Some(parse_serve(value)?),
+			"--servedir" => servedir = Some(require_value(flag, value)?.to_string()),
+			"--keyfile" => tls.0 = Some(require_value(flag, value)?.to_string()),
+			"--certfile" => tls.1 = Some(require_value(flag, value)?.to_string()),
+			"--serve-fallback" => fallback = Some(require_value(flag, value)?.to_string()),
+			_ => return Err(ArgsError(format!("Invalid build flag: {arg:?}")))
+		}
+	}
+
+	if let Some(mut options) = serve {
+		if matches!(parsed.mode, Mode::Watch { .. }) {
+			return Err(ArgsError("Cannot use \"--watch\" and \"--serve\" at the same time".to_string()));
+		}
+		if let Some(servedir) = servedir {
+			options = options.with_servedir(servedir);
+		}
+		match tls {
+			(Some(keyfile), Some(certfile)) => options = options.with_tls(keyfile, certfile),
+			(None, None) => {}
+			_ => return Err(ArgsError("\"--keyfile\" and \"--certfile\" must be used together".to_string()))
+		}
+		if let Some(fallback) = fallback {
+			options = options.with_fallback(fallback);
+		}
+		parsed.mode = Mode::Serve(options);
+	} else if servedir.is_some() || tls.0.is_some() || tls.1.is_some() || fallback.is_some() {
+		return Err(ArgsError("\"--servedir\", \"--keyfile\", \"--certfile\" and \"--serve-fallback\" require \"--serve\"".to_string()));
+	}
+
+	if parsed.entry_points.is_empty() {
+		return Err(ArgsError("No entry points were specified".to_string()));
+	}
+	if parsed.outfile.is_some() && parsed.outdir.is_some() {
+		return Err(ArgsError("Cannot use both \"--outfile\" and \"--outdir\"".to_string()));
+	}
+
+	Ok(Command::Run(Box::new(parsed)))
+}
+
+pub const HELP: &str = "\
+Usage:
+  zsbuild [options] [entry points]
+
+Options:
+  --bundle                  Bundle all dependencies into the output files
+  --alias:X=Y               Substitute package X with package (or path) Y
+  --conditions=...          Custom conditions for the \"exports\" field of
+                            package.json (comma-separated)
+  --define:K=V              Substitute K with V while parsing
+  --external:M              Exclude module M from the bundle (can use * wildcards)
+  --format=...              Output format (iife | cjs | esm)
+  --jsx=...                 Set to \"automatic\" to use React's automatic runtime
+                            or to \"preserve\" to disable transforming JSX to JS
+  --jsx-factory=...         What to use for JSX instead of React.createElement
+  --jsx-fragment=...        What to use for JSX instead of React.Fragment
+  --jsx-import-source=...   Override the package name for the automatic runtime
+                            (default \"react\")
+  --jsx-dev                 Use React's automatic runtime in development mode
+  --jsx-side-effects        Do not remove unused JSX expressions
+  --legal-comments=...      Where to place legal comments (none | inline | eof |
+                            linked | external, default eof when bundling
+                            and inline otherwise)
+  --loader:X=L              Use loader L to load file extension X, where L is
+                            one of: base64 | binary | copy | css | dataurl |
+                            empty | file | global-css | js | json | jsx |
+                            local-css | text | ts | tsx
+  --main-fields=...         Override the main file order in package.json
+                            (default \"browser,module,main\" when platform is
+                            browser and \"main,module\" when platform is node)
+  --packages=...            Set to \"external\" to avoid bundling any package
+  --platform=...            Platform target (browser | node | neutral, default browser)
+  --target=...              Environment target (e.g. es2017, chrome58,
+                            firefox57, safari11, edge16, node10, ie9, opera45)
+  --minify                  Minify the output (sets all --minify-* flags)
+  --minify-whitespace       Remove whitespace in output files
+  --minify-identifiers      Shorten identifiers in output files
+  --minify-syntax           Use equivalent but shorter syntax
+  --outdir=...              The output directory (for multiple entry points)
+  --outbase=...             The base path used to determine entry point output
+                            paths (for multiple entry points)
+  --entry-names=...         Path template to use for entry point output paths
+                            (default \"[dir]/[name]\", can also use \"[hash]\")
+  --chunk-names=...         Path template to use for code splitting chunks
+                            (default \"[name]-[hash]\")
+  --asset-names=...         Path template to use for \"file\" loader files
+                            (default \"[name]-[hash]\")
+  --public-path=...         Set the base URL for the \"file\" loader
+  --out-extension:.js=.mjs  Use a custom output extension instead of \".js\"
+  --outfile=...             The output file (for one entry point)
+  --metafile=...            Write metadata about the build to a JSON file
+  --mangle-props=...        Rename all properties matching a regular expression
+  --reserve-props=...       Do not mangle these properties
+  --mangle-quoted[=...]     Also mangle quoted properties (true | false)
+  --mangle-cache=...        Load and save mangled property names to a JSON file
+  --sourcemap[=...]         Emit a source map (linked | inline | external | both)
+  --preserve-symlinks       Disable symlink resolution for module lookup
+  --resolve-extensions=...  A comma-separated list of implicit extensions
+                            (default \".tsx,.ts,.jsx,.js,.css,.json\")
+  --tsconfig=...            Use this tsconfig.json file instead of other ones
+  --splitting               Enable code splitting (currently only for esm)
+  --watch[=forever]         Watch mode: rebuild on file system changes
+  --serve[=[host:]port]     Start a local HTTP server on this host:port for outputs
+  --servedir=...            What to serve in addition to generated output files
+  --keyfile=...             Private key for serving over HTTPS
+  --certfile=...            Certificate for serving over HTTPS
+  --serve-fallback=...      File to serve for requests that match nothing else
+  --color=...               Force use of color terminal escapes (true | false)
+  --log-level=...           Disable logging (verbose | debug | info | warning | error | silent, default info)
+  --log-limit=...           Maximum message count or 0 to disable (default 6)
+  --log-override:X=Y        Use log level Y for log messages with identifier X
+  --version                 Print the current version and exit
+  -h, --help                Print this help and exit
+";
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse_run(args: &[&str]) -> Args {
+		match parse(args.iter().map(|s| s.to_string())) {
+			Ok(Command::Run(args)) => *args,
+			Ok(c) => panic!("unexpected command {c:?}"),
+			Err(e) => panic!("{e}")
+		}
+	}
+
+	#[test]
+	fn test_parse_build_flags() {
+		let args = parse_run(&["out=src/main.js", "test/foo.js", "--bundle", "--minify", "--format=esm", "--sourcemap", "--outdir=dist"]);
+		assert_eq!(args.entry_points, vec![("src/main.js".to_string(), Some("out".to_string())), ("test/foo.js".to_string(), None)]);
+		assert!(args.bundle && args.minify_whitespace && args.minify_identifiers && args.minify_syntax);
+		assert_eq!(args.format, Some(Format::EsModule));
+		assert_eq!(args.sourcemap, Some(SourceMap::Linked));
+		assert_eq!(args.outdir.as_deref(), Some("dist"));
+		assert!(matches!(args.mode, Mode::Build));
+	}
+
+	#[test]
+	fn test_parse_mangle_flags() {
+		let args = parse_run(&["a.js", "--mangle-props=_$", "--reserve-props=^__", "--mangle-quoted", "--mangle-cache=cache.json"]);
+		assert_eq!(args.mangle_props.as_deref(), Some("_$"));
+		assert_eq!(args.reserve_props.as_deref(), Some("^__"));
+		assert!(args.mangle_quoted);
+		assert_eq!(args.mangle_cache.as_deref(), Some("cache.json"));
+	}
+
+	#[test]
+	fn test_parse_typed_flags() {
+		let args = parse_run(&["a.ts", "--loader:.svg=dataurl", "--platform=node", "--target=es2020,node18"]);
+		assert_eq!(args.loaders, vec![(".svg".to_string(), Loader::DataUrl)]);
+		assert_eq!(args.platform, Some(Platform::Node));
+		assert_eq!(args.target.map(|t| t.len()), Some(2));
+		assert!(parse(["a.ts".to_string(), "--loader:.svg=svg".to_string()]).is_err());
+	}
+
+	#[test]
+	fn test_parse_naming_flags() {
+		let args = parse_run(&["a.js", "--outdir=dist", "--entry-names=[dir]/[name]-[hash]", "--out-extension:.js=.mjs", "--public-path=/static"]);
+		assert!(args.entry_names.is_some_and(|template| template.is_hashed()));
+		assert_eq!(args.out_extensions, vec![(".js".to_string(), ".mjs".to_string())]);
+		assert_eq!(args.public_path.as_deref(), Some("/static"));
+		assert!(parse(["a.js".to_string(), "--chunk-names=[name]-[chunkhash]".to_string()]).is_err());
+	}
+
+	#[test]
+	fn test_parse_serve() {
+		let args = parse_run(&["test/main.js", "--serve=localhost:8000", "--servedir=www"]);
+		assert!(matches!(args.mode, Mode::Serve(_)));
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		assert!(parse(["--bundle".to_string()]).is_err());
+		assert!(parse(["a.js".to_string(), "--format=umd".to_string()]).is_err());
+		assert!(parse(["a.js".to_string(), "--watch".to_string(), "--serve".to_string()]).is_err());
+		assert!(parse(["a.js".to_string(), "--not-a-flag".to_string()]).is_err());
+	}
+}
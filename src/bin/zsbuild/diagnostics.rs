@@ -0,0 +1,195 @@
+use std::fmt::Write;
+
+use zsbuild::{Location, LogLevel, Message};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[37m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const INVERT_RED: &str = "\x1b[41;31m";
+const INVERT_YELLOW: &str = "\x1b[43;33m";
+const WHITE_ON_RED: &str = "\x1b[41;97m";
+const BLACK_ON_YELLOW: &str = "\x1b[43;30m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+	Error,
+	Warning
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Printer {
+	color: bool,
+	level: LogLevel,
+	limit: usize
+}
+
+impl Printer {
+	pub fn new(color: bool, level: LogLevel, limit: usize) -> Self {
+		Self { color, level, limit }
+	}
+
+	pub fn level(&self) -> LogLevel {
+		self.level
+	}
+
+	pub fn shows(&self, kind: Kind) -> bool {
+		let level = match kind {
+			Kind::Error => LogLevel::Error,
+			Kind::Warning => LogLevel::Warning
+		};
+		self.level <= level
+	}
+
+	/// The maximum number of messages to print, or `usize::MAX` if unlimited.
+	pub fn limit(&self) -> usize {
+		if self.limit == 0 { usize::MAX } else { self.limit }
+	}
+
+	fn paint(&self, out: &mut String, style: &str, text: &str) {
+		if self.color {
+			out.push_str(style);
+			out.push_str(text);
+			out.push_str(RESET);
+		} else {
+			out.push_str(text);
+		}
+	}
+
+	pub fn format_message(&self, kind: Kind, message: &Message<'_>) -> String {
+		let mut out = String::new();
+		let (accent, badge_edge, badge, mark, label) = match kind {
+			Kind::Error => (RED, INVERT_RED, WHITE_ON_RED, "✘", "ERROR"),
+			Kind::Warning => (YELLOW, INVERT_YELLOW, BLACK_ON_YELLOW, "▲", "WARNING")
+		};
+
+		self.paint(&mut out, accent, mark);
+		out.push(' ');
+		if self.color {
+			self.paint(&mut out, badge_edge, "[");
+			self.paint(&mut out, badge, label);
+			self.paint(&mut out, badge_edge, "]");
+		} else {
+			let _ = write!(out, "[{label}]");
+		}
+		out.push(' ');
+		self.paint(&mut out, BOLD, message.text());
+		if let Some(id) = message.id() {
+			out.push(' ');
+			self.paint(&mut out, DIM, &format!("[{id}]"));
+		}
+		if let Some(plugin_name) = message.plugin_name() {
+			out.push(' ');
+			self.paint(&mut out, accent, &format!("[plugin {plugin_name}]"));
+		}
+		out.push_str("\n\n");
+
+		if let Some(location) = message.location() {
+			self.format_location(&mut out, &location, accent);
+		}
+
+		for note in message.notes() {
+			if note.text().is_empty() {
+				continue;
+			}
+			out.push_str("  ");
+			out.push_str(note.text());
+			out.push_str("\n\n");
+			if let Some(location) = note.location() {
+				self.format_location(&mut out, &location, GREEN);
+			}
+		}
+
+		out
+	}
+
+	fn format_location(&self, out: &mut String, location: &Location<'_>, accent: &str) {
+		let Some(file) = location.file() else {
+			return;
+		};
+
+		out.push_str("    ");
+		self.paint(out, BOLD, &format!("{}:{}:{}:", file, location.line(), location.column()));
+		out.push('\n');
+
+		let line_text = location.line_text();
+		let line_number = location.line().to_string();
+		let gutter = " ".repeat(line_number.len());
+
+		let column = floor_char_boundary(line_text, location.column());
+		let end = floor_char_boundary(line_text, column + location.len());
+		let (before, rest) = line_text.split_at(column);
+		let (highlight, after) = rest.split_at(end - column);
+
+		out.push_str("      ");
+		self.paint(out, DIM, &format!("{line_number} │ "));
+		out.push_str(before);
+		self.paint(out, accent, highlight);
+		self.paint(out, DIM, after);
+		out.push('\n');
+
+		out.push_str("      ");
+		self.paint(out, DIM, &format!("{gutter} ╵ "));
+		out.push_str(&" ".repeat(before.chars().count()));
+		let underline = match highlight.chars().count() {
+			0 => "^".to_string(),
+			n => "~".repeat(n)
+		};
+		self.paint(out, accent, &underline);
+		out.push('\n');
+
+		if let Some(suggestion) = location.suggestion() {
+			out.push_str("      ");
+			self.paint(out, DIM, &format!("{gutter} │ "));
+			out.push_str(&" ".repeat(before.chars().count()));
+			self.paint(out, GREEN, suggestion);
+			out.push('\n');
+		}
+
+		out.push('\n');
+	}
+
+	pub fn format_summary(&self, errors: usize, warnings: usize) -> Option<String> {
+		fn plural(n: usize, word: &str) -> String {
+			if n == 1 { format!("{n} {word}") } else { format!("{n} {word}s") }
+		}
+
+		let text = match (errors, warnings) {
+			(0, 0) => return None,
+			(0, w) => plural(w, "warning"),
+			(e, 0) => plural(e, "error"),
+			(e, w) => format!("{} and {}", plural(w, "warning"), plural(e, "error"))
+		};
+		Some(text)
+	}
+
+	pub fn format_output(&self, path: &str, size: usize, width: usize) -> String {
+		let mut out = String::from("  ");
+		self.paint(&mut out, BOLD, path);
+		out.push_str(&" ".repeat(width.saturating_sub(path.len()) + 2));
+		self.paint(&mut out, DIM, &format_size(size));
+		out.push('\n');
+		out
+	}
+}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+	if index >= s.len() {
+		return s.len();
+	}
+	while !s.is_char_boundary(index) {
+		index -= 1;
+	}
+	index
+}
+
+pub fn format_size(size: usize) -> String {
+	match size {
+		s if s < 1024 => format!("{s}b"),
+		s if s < 1024 * 1024 => format!("{:.1}kb", s as f64 / 1024.0),
+		s if s < 1024 * 1024 * 1024 => format!("{:.1}mb", s as f64 / (1024.0 * 1024.0)),
+		s => format!("{:.1}gb", s as f64 / (1024.0 * 1024.0 * 1024.0))
+	}
+}
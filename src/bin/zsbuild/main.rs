@@ -0,0 +1,241 @@
+use std::{
+	env, fs,
+	io::{self, IsTerminal, Write},
+	process::ExitCode,
+	thread,
+	time::Instant
+};
+
+use zsbuild::{BuildResult, Context, LogLevel, MangleCache, MessageBuilder, OnEndResult, Plugin, PluginBuilder};
+
+use self::{
+	args::{Color, Command, Mode},
+	diagnostics::{Kind, Printer}
+};
+
+mod args;
+mod diagnostics;
+
+fn report(printer: &Printer, result: &BuildResult) {
+	let mut stderr = io::stderr().lock();
+	let warnings = result
+		.warnings()
+		.iter()
+		.filter(|_| printer.shows(Kind::Warning))
+		.map(|m| (Kind::Warning, m));
+	let errors = result.errors().iter().filter(|_| printer.shows(Kind::Error)).map(|m| (Kind::Error, m));
+	let mut printed = 0;
+	let mut skipped = 0;
+	for (kind, message) in warnings.chain(errors) {
+		if printed < printer.limit() {
+			let _ = stderr.write_all(printer.format_message(kind, message).as_bytes());
+			printed += 1;
+		} else {
+			skipped += 1;
+		}
+	}
+	if skipped > 0 {
+		let _ = writeln!(stderr, "{skipped} more messages were not shown (use \"--log-limit=0\" to show all)\n");
+	}
+	if printed > 0 || skipped > 0 {
+		if let Some(summary) = printer.format_summary(result.errors().len(), result.warnings().len()) {
+			let _ = writeln!(stderr, "{summary}\n");
+		}
+	}
+}
+
+fn report_outputs(printer: &Printer, result: &BuildResult) {
+	let outputs = result.outputs();
+	let width = outputs.iter().map(|o| o.path().len()).max().unwrap_or(0);
+	let cwd = env::current_dir().ok();
+	let mut stderr = io::stderr().lock();
+	for output in outputs {
+		let path = cwd
+			.as_deref()
+			.and_then(|cwd| output.path().strip_prefix(&*cwd.to_string_lossy()))
+			.map(|p| p.trim_start_matches(['/', '\\']))
+			.unwrap_or(output.path());
+		let _ = stderr.write_all(printer.format_output(path, output.contents().len(), width).as_bytes());
+	}
+	let _ = stderr.write_all(b"\n");
+}
+
+fn write_metafile(path: &str, result: &BuildResult) -> io::Result<()> {
+	match result.metafile() {
+		Some(metafile) => fs::write(path, metafile),
+		None => Ok(())
+	}
+}
+
+fn read_mangle_cache(path: &str) -> Result<Option<MangleCache>, String> {
+	match fs::read_to_string(path) {
+		Ok(json) => MangleCache::from_json(&json)
+			.map(Some)
+			.map_err(|e| format!("invalid mangle cache {path}: {e}")),
+		// like esbuild, a missing cache file just means this is the first build.
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(format!("failed to read mangle cache {path}: {e}"))
+	}
+}
+
+fn write_mangle_cache(path: &str, result: &BuildResult) -> io::Result<()> {
+	match result.mangle_cache() {
+		Some(cache) => fs::write(path, cache.to_json()),
+		None => Ok(())
+	}
+}
+
+/// Writes the metafile & mangle cache after every build of a watch or serve context, like build mode does once.
+struct WriteArtifacts {
+	metafile: Option<String>,
+	mangle_cache: Option<String>
+}
+
+impl Plugin for WriteArtifacts {
+	fn name(&self) -> &str {
+		"zsbuild:write-artifacts"
+	}
+
+	fn build(&self, builder: &mut PluginBuilder) {
+		let (metafile, mangle_cache) = (self.metafile.clone(), self.mangle_cache.clone());
+		builder.on_end(move |result| {
+			if result.is_error() {
+				return OnEndResult::ok();
+			}
+			if let Some(path) = &metafile {
+				if let Err(e) = write_metafile(path, result) {
+					return OnEndResult::error(MessageBuilder::new(format!("failed to write metafile to {path}: {e}")));
+				}
+			}
+			if let Some(path) = &mangle_cache {
+				if let Err(e) = write_mangle_cache(path, result) {
+					return OnEndResult::error(MessageBuilder::new(format!("failed to write mangle cache to {path}: {e}")));
+				}
+			}
+			OnEndResult::ok()
+		});
+	}
+}
+
+fn main() -> ExitCode {
+	let args = match args::parse(env::args().skip(1)) {
+		Ok(Command::Run(args)) => args,
+		Ok(Command::Help) => {
+			print!("{}", args::HELP);
+			return ExitCode::SUCCESS;
+		}
+		Ok(Command::Version) => {
+			println!("{}", env!("CARGO_PKG_VERSION"));
+			return ExitCode::SUCCESS;
+		}
+		Err(e) => {
+			eprintln!("zsbuild: {e}");
+			eprintln!("Run \"zsbuild --help\" for usage.");
+			return ExitCode::FAILURE;
+		}
+	};
+
+	let color = match args.color {
+		Color::Always => true,
+		Color::Never => false,
+		Color::Auto => io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none()
+	};
+	let printer = Printer::new(color, args.log_level, args.log_limit);
+	let mut options = args.to_build_options();
+	if let Some(path) = &args.mangle_cache {
+		match read_mangle_cache(path) {
+			Ok(Some(cache)) => options = options.mangle_cache(&cache),
+			Ok(None) => options = options.mangle_cache(&MangleCache::new()),
+			Err(e) => {
+				eprintln!("zsbuild: {e}");
+				return ExitCode::FAILURE;
+			}
+		}
+	}
+
+	if !matches!(args.mode, Mode::Build) && (args.metafile.is_some() || args.mangle_cache.is_some()) {
+		options = options.plugin(WriteArtifacts {
+			metafile: args.metafile.clone(),
+			mangle_cache: args.mangle_cache.clone()
+		});
+	}
+
+	match &args.mode {
+		Mode::Build => {
+			let start = Instant::now();
+			let result = zsbuild::build(&options);
+			report(&printer, &result);
+			if result.is_error() {
+				return ExitCode::FAILURE;
+			}
+			if let Some(path) = &args.metafile {
+				if let Err(e) = write_metafile(path, &result) {
+					eprintln!("zsbuild: failed to write metafile to {path}: {e}");
+					return ExitCode::FAILURE;
+				}
+			}
+			if let Some(path) = &args.mangle_cache {
+				if let Err(e) = write_mangle_cache(path, &result) {
+					eprintln!("zsbuild: failed to write mangle cache to {path}: {e}");
+					return ExitCode::FAILURE;
+				}
+			}
+			if printer.level() <= LogLevel::Info {
+				report_outputs(&printer, &result);
+				eprintln!("⚡ Done in {}ms", start.elapsed().as_millis());
+			}
+			ExitCode::SUCCESS
+		}
+		Mode::Watch { forever } => {
+			let context = match Context::new(&options) {
+				Ok(context) => context,
+				Err(e) => {
+					eprintln!("zsbuild: {e}");
+					return ExitCode::FAILURE;
+				}
+			};
+			// esbuild logs watch builds itself, including errors writing the metafile or mangle cache.
+			if let Err(e) = context.watch() {
+				eprintln!("zsbuild: {e}");
+				return ExitCode::FAILURE;
+			}
+			if *forever {
+				loop {
+					thread::park();
+				}
+			}
+			// Like esbuild, stop watching once stdin is closed so the process doesn't outlive its parent.
+			let _ = io::copy(&mut io::stdin().lock(), &mut io::sink());
+			ExitCode::SUCCESS
+		}
+		Mode::Serve(serve_options) => {
+			let context = match Context::new(&options) {
+				Ok(context) => context,
+				Err(e) => {
+					eprintln!("zsbuild: {e}");
+					return ExitCode::FAILURE;
+				}
+			};
+			// Like watch mode, esbuild reports the initial build and every rebuild triggered by a request itself.
+			drop(context.build());
+			match context.serve(serve_options) {
+				Ok(serve) => {
+					let host = match serve.host() {
+						"" | "0.0.0.0" => "127.0.0.1",
+						host => host
+					};
+					if printer.level() <= LogLevel::Info {
+						eprintln!("\n > Local: http://{}:{}/\n", host, serve.port());
+					}
+				}
+				Err(e) => {
+					eprintln!("zsbuild: {e}");
+					return ExitCode::FAILURE;
+				}
+			}
+			loop {
+				thread::park();
+			}
+		}
+	}
+}
@@ -6,21 +6,22 @@ use std::{
 	task::{Context, Poll, Waker}
 };
 
-use crate::{context::SharedContextHandle, sys, BuildResult};
+use super::options::SharedBuildOptionsHandle;
+use crate::{BuildResult, context::SharedContextHandle, sys};
 
 struct ContextBuildAsyncCallbackData {
 	cb: *mut Box<dyn FnOnce(BuildResult) + Send>,
 	_context_handle: Arc<SharedContextHandle>
 }
 
-pub(crate) fn context_build_async_inner<F: FnOnce(BuildResult) + Send + 'static>(context: Arc<SharedContextHandle>, cb: F) {
+pub(crate) fn context_build_async_inner<F: FnOnce(BuildResult) + Send + 'static>(context: Arc<SharedContextHandle>, build_id: u64, timeout_ms: i64, cb: F) {
 	let handle = context.handle();
 	let data = Box::into_raw(Box::new(ContextBuildAsyncCallbackData {
 		_context_handle: context,
 		cb: Box::into_raw(Box::new(Box::new(cb) as Box<dyn FnOnce(BuildResult) + Send>))
 	}));
 
-	unsafe { sys::Zsb_Context_BuildAsync(handle, Some(context_build_async_callback), data as *mut _) };
+	unsafe { sys::Zsb_Context_BuildAsync(handle, build_id, timeout_ms, Some(context_build_async_callback), data as *mut _) };
 }
 
 extern "C" fn context_build_async_callback(build_result: *mut sys::BuildResult, data: *mut c_void) {
@@ -29,6 +30,29 @@ extern "C" fn context_build_async_callback(build_result: *mut sys::BuildResult,
 	(*cb)(BuildResult::new(build_result))
 }
 
+struct BuildAsyncCallbackData {
+	cb: *mut Box<dyn FnOnce(BuildResult) + Send>,
+	// esbuild reads the options for the entire duration of the build, so they must outlive it even if the
+	// `BuildOptions` that started it is dropped in the meantime.
+	_options_handle: Arc<SharedBuildOptionsHandle>
+}
+
+pub(crate) fn build_async_inner<F: FnOnce(BuildResult) + Send + 'static>(options: Arc<SharedBuildOptionsHandle>, cb: F) {
+	let handle = options.handle();
+	let data = Box::into_raw(Box::new(BuildAsyncCallbackData {
+		_options_handle: options,
+		cb: Box::into_raw(Box::new(Box::new(cb) as Box<dyn FnOnce(BuildResult) + Send>))
+	}));
+
+	unsafe { sys::Zsb_BuildAsync(handle, Some(build_async_callback), data as *mut _) };
+}
+
+extern "C" fn build_async_callback(build_result: *mut sys::BuildResult, data: *mut c_void) {
+	let data = unsafe { Box::from_raw(data as *mut BuildAsyncCallbackData) };
+	let cb = unsafe { Box::from_raw(data.cb) };
+	(*cb)(BuildResult::new(build_result))
+}
+
 #[derive(Default)]
 pub struct BuildFutureState {
 	pub(crate) result: Option<BuildResult>,
@@ -45,15 +69,15 @@ impl BuildFutureState {
 }
 
 pub struct BuildFuture {
-	context: Option<Arc<SharedContextHandle>>,
+	build: Option<(Arc<SharedContextHandle>, u64)>,
 	cancellable: bool,
 	state: Arc<Mutex<BuildFutureState>>
 }
 
 impl BuildFuture {
-	pub(crate) fn new(state: Arc<Mutex<BuildFutureState>>, context: Option<&Arc<SharedContextHandle>>) -> Self {
+	pub(crate) fn new(state: Arc<Mutex<BuildFutureState>>, build: Option<(&Arc<SharedContextHandle>, u64)>) -> Self {
 		BuildFuture {
-			context: context.cloned(),
+			build: build.map(|(context, build_id)| (Arc::clone(context), build_id)),
 			cancellable: false,
 			state
 		}
@@ -83,8 +107,9 @@ impl Future for BuildFuture {
 impl Drop for BuildFuture {
 	fn drop(&mut self) {
 		if self.cancellable {
-			if let Some(context) = &self.context {
-				unsafe { sys::Zsb_Context_Cancel(context.handle()) };
+			// only cancels this build; this is a no-op if it has already finished.
+			if let Some((context, build_id)) = &self.build {
+				unsafe { sys::Zsb_Context_CancelBuild(context.handle(), *build_id) };
 			}
 		}
 	}
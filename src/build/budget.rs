@@ -0,0 +1,298 @@
+use std::{borrow::Cow, fs, path::Path};
+
+use super::{BuildResult, compress, metafile::Metafile};
+use crate::{
+	error::{MessageBuilder, NoteBuilder},
+	plugin::{OnEndResult, Plugin, PluginBuilder},
+	util
+};
+
+/// Which outputs a [`SizeBudget`] applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetTarget {
+	/// Every output generated for an entry point.
+	Entries,
+	/// The outputs generated for the entry point with this input path, e.g. `src/main.ts`.
+	Entry(String),
+	/// Every shared chunk, i.e. output that isn't generated for an entry point.
+	Chunks,
+	/// Outputs whose path (relative to the working directory, with `/` separators) matches this glob, e.g.
+	/// `dist/**/*.css`.
+	Glob(String)
+}
+
+/// How the size of an output is measured for a [`SizeBudget`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BudgetCompression {
+	#[default]
+	Raw,
+	/// The size after gzip compression at level 9.
+	Gzip
+}
+
+/// A maximum size for a set of outputs. See [`SizeBudgets`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeBudget {
+	target: BudgetTarget,
+	max_bytes: u64,
+	compression: BudgetCompression,
+	warning: bool
+}
+
+impl SizeBudget {
+	pub fn new(target: BudgetTarget, max_bytes: u64) -> Self {
+		Self {
+			target,
+			max_bytes,
+			compression: BudgetCompression::Raw,
+			warning: false
+		}
+	}
+
+	pub fn entries(max_bytes: u64) -> Self {
+		Self::new(BudgetTarget::Entries, max_bytes)
+	}
+
+	pub fn entry(entry_point: impl ToString, max_bytes: u64) -> Self {
+		Self::new(BudgetTarget::Entry(entry_point.to_string()), max_bytes)
+	}
+
+	pub fn chunks(max_bytes: u64) -> Self {
+		Self::new(BudgetTarget::Chunks, max_bytes)
+	}
+
+	pub fn glob(pattern: impl ToString, max_bytes: u64) -> Self {
+		Self::new(BudgetTarget::Glob(pattern.to_string()), max_bytes)
+	}
+
+	pub fn compression(mut self, compression: BudgetCompression) -> Self {
+		self.compression = compression;
+		self
+	}
+
+	/// Measures outputs after gzip compression instead of by their raw size.
+	pub fn gzip(self) -> Self {
+		self.compression(BudgetCompression::Gzip)
+	}
+
+	/// Reports violations of this budget as warnings instead of errors, so they don't fail the build.
+	pub fn warning(mut self) -> Self {
+		self.warning = true;
+		self
+	}
+
+	pub fn target(&self) -> &BudgetTarget {
+		&self.target
+	}
+
+	pub fn max_bytes(&self) -> u64 {
+		self.max_bytes
+	}
+
+	pub fn is_warning(&self) -> bool {
+		self.warning
+	}
+
+	fn applies_to(&self, path: &str, entry_point: Option<&str>) -> bool {
+		let is_source_map = path.ends_with(".map");
+		match &self.target {
+			BudgetTarget::Entries => entry_point.is_some() && !is_source_map,
+			BudgetTarget::Entry(input) => {
+				let input = input.strip_prefix("./").unwrap_or(input);
+				entry_point == Some(input) && !is_source_map
+			}
+			BudgetTarget::Chunks => entry_point.is_none() && !is_source_map,
+			BudgetTarget::Glob(pattern) => util::glob_match(pattern.strip_prefix("./").unwrap_or(pattern), path)
+		}
+	}
+}
+
+/// Size limits evaluated after each build, i.e. to fail CI when a bundle grows too large.
+///
+/// Budgets are checked by a plugin, so violations are reported like any other esbuild error or warning, and are
+/// included in the [`errors`](BuildResult::errors) & [`warnings`](BuildResult::warnings) of the build result. Each
+/// message has notes listing the inputs that contribute the most to the output. Use
+/// [`BuildOptions::size_budgets`](crate::BuildOptions::size_budgets) to add them to a build.
+///
+/// ```no_run
+/// # use zsbuild::{BuildOptions, SizeBudget, SizeBudgets};
+/// let entries = SizeBudget::entries(200 * 1024);
+/// let styles = SizeBudget::glob("dist/**/*.css", 30 * 1024).gzip();
+/// let budgets = SizeBudgets::new().budget(entries).budget(styles);
+/// let options = BuildOptions::new().entry_point("src/main.ts", "main").outdir("dist");
+/// let options = options.size_budgets(budgets);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeBudgets {
+	budgets: Vec<SizeBudget>,
+	max_notes: usize
+}
+
+impl Default for SizeBudgets {
+	fn default() -> Self {
+		Self { budgets: Vec::new(), max_notes: 5 }
+	}
+}
+
+impl SizeBudgets {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn budget(mut self, budget: SizeBudget) -> Self {
+		self.budgets.push(budget);
+		self
+	}
+
+	/// The maximum number of contributing inputs listed in the notes of each violation. Defaults to 5.
+	pub fn max_notes(mut self, max_notes: usize) -> Self {
+		self.max_notes = max_notes;
+		self
+	}
+
+	pub fn budgets(&self) -> &[SizeBudget] {
+		&self.budgets
+	}
+
+	/// Checks the outputs of `result` against the budgets. Requires [`metafile`](crate::BuildOptions::metafile).
+	pub fn evaluate(&self, result: &BuildResult) -> OnEndResult {
+		if self.budgets.is_empty() || result.is_error() {
+			return OnEndResult::ok();
+		}
+		let Some(metafile) = result.parse_metafile() else {
+			return OnEndResult::ok().with_warning(MessageBuilder::new("Size budgets could not be checked because the build has no metafile"));
+		};
+		let cwd = std::env::current_dir().unwrap_or_default();
+		let outputs = result.outputs();
+		self.evaluate_metafile(&metafile, |path| {
+			let absolute = util::normalize_path(&cwd.join(path));
+			match outputs.iter().find(|output| Path::new(output.path()) == absolute) {
+				Some(output) => Some(Cow::Borrowed(output.contents())),
+				// outputs aren't returned when they are written to disk
+				None => fs::read(absolute).ok().map(Cow::Owned)
+			}
+		})
+	}
+
+	pub(crate) fn evaluate_metafile<'a>(&self, metafile: &Metafile, contents: impl Fn(&str) -> Option<Cow<'a, [u8]>>) -> OnEndResult {
+		let mut result = OnEndResult::ok();
+		for (path, output) in &metafile.outputs {
+			let mut gzip_bytes = None;
+			for budget in self
+				.budgets
+				.iter()
+				.filter(|budget| budget.applies_to(path, output.entry_point.as_deref()))
+			{
+				let bytes = match budget.compression {
+					BudgetCompression::Raw => output.bytes,
+					BudgetCompression::Gzip => {
+						if gzip_bytes.is_none() {
+							gzip_bytes = Some(contents(path).map(|contents| compress::gzip(&contents, 9).len() as u64));
+						}
+						match gzip_bytes.flatten() {
+							Some(bytes) => bytes,
+							None => continue
+						}
+					}
+				};
+				if bytes <= budget.max_bytes {
+					continue;
+				}
+
+				let compression = match budget.compression {
+					BudgetCompression::Raw => "",
+					BudgetCompression::Gzip => " gzipped"
+				};
+				let mut message = MessageBuilder::new(format!(
+					"\"{path}\" is {}{compression}, which exceeds its size budget of {} by {}",
+					format_bytes(bytes),
+					format_bytes(budget.max_bytes),
+					format_bytes(bytes - budget.max_bytes)
+				))
+				.with_id("size-budget");
+				let mut inputs = output.inputs.iter().filter(|(_, input)| input.bytes_in_output > 0).collect::<Vec<_>>();
+				inputs.sort_by(|a, b| b.1.bytes_in_output.cmp(&a.1.bytes_in_output).then_with(|| a.0.cmp(b.0)));
+				for (input, contribution) in inputs.into_iter().take(self.max_notes) {
+					let percent = contribution.bytes_in_output as f64 / output.bytes.max(1) as f64 * 100.0;
+					message = message.with_note(NoteBuilder::new(format!(
+						"\"{input}\" contributes {} ({percent:.1}% of the uncompressed output)",
+						format_bytes(contribution.bytes_in_output)
+					)));
+				}
+				result = if budget.warning { result.with_warning(message) } else { result.with_error(message) };
+			}
+		}
+		result
+	}
+}
+
+impl Plugin for SizeBudgets {
+	fn name(&self) -> &str {
+		"zsbuild:size-budgets"
+	}
+
+	fn build(&self, builder: &mut PluginBuilder) {
+		let budgets = self.clone();
+		builder.on_end(move |result| budgets.evaluate(result));
+	}
+}
+
+/// Formats a size the same way esbuild's analysis output does, i.e. `1.5kb`.
+fn format_bytes(bytes: u64) -> String {
+	match bytes {
+		0..=1023 => format!("{bytes}b"),
+		1024..=1048575 => format!("{:.1}kb", bytes as f64 / 1024.0),
+		_ => format!("{:.1}mb", bytes as f64 / 1048576.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_size_budgets() {
+		let metafile = Metafile::from_json(
+			r#"{
+				"inputs": {
+					"node_modules/big/index.js": { "bytes": 2500 },
+					"src/main.ts": { "bytes": 400 },
+					"src/types.ts": { "bytes": 10 },
+					"src/shared.ts": { "bytes": 90 }
+				},
+				"outputs": {
+					"dist/main.js": {
+						"bytes": 3000,
+						"inputs": { "node_modules/big/index.js": { "bytesInOutput": 2500 }, "src/main.ts": { "bytesInOutput": 400 }, "src/types.ts": { "bytesInOutput": 0 } },
+						"entryPoint": "src/main.ts"
+					},
+					"dist/main.js.map": { "bytes": 9000 },
+					"dist/chunk-AAAA.js": { "bytes": 100, "inputs": { "src/shared.ts": { "bytesInOutput": 90 } } },
+					"dist/main.css": { "bytes": 200, "entryPoint": "src/main.ts" }
+				}
+			}"#
+		)
+		.unwrap();
+		let budgets = SizeBudgets::new()
+			.budget(SizeBudget::entry("./src/main.ts", 2048))
+			.budget(SizeBudget::chunks(1000))
+			.budget(SizeBudget::glob("dist/**/*.css", 100).warning())
+			.budget(SizeBudget::glob("dist/*.js", 100).gzip());
+		let result = budgets.evaluate_metafile(&metafile, |path| (path == "dist/main.js").then(|| Cow::Owned(vec![b'a'; 3000])));
+
+		assert_eq!(result.errors().len(), 1);
+		let error = &result.errors()[0];
+		assert_eq!(error.id(), Some("size-budget"));
+		assert_eq!(error.text(), "\"dist/main.js\" is 2.9kb, which exceeds its size budget of 2.0kb by 952b");
+		assert_eq!(error.notes().len(), 2);
+		assert_eq!(error.notes()[0].text(), "\"node_modules/big/index.js\" contributes 2.4kb (83.3% of the uncompressed output)");
+
+		assert_eq!(result.warnings().len(), 1);
+		assert!(result.warnings()[0].text().starts_with("\"dist/main.css\" is 200b"));
+
+		assert!(util::glob_match("dist/**/*.js", "dist/a/b/c.js"));
+		assert!(util::glob_match("dist/**/*.js", "dist/c.js"));
+		assert!(!util::glob_match("dist/*.js", "dist/a/c.js"));
+		assert!(util::glob_match("dist/chunk-????.js", "dist/chunk-AAAA.js"));
+	}
+}
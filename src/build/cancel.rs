@@ -0,0 +1,67 @@
+use std::sync::{
+	Arc, Mutex, Weak,
+	atomic::{AtomicBool, AtomicU64, Ordering}
+};
+
+use crate::{context::SharedContextHandle, sys};
+
+static NEXT_BUILD_ID: AtomicU64 = AtomicU64::new(1);
+
+pub(crate) fn next_build_id() -> u64 {
+	NEXT_BUILD_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Default)]
+struct CancellationTokenInner {
+	cancelled: AtomicBool,
+	builds: Mutex<Vec<(Weak<SharedContextHandle>, u64)>>
+}
+
+/// A handle used to cancel one or more specific [`Context`](crate::Context) builds, without affecting other builds
+/// running on the same context.
+///
+/// Cloning a token yields a handle to the same underlying token. Once cancelled, a token stays cancelled; builds
+/// started with an already-cancelled token finish immediately with
+/// [`BuildResult::is_cancelled`](crate::BuildResult::is_cancelled) set.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+	inner: Arc<CancellationTokenInner>
+}
+
+impl CancellationToken {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn cancel(&self) {
+		self.inner.cancelled.store(true, Ordering::SeqCst);
+		let builds = std::mem::take(&mut *self.inner.builds.lock().unwrap());
+		for (context, build_id) in builds {
+			if let Some(context) = context.upgrade() {
+				unsafe { sys::Zsb_Context_CancelBuild(context.handle(), build_id) };
+			}
+		}
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.inner.cancelled.load(Ordering::SeqCst)
+	}
+
+	pub(crate) fn register(&self, context: &Arc<SharedContextHandle>, build_id: u64) {
+		self.inner.builds.lock().unwrap().push((Arc::downgrade(context), build_id));
+		// `cancel()` may have drained the list just before we pushed to it.
+		if self.is_cancelled() {
+			unsafe { sys::Zsb_Context_CancelBuild(context.handle(), build_id) };
+		}
+	}
+
+	pub(crate) fn unregister(&self, build_id: u64) {
+		self.inner.builds.lock().unwrap().retain(|(_, id)| *id != build_id);
+	}
+}
+
+impl std::fmt::Debug for CancellationToken {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("CancellationToken").field("cancelled", &self.is_cancelled()).finish()
+	}
+}
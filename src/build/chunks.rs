@@ -0,0 +1,211 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque, btree_map};
+
+use super::metafile::Metafile;
+
+/// An output file in a [`ChunkGraph`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Chunk {
+	path: String,
+	entry_point: Option<String>,
+	imports: Vec<String>,
+	dynamic_imports: Vec<String>,
+	css_bundle: Option<String>
+}
+
+impl Chunk {
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	/// The input this chunk was generated from, if it is an entry point (including the targets of dynamic `import()`s
+	/// when code splitting).
+	pub fn entry_point(&self) -> Option<&str> {
+		self.entry_point.as_deref()
+	}
+
+	/// Outputs this chunk imports with `import` statements, i.e. the shared chunks it needs before it can run.
+	pub fn imports(&self) -> &[String] {
+		&self.imports
+	}
+
+	/// Outputs this chunk loads with `import()`.
+	pub fn dynamic_imports(&self) -> &[String] {
+		&self.dynamic_imports
+	}
+
+	/// The CSS file generated for the CSS this chunk imports, if any.
+	pub fn css_bundle(&self) -> Option<&str> {
+		self.css_bundle.as_deref()
+	}
+}
+
+/// How the outputs of a build import each other, computed from the metafile. With
+/// [`splitting`](crate::BuildOptions::splitting), this tells which shared chunks each entry point needs, e.g. to emit
+/// `<link rel="modulepreload">` tags for them.
+///
+/// Chunks are keyed by their output path, relative to the working directory. Source maps are not included, and
+/// imports of external modules are ignored.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChunkGraph {
+	chunks: BTreeMap<String, Chunk>
+}
+
+impl ChunkGraph {
+	pub fn from_metafile(metafile: &Metafile) -> Self {
+		let is_chunk = |path: &str| !path.ends_with(".map");
+		let chunks = metafile
+			.outputs
+			.iter()
+			.filter(|(path, _)| is_chunk(path))
+			.map(|(path, output)| {
+				let imported = |kind: &str| {
+					output
+						.imports
+						.iter()
+						.filter(|import| !import.external && import.kind == kind && metafile.outputs.contains_key(&import.path))
+						.map(|import| import.path.clone())
+						.collect::<Vec<_>>()
+				};
+				let chunk = Chunk {
+					path: path.clone(),
+					entry_point: output.entry_point.clone(),
+					imports: imported("import-statement"),
+					dynamic_imports: imported("dynamic-import"),
+					css_bundle: output.css_bundle.clone()
+				};
+				(path.clone(), chunk)
+			})
+			.collect();
+		Self { chunks }
+	}
+
+	pub fn get(&self, path: &str) -> Option<&Chunk> {
+		self.chunks.get(path)
+	}
+
+	/// Finds the chunk generated from the entry point `input`, e.g. `src/main.ts`.
+	pub fn entry(&self, input: &str) -> Option<&Chunk> {
+		self.chunks.values().find(|chunk| chunk.entry_point() == Some(input))
+	}
+
+	pub fn len(&self) -> usize {
+		self.chunks.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.chunks.is_empty()
+	}
+
+	pub fn iter(&self) -> btree_map::Values<'_, String, Chunk> {
+		self.chunks.values()
+	}
+
+	/// Returns every chunk `path` statically depends on, directly or through other chunks, closest first. These are
+	/// the chunks to preload along with `path`; chunks that are only loaded with `import()` are not included.
+	pub fn transitive_imports(&self, path: &str) -> Vec<&str> {
+		let mut seen = BTreeSet::from([path]);
+		let mut queue = VecDeque::from([path]);
+		let mut imports = Vec::new();
+		while let Some(current) = queue.pop_front() {
+			let Some(chunk) = self.chunks.get(current) else {
+				continue;
+			};
+			for import in &chunk.imports {
+				if seen.insert(import.as_str()) {
+					imports.push(import.as_str());
+					queue.push_back(import.as_str());
+				}
+			}
+		}
+		imports
+	}
+
+	/// Returns the CSS bundles of `path` and the chunks it statically depends on, which must be loaded along with it.
+	pub fn stylesheets(&self, path: &str) -> Vec<&str> {
+		std::iter::once(path)
+			.chain(self.transitive_imports(path))
+			.filter_map(|path| self.chunks.get(path)?.css_bundle())
+			.collect()
+	}
+}
+
+impl<'a> IntoIterator for &'a ChunkGraph {
+	type Item = &'a Chunk;
+	type IntoIter = btree_map::Values<'a, String, Chunk>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.chunks.values()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{BuildOptions, Format, build};
+
+	#[test]
+	fn test_chunk_graph() {
+		let metafile = Metafile::from_json(
+			r#"{
+				"inputs": {},
+				"outputs": {
+					"dist/a.js": {
+						"bytes": 1,
+						"imports": [
+							{ "path": "dist/chunk-SHARED.js", "kind": "import-statement" },
+							{ "path": "dist/lazy.js", "kind": "dynamic-import" },
+							{ "path": "react", "kind": "import-statement", "external": true }
+						],
+						"entryPoint": "src/a.js",
+						"cssBundle": "dist/a.css"
+					},
+					"dist/a.js.map": { "bytes": 1 },
+					"dist/a.css": { "bytes": 1 },
+					"dist/chunk-SHARED.js": { "bytes": 1, "imports": [{ "path": "dist/chunk-DEEP.js", "kind": "import-statement" }] },
+					"dist/chunk-DEEP.js": { "bytes": 1 },
+					"dist/lazy.js": { "bytes": 1, "imports": [{ "path": "dist/chunk-SHARED.js", "kind": "import-statement" }], "entryPoint": "src/lazy.js" }
+				}
+			}"#
+		)
+		.unwrap();
+		let graph = ChunkGraph::from_metafile(&metafile);
+		assert_eq!(graph.len(), 5);
+
+		let a = graph.entry("src/a.js").unwrap();
+		assert_eq!(a.path(), "dist/a.js");
+		assert_eq!(a.imports(), ["dist/chunk-SHARED.js"]);
+		assert_eq!(a.dynamic_imports(), ["dist/lazy.js"]);
+		assert_eq!(a.css_bundle(), Some("dist/a.css"));
+		assert_eq!(graph.transitive_imports("dist/a.js"), ["dist/chunk-SHARED.js", "dist/chunk-DEEP.js"]);
+		assert_eq!(graph.stylesheets("dist/a.js"), ["dist/a.css"]);
+		assert_eq!(graph.get("dist/chunk-SHARED.js").unwrap().entry_point(), None);
+	}
+
+	#[test]
+	fn test_chunk_graph_splitting() {
+		let res = build(
+			&BuildOptions::new()
+				.entry_points(["test/chunks/a.js", "test/chunks/b.js"])
+				.bundle(true)
+				.splitting(true)
+				.format(Format::EsModule)
+				.outdir("test/chunks/out")
+				.metafile(true)
+				.write(false)
+		);
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+		let graph = res.chunk_graph().unwrap();
+		let a = graph.entry("test/chunks/a.js").unwrap();
+		let b = graph.entry("test/chunks/b.js").unwrap();
+		// `shared.js` is imported by both entry points, so it is split into a chunk of its own
+		assert_eq!(a.imports().len(), 1);
+		assert_eq!(a.imports(), b.imports());
+		assert_eq!(graph.get(&a.imports()[0]).unwrap().entry_point(), None);
+		assert_eq!(a.css_bundle(), Some("test/chunks/out/a.css"));
+
+		let lazy = graph.get(&b.dynamic_imports()[0]).unwrap();
+		assert_eq!(lazy.entry_point(), Some("test/chunks/lazy.js"));
+	}
+}
@@ -0,0 +1,20 @@
+use std::io::Write;
+
+use flate2::{Compression, write::GzEncoder};
+
+// the largest window size brotli supports without the "large window" extension, which browsers don't understand
+const BROTLI_LG_WINDOW: u32 = 22;
+
+/// Compresses `data` with gzip at the given level (`0..=9`).
+pub(crate) fn gzip(data: &[u8], level: u32) -> Vec<u8> {
+	let mut encoder = GzEncoder::new(Vec::with_capacity(data.len() / 2), Compression::new(level.min(9)));
+	encoder.write_all(data).expect("writing to a Vec is infallible");
+	encoder.finish().expect("writing to a Vec is infallible")
+}
+
+/// Compresses `data` with brotli at the given quality (`0..=11`).
+pub(crate) fn brotli(data: &[u8], quality: u32) -> Vec<u8> {
+	let mut encoder = brotli::CompressorWriter::new(Vec::with_capacity(data.len() / 2), 4096, quality.min(11), BROTLI_LG_WINDOW);
+	encoder.write_all(data).expect("writing to a Vec is infallible");
+	encoder.into_inner()
+}
@@ -0,0 +1,285 @@
+use std::{collections::BTreeSet, fs, io, path::Path, sync::Arc};
+
+use crate::{
+	error::{LocationBuilder, MessageBuilder, NoteBuilder},
+	plugin::{Filter, OnLoadResult, Plugin, PluginBuilder}
+};
+
+/// Environment variables to inline as `process.env.X` & `import.meta.env.X`, for use with
+/// [`BuildOptions::define_env`](crate::BuildOptions::define_env).
+///
+/// Only variables that are explicitly listed (or loaded from a `.env` file) are exposed, so secrets in the build
+/// environment can't leak into a bundle by accident.
+///
+/// ```no_run
+/// # use zsbuild::{BuildOptions, DefineEnv};
+/// let env = DefineEnv::new().dotenv(".env")?.vars(["API_URL", "NODE_ENV"]);
+/// let options = BuildOptions::new().entry_point("src/main.ts", "main.js").bundle(true).define_env(env);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefineEnv {
+	values: Vec<(String, String)>,
+	process_env: bool,
+	import_meta_env: bool,
+	warn_undefined: bool
+}
+
+impl Default for DefineEnv {
+	fn default() -> Self {
+		Self {
+			values: Vec::new(),
+			process_env: true,
+			import_meta_env: true,
+			warn_undefined: true
+		}
+	}
+}
+
+impl DefineEnv {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Exposes the environment variable `name` from the environment of the build process. Variables that aren't set
+	/// are left undefined.
+	pub fn var(self, name: impl AsRef<str>) -> Self {
+		let name = name.as_ref();
+		match std::env::var(name) {
+			Ok(value) => self.value(name, value),
+			Err(_) => self
+		}
+	}
+
+	pub fn vars<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, names: I) -> Self {
+		for name in names {
+			self = self.var(name);
+		}
+		self
+	}
+
+	/// Exposes `name` with the given value, overriding any previous value.
+	pub fn value(mut self, name: impl ToString, value: impl ToString) -> Self {
+		let name = name.to_string();
+		self.values.retain(|(n, _)| *n != name);
+		self.values.push((name, value.to_string()));
+		self
+	}
+
+	/// Exposes every variable defined in the `.env` file at `path`.
+	pub fn dotenv(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+		for (name, value) in parse_dotenv(&fs::read_to_string(path)?) {
+			self = self.value(name, value);
+		}
+		Ok(self)
+	}
+
+	/// Whether to define `process.env.X`. Defaults to `true`.
+	pub fn process_env(mut self, enable: bool) -> Self {
+		self.process_env = enable;
+		self
+	}
+
+	/// Whether to define `import.meta.env.X`. Defaults to `true`.
+	pub fn import_meta_env(mut self, enable: bool) -> Self {
+		self.import_meta_env = enable;
+		self
+	}
+
+	/// Whether to warn about references to variables that aren't defined. Defaults to `true`.
+	///
+	/// Only sources outside of `node_modules` are checked, and only against the variables of this `DefineEnv`, so
+	/// variables defined with [`define`](crate::BuildOptions::define) will also be reported.
+	pub fn warn_undefined(mut self, enable: bool) -> Self {
+		self.warn_undefined = enable;
+		self
+	}
+
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self.values.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+		self.values.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+	}
+
+	fn prefixes(&self) -> Vec<&'static str> {
+		let mut prefixes = Vec::new();
+		if self.process_env {
+			prefixes.push("process.env.");
+		}
+		if self.import_meta_env {
+			prefixes.push("import.meta.env.");
+		}
+		prefixes
+	}
+
+	/// The `(key, expression)` pairs to pass to esbuild's `define`.
+	pub(crate) fn defines(&self) -> Vec<(String, String)> {
+		let prefixes = self.prefixes();
+		self.values
+			.iter()
+			.flat_map(|(name, value)| {
+				let value = serde_json::to_string(value).expect("strings are always serializable");
+				prefixes.iter().map(move |prefix| (format!("{prefix}{name}"), value.clone()))
+			})
+			.collect()
+	}
+
+	pub(crate) fn undefined_plugin(&self) -> Option<UndefinedEnvPlugin> {
+		let prefixes = self.prefixes();
+		(self.warn_undefined && !prefixes.is_empty()).then(|| UndefinedEnvPlugin {
+			prefixes,
+			defined: Arc::new(self.values.iter().map(|(name, _)| name.clone()).collect())
+		})
+	}
+}
+
+/// Parses the contents of a `.env` file. Supports `export` prefixes, comments, and single, double & back quoted values
+/// (only double quoted values have escapes expanded).
+pub(crate) fn parse_dotenv(contents: &str) -> Vec<(String, String)> {
+	let mut values = Vec::new();
+	let mut lines = contents.lines();
+	while let Some(line) = lines.next() {
+		let line = line.trim();
+		let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let Some((name, value)) = line.split_once('=') else {
+			continue;
+		};
+		let name = name.trim();
+		let value = value.trim_start();
+		let value = match value.chars().next() {
+			Some(quote @ ('"' | '\'' | '`')) => {
+				// quoted values may span multiple lines
+				let mut raw = value[1..].to_string();
+				while !raw.contains(quote) {
+					let Some(next) = lines.next() else {
+						break;
+					};
+					raw.push('\n');
+					raw.push_str(next);
+				}
+				let raw = raw.split(quote).next().unwrap_or_default();
+				if quote == '"' {
+					raw.replace("\\n", "\n").replace("\\r", "\r").replace("\\t", "\t")
+				} else {
+					raw.to_string()
+				}
+			}
+			_ => value.split(" #").next().unwrap_or_default().trim_end().to_string()
+		};
+		if !name.is_empty() {
+			values.push((name.to_string(), value));
+		}
+	}
+	values
+}
+
+pub(crate) struct UndefinedEnvPlugin {
+	prefixes: Vec<&'static str>,
+	defined: Arc<BTreeSet<String>>
+}
+
+impl UndefinedEnvPlugin {
+	fn check(&self, path: &str, source: &str) -> Vec<MessageBuilder> {
+		let is_ident = |c: u8| c.is_ascii_alphanumeric() || c == b'_' || c == b'$';
+		let mut warnings = Vec::new();
+		for (line_number, line) in source.lines().enumerate() {
+			for &prefix in &self.prefixes {
+				let mut offset = 0;
+				while let Some(i) = line[offset..].find(prefix) {
+					let start = offset + i;
+					offset = start + prefix.len();
+					if start > 0 && (is_ident(line.as_bytes()[start - 1]) || line.as_bytes()[start - 1] == b'.') {
+						continue;
+					}
+					let name_len = line[offset..].bytes().take_while(|&c| is_ident(c)).count();
+					let name = &line[offset..offset + name_len];
+					if name.is_empty() || name.as_bytes()[0].is_ascii_digit() || self.defined.contains(name) {
+						continue;
+					}
+					let expr = &line[start..offset + name_len];
+					warnings.push(
+						MessageBuilder::new(format!("\"{expr}\" is used but not defined"))
+							.with_id("undefined-env")
+							.at(LocationBuilder::new(path, line_number as i64 + 1, start as i64, expr.len() as i64).with_line_text(line))
+							.with_note(NoteBuilder::new(format!("Add \"{name}\" to the `DefineEnv` of this build to define it")))
+					);
+				}
+			}
+		}
+		warnings
+	}
+}
+
+impl Plugin for UndefinedEnvPlugin {
+	fn name(&self) -> &str {
+		"zsbuild:undefined-env"
+	}
+
+	fn build(&self, builder: &mut PluginBuilder) {
+		let plugin = UndefinedEnvPlugin {
+			prefixes: self.prefixes.clone(),
+			defined: Arc::clone(&self.defined)
+		};
+		builder.on_load(Filter::new(r"\.[cm]?[jt]sx?$").with_namespace("file"), move |args| {
+			let path = args.path();
+			if path.split(['/', '\\']).any(|component| component == "node_modules") {
+				return OnLoadResult::pass();
+			}
+			let Ok(source) = fs::read_to_string(path) else {
+				return OnLoadResult::pass();
+			};
+			plugin
+				.check(path, &source)
+				.into_iter()
+				.fold(OnLoadResult::pass(), |result, warning| result.with_warning(warning))
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_dotenv() {
+		let values =
+			parse_dotenv("# comment\nexport API_URL=https://example.com # trailing\nEMPTY=\nQUOTED=\"a\\nb\" \nSINGLE='c\\nd'\nMULTI=\"line 1\nline 2\"\n");
+		assert_eq!(
+			values,
+			[
+				("API_URL".to_string(), "https://example.com".to_string()),
+				("EMPTY".to_string(), String::new()),
+				("QUOTED".to_string(), "a\nb".to_string()),
+				("SINGLE".to_string(), "c\\nd".to_string()),
+				("MULTI".to_string(), "line 1\nline 2".to_string())
+			]
+		);
+	}
+
+	#[test]
+	fn test_define_env() {
+		let env = DefineEnv::new()
+			.value("API_URL", "https://example.com")
+			.value("DEBUG", "1")
+			.import_meta_env(false);
+		assert_eq!(
+			env.defines(),
+			[
+				("process.env.API_URL".to_string(), "\"https://example.com\"".to_string()),
+				("process.env.DEBUG".to_string(), "\"1\"".to_string())
+			]
+		);
+
+		let plugin = env.undefined_plugin().unwrap();
+		let warnings = plugin.check("src/main.ts", "fetch(process.env.API_URL);\nif (process.env.SECRET_KEY) {}\nmyprocess.env.OTHER;\n");
+		assert_eq!(warnings.len(), 1);
+		assert_eq!(warnings[0].text(), "\"process.env.SECRET_KEY\" is used but not defined");
+		let location = warnings[0].location().unwrap();
+		assert_eq!((location.line(), location.column()), (2, 4));
+	}
+}
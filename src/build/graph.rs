@@ -0,0 +1,384 @@
+use std::{
+	collections::{BTreeMap, BTreeSet, VecDeque},
+	fmt::Write,
+	path::{Component, Path, PathBuf}
+};
+
+use super::{licenses::find_package, metafile::Metafile};
+
+/// One installation of a package in `node_modules`. See [`DependencyGraph::duplicate_packages`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageInstance {
+	version: Option<String>,
+	path: PathBuf,
+	inputs: Vec<String>
+}
+
+impl PackageInstance {
+	pub fn version(&self) -> Option<&str> {
+		self.version.as_deref()
+	}
+
+	/// The directory containing the package's `package.json`.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The inputs of the build that belong to this installation.
+	pub fn inputs(&self) -> &[String] {
+		&self.inputs
+	}
+}
+
+/// A package that is bundled more than once, at different versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicatePackage {
+	name: String,
+	instances: Vec<PackageInstance>
+}
+
+impl DuplicatePackage {
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn instances(&self) -> &[PackageInstance] {
+		&self.instances
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Edge {
+	to: usize,
+	dynamic: bool
+}
+
+/// The import graph of a build's inputs, built from its [metafile](crate::BuildOptions::metafile).
+///
+/// Input paths are relative to the working directory of the build, exactly as they appear in the metafile. External
+/// imports are not part of the graph.
+#[derive(Debug, Clone)]
+pub struct DependencyGraph {
+	inputs: Vec<String>,
+	indices: BTreeMap<String, usize>,
+	imports: Vec<Vec<Edge>>,
+	importers: Vec<Vec<Edge>>,
+	entry_points: Vec<usize>,
+	packages: BTreeMap<String, Vec<PackageInstance>>
+}
+
+impl DependencyGraph {
+	/// Builds the graph from `metafile`, looking up the `package.json` of `node_modules` inputs relative to the current
+	/// working directory.
+	pub fn from_metafile(metafile: &Metafile) -> Self {
+		let cwd = std::env::current_dir().unwrap_or_default();
+		Self::from_metafile_in(metafile, &cwd)
+	}
+
+	pub(crate) fn from_metafile_in(metafile: &Metafile, root: &Path) -> Self {
+		let inputs = metafile.inputs.keys().cloned().collect::<Vec<_>>();
+		let indices = inputs.iter().enumerate().map(|(i, input)| (input.clone(), i)).collect::<BTreeMap<_, _>>();
+
+		let mut imports = vec![Vec::new(); inputs.len()];
+		let mut importers = vec![Vec::new(); inputs.len()];
+		for (from, input) in metafile.inputs.values().enumerate() {
+			for import in input.imports.iter().filter(|import| !import.external) {
+				let Some(&to) = indices.get(&import.path) else {
+					continue;
+				};
+				let dynamic = import.is_dynamic();
+				imports[from].push(Edge { to, dynamic });
+				importers[to].push(Edge { to: from, dynamic });
+			}
+		}
+		for edges in imports.iter_mut().chain(importers.iter_mut()) {
+			edges.sort();
+			edges.dedup();
+		}
+
+		let entry_points = metafile
+			.outputs
+			.values()
+			.filter_map(|output| output.entry_point.as_ref())
+			.filter_map(|entry_point| indices.get(entry_point).copied())
+			.collect::<BTreeSet<_>>()
+			.into_iter()
+			.collect();
+
+		let mut packages: BTreeMap<String, Vec<PackageInstance>> = BTreeMap::new();
+		for input in &inputs {
+			let path = root.join(input);
+			if !path.components().any(|c| c == Component::Normal("node_modules".as_ref())) {
+				continue;
+			}
+			let Some((dir, package)) = find_package(&path) else {
+				continue;
+			};
+			let instances = packages.entry(package.name.unwrap_or_default()).or_default();
+			match instances.iter_mut().find(|instance| instance.path == dir) {
+				Some(instance) => instance.inputs.push(input.clone()),
+				None => instances.push(PackageInstance {
+					version: package.version,
+					path: dir,
+					inputs: vec![input.clone()]
+				})
+			}
+		}
+
+		Self {
+			inputs,
+			indices,
+			imports,
+			importers,
+			entry_points,
+			packages
+		}
+	}
+
+	pub fn inputs(&self) -> &[String] {
+		&self.inputs
+	}
+
+	pub fn contains(&self, input: &str) -> bool {
+		self.indices.contains_key(input)
+	}
+
+	pub fn entry_points(&self) -> impl Iterator<Item = &str> {
+		self.entry_points.iter().map(|&i| self.inputs[i].as_str())
+	}
+
+	/// The inputs directly imported by `input`, statically or dynamically.
+	pub fn imports(&self, input: &str) -> impl Iterator<Item = &str> {
+		let edges = self.indices.get(input).map(|&i| self.imports[i].as_slice()).unwrap_or_default();
+		edges.iter().map(|edge| self.inputs[edge.to].as_str())
+	}
+
+	/// The inputs that directly import `input`.
+	pub fn importers(&self, input: &str) -> impl Iterator<Item = &str> {
+		let edges = self.indices.get(input).map(|&i| self.importers[i].as_slice()).unwrap_or_default();
+		edges.iter().map(|edge| self.inputs[edge.to].as_str())
+	}
+
+	/// Packages installed more than once in `node_modules` at different versions, i.e. because two dependencies
+	/// require incompatible versions of the same package.
+	pub fn duplicate_packages(&self) -> Vec<DuplicatePackage> {
+		self.packages
+			.iter()
+			.filter(|(_, instances)| {
+				let versions = instances.iter().map(|instance| &instance.version).collect::<BTreeSet<_>>();
+				versions.len() > 1 || versions.contains(&None) && instances.len() > 1
+			})
+			.map(|(name, instances)| DuplicatePackage {
+				name: name.clone(),
+				instances: instances.clone()
+			})
+			.collect()
+	}
+
+	/// Finds the shortest chain of imports from `from` to `to`, including both ends.
+	pub fn import_path(&self, from: &str, to: &str) -> Option<Vec<&str>> {
+		let from = *self.indices.get(from)?;
+		self.shortest_path(&[from], *self.indices.get(to)?)
+	}
+
+	/// Finds the shortest chain of imports from any entry point to `to`, explaining why it is included in the build.
+	pub fn entry_import_path(&self, to: &str) -> Option<Vec<&str>> {
+		self.shortest_path(&self.entry_points, *self.indices.get(to)?)
+	}
+
+	fn shortest_path(&self, from: &[usize], to: usize) -> Option<Vec<&str>> {
+		let mut previous: Vec<Option<usize>> = vec![None; self.inputs.len()];
+		let mut visited = vec![false; self.inputs.len()];
+		let mut queue = VecDeque::new();
+		for &start in from {
+			visited[start] = true;
+			queue.push_back(start);
+		}
+		while let Some(node) = queue.pop_front() {
+			if node == to {
+				let mut path = vec![self.inputs[node].as_str()];
+				let mut node = node;
+				while let Some(prev) = previous[node] {
+					path.push(self.inputs[prev].as_str());
+					node = prev;
+				}
+				path.reverse();
+				return Some(path);
+			}
+			for edge in &self.imports[node] {
+				if !visited[edge.to] {
+					visited[edge.to] = true;
+					previous[edge.to] = Some(node);
+					queue.push_back(edge.to);
+				}
+			}
+		}
+		None
+	}
+
+	/// Groups of inputs that (transitively) import each other. Each cycle is sorted by path.
+	pub fn cycles(&self) -> Vec<Vec<&str>> {
+		// iterative version of Tarjan's strongly connected components algorithm, so deep import chains can't overflow
+		// the stack
+		const UNVISITED: usize = usize::MAX;
+		let len = self.inputs.len();
+		let mut index = vec![UNVISITED; len];
+		let mut low_link = vec![0; len];
+		let mut on_stack = vec![false; len];
+		let mut stack = Vec::new();
+		let mut next_index = 0;
+		let mut cycles = Vec::new();
+
+		for root in 0..len {
+			if index[root] != UNVISITED {
+				continue;
+			}
+			let mut work = vec![(root, 0)];
+			while let Some(&mut (node, ref mut edge)) = work.last_mut() {
+				if *edge == 0 && index[node] == UNVISITED {
+					index[node] = next_index;
+					low_link[node] = next_index;
+					next_index += 1;
+					stack.push(node);
+					on_stack[node] = true;
+				}
+				if let Some(&Edge { to, .. }) = self.imports[node].get(*edge) {
+					*edge += 1;
+					if index[to] == UNVISITED {
+						work.push((to, 0));
+					} else if on_stack[to] {
+						low_link[node] = low_link[node].min(index[to]);
+					}
+					continue;
+				}
+
+				work.pop();
+				if let Some(&(parent, _)) = work.last() {
+					low_link[parent] = low_link[parent].min(low_link[node]);
+				}
+				if low_link[node] == index[node] {
+					let mut component = Vec::new();
+					while let Some(member) = stack.pop() {
+						on_stack[member] = false;
+						component.push(member);
+						if member == node {
+							break;
+						}
+					}
+					let is_self_import = || self.imports[node].iter().any(|edge| edge.to == node);
+					if component.len() > 1 || is_self_import() {
+						let mut component = component.into_iter().map(|i| self.inputs[i].as_str()).collect::<Vec<_>>();
+						component.sort_unstable();
+						cycles.push(component);
+					}
+				}
+			}
+		}
+		cycles.sort_unstable();
+		cycles
+	}
+
+	/// Inputs that can't be reached from any entry point, i.e. files only pulled in by a plugin.
+	pub fn unreachable_inputs(&self) -> Vec<&str> {
+		let mut visited = vec![false; self.inputs.len()];
+		let mut stack = self.entry_points.clone();
+		while let Some(node) = stack.pop() {
+			if std::mem::replace(&mut visited[node], true) {
+				continue;
+			}
+			stack.extend(self.imports[node].iter().map(|edge| edge.to).filter(|&to| !visited[to]));
+		}
+		visited
+			.iter()
+			.enumerate()
+			.filter(|(_, visited)| !**visited)
+			.map(|(i, _)| self.inputs[i].as_str())
+			.collect()
+	}
+
+	/// Renders the graph in Graphviz's DOT format. Entry points are drawn as boxes and dynamic imports as dashed edges.
+	pub fn to_dot(&self) -> String {
+		let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+		let mut dot = String::from("digraph dependencies {\n\trankdir=LR;\n\tnode [shape=ellipse];\n");
+		for (i, input) in self.inputs.iter().enumerate() {
+			let shape = if self.entry_points.contains(&i) { " [shape=box]" } else { "" };
+			writeln!(dot, "\t\"{}\"{shape};", escape(input)).unwrap();
+		}
+		for (from, edges) in self.imports.iter().enumerate() {
+			for edge in edges {
+				let style = if edge.dynamic { " [style=dashed]" } else { "" };
+				writeln!(dot, "\t\"{}\" -> \"{}\"{style};", escape(&self.inputs[from]), escape(&self.inputs[edge.to])).unwrap();
+			}
+		}
+		dot.push_str("}\n");
+		dot
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_dependency_graph() {
+		let metafile = Metafile::from_json(
+			r#"{
+				"inputs": {
+					"test/node_modules/dup/index.js": { "bytes": 1 },
+					"test/node_modules/tiny-pkg/node_modules/dup/index.js": { "bytes": 1 },
+					"test/node_modules/tiny-pkg/lib/index.js": {
+						"bytes": 1,
+						"imports": [{ "path": "test/node_modules/tiny-pkg/node_modules/dup/index.js", "kind": "import-statement" }]
+					},
+					"src/a.ts": {
+						"bytes": 1,
+						"imports": [
+							{ "path": "src/b.ts", "kind": "import-statement" },
+							{ "path": "test/node_modules/dup/index.js", "kind": "import-statement" },
+							{ "path": "src/lazy.ts", "kind": "dynamic-import" },
+							{ "path": "react", "kind": "import-statement", "external": true }
+						]
+					},
+					"src/b.ts": { "bytes": 1, "imports": [{ "path": "src/c.ts", "kind": "import-statement" }] },
+					"src/c.ts": { "bytes": 1, "imports": [{ "path": "src/b.ts", "kind": "import-statement" }] },
+					"src/lazy.ts": { "bytes": 1, "imports": [{ "path": "test/node_modules/tiny-pkg/lib/index.js", "kind": "import-statement" }] },
+					"src/orphan.ts": { "bytes": 1 }
+				},
+				"outputs": {
+					"dist/a.js": { "bytes": 1, "entryPoint": "src/a.ts" }
+				}
+			}"#
+		)
+		.unwrap();
+		let graph = DependencyGraph::from_metafile_in(&metafile, Path::new(env!("CARGO_MANIFEST_DIR")));
+
+		assert_eq!(graph.entry_points().collect::<Vec<_>>(), ["src/a.ts"]);
+		assert_eq!(
+			graph.entry_import_path("test/node_modules/tiny-pkg/node_modules/dup/index.js").unwrap(),
+			[
+				"src/a.ts",
+				"src/lazy.ts",
+				"test/node_modules/tiny-pkg/lib/index.js",
+				"test/node_modules/tiny-pkg/node_modules/dup/index.js"
+			]
+		);
+		assert_eq!(graph.import_path("src/c.ts", "src/a.ts"), None);
+		assert_eq!(graph.cycles(), [["src/b.ts", "src/c.ts"]]);
+		assert_eq!(graph.unreachable_inputs(), ["src/orphan.ts"]);
+		assert_eq!(graph.importers("src/b.ts").collect::<Vec<_>>(), ["src/a.ts", "src/c.ts"]);
+
+		let duplicates = graph.duplicate_packages();
+		assert_eq!(duplicates.len(), 1);
+		assert_eq!(duplicates[0].name(), "dup");
+		let versions = duplicates[0]
+			.instances()
+			.iter()
+			.map(|instance| instance.version().unwrap())
+			.collect::<BTreeSet<_>>();
+		assert_eq!(versions, BTreeSet::from(["1.0.0", "2.0.0"]));
+
+		let dot = graph.to_dot();
+		assert!(dot.contains("\t\"src/a.ts\" [shape=box];\n"));
+		assert!(dot.contains("\t\"src/a.ts\" -> \"src/lazy.ts\" [style=dashed];\n"));
+	}
+}
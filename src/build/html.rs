@@ -0,0 +1,386 @@
+use std::{
+	collections::BTreeSet,
+	error::Error,
+	fmt::{self, Display},
+	fs, io,
+	ops::Range,
+	path::{Component, Path, PathBuf}
+};
+
+use super::{BuildResult, OwnedOutputFile, chunks::ChunkGraph, metafile::Metafile, options::BuildOptions};
+use crate::util;
+
+#[derive(Debug)]
+pub enum HtmlError {
+	Io(io::Error),
+	/// The build did not generate a metafile, which is needed to map references to their outputs.
+	MissingMetafile,
+	/// No output was generated for a script or stylesheet referenced by the HTML file.
+	MissingOutput(String)
+}
+
+impl Display for HtmlError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Io(e) => write!(f, "failed to read HTML entry point: {e}"),
+			Self::MissingMetafile => f.write_str("HTML entry points require the metafile to be enabled"),
+			Self::MissingOutput(path) => write!(f, "no output was generated for {path:?}")
+		}
+	}
+}
+
+impl Error for HtmlError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::Io(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReferenceKind {
+	Script,
+	Stylesheet
+}
+
+#[derive(Debug, Clone)]
+struct Reference {
+	kind: ReferenceKind,
+	/// Byte range of the `src`/`href` attribute value in the source.
+	value: Range<usize>,
+	/// The referenced file, relative to the working directory.
+	input: String
+}
+
+/// An HTML file acting as an entry point.
+///
+/// esbuild doesn't understand HTML, so this scans the file for `<script type="module" src="...">` and
+/// `<link rel="stylesheet" href="...">` tags referencing local files and adds those as entry points. After the build,
+/// [`render`](Self::render) rewrites the references to point to the hashed outputs, links the CSS imported by scripts
+/// and adds `<link rel="modulepreload">` tags for the chunks each script statically imports.
+///
+/// ```ignore
+/// let html = HtmlEntry::load("src/index.html")?;
+/// let result = build(&html.configure(BuildOptions::new().bundle(true).splitting(true).format(Format::EsModule).outdir("dist")));
+/// html.render(&result, "dist/index.html")?.write()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtmlEntry {
+	source: String,
+	references: Vec<Reference>
+}
+
+impl HtmlEntry {
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, HtmlError> {
+		let path = path.as_ref();
+		Ok(Self::new(path, fs::read_to_string(path).map_err(HtmlError::Io)?))
+	}
+
+	/// Creates an HTML entry point from its source. References are resolved relative to the directory of `path`.
+	pub fn new(path: impl AsRef<Path>, source: impl Into<String>) -> Self {
+		let source = source.into();
+		let dir = cwd_relative(path.as_ref()).parent().map(Path::to_path_buf).unwrap_or_default();
+		let references = scan_tags(&source)
+			.into_iter()
+			.filter(|tag| !tag.closing)
+			.filter_map(|tag| {
+				let attr = |name: &str| tag.attrs.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone());
+				let value = |range: &Range<usize>| &source[range.clone()];
+				let (kind, value_range) = if tag.name.eq_ignore_ascii_case("script") {
+					if !attr("type").map(|r| value(&r).eq_ignore_ascii_case("module")).unwrap_or(false) {
+						return None;
+					}
+					(ReferenceKind::Script, attr("src")?)
+				} else if tag.name.eq_ignore_ascii_case("link") {
+					if !attr("rel").map(|r| value(&r).eq_ignore_ascii_case("stylesheet")).unwrap_or(false) {
+						return None;
+					}
+					(ReferenceKind::Stylesheet, attr("href")?)
+				} else {
+					return None;
+				};
+				let input = resolve_local_url(&dir, value(&value_range))?;
+				Some(Reference { kind, value: value_range, input })
+			})
+			.collect();
+		Self { source, references }
+	}
+
+	/// The scripts & stylesheets referenced by the HTML file, relative to the working directory.
+	pub fn entry_points(&self) -> impl Iterator<Item = &str> {
+		self.references.iter().map(|r| r.input.as_str())
+	}
+
+	/// Adds the referenced scripts & stylesheets as entry points, and enables the metafile.
+	pub fn configure(&self, mut options: BuildOptions) -> BuildOptions {
+		for input in self.entry_points().collect::<BTreeSet<_>>() {
+			options = options.entry_point(format!("./{input}"), "");
+		}
+		options.metafile(true)
+	}
+
+	/// Rewrites the HTML for the outputs of `result`, returning it as an output file at `output_path`. References are
+	/// rewritten relative to `output_path`.
+	pub fn render(&self, result: &BuildResult, output_path: impl AsRef<Path>) -> Result<OwnedOutputFile, HtmlError> {
+		let metafile = result.parse_metafile().ok_or(HtmlError::MissingMetafile)?;
+		let output_path = output_path.as_ref();
+		Ok(OwnedOutputFile::new(output_path.to_string_lossy(), self.render_with_metafile(&metafile, output_path)?))
+	}
+
+	fn render_with_metafile(&self, metafile: &Metafile, output_path: &Path) -> Result<String, HtmlError> {
+		let out_dir = cwd_relative(output_path).parent().map(Path::to_path_buf).unwrap_or_default();
+		let graph = ChunkGraph::from_metafile(metafile);
+		let output_for = |input: &str| graph.entry(input).ok_or_else(|| HtmlError::MissingOutput(input.to_string()));
+
+		let mut urls = Vec::new();
+		let mut head = Vec::new();
+		let mut preloaded = BTreeSet::new();
+		for reference in &self.references {
+			let chunk = output_for(&reference.input)?;
+			urls.push((reference.value.clone(), relative_url(&out_dir, chunk.path())));
+			if reference.kind != ReferenceKind::Script {
+				continue;
+			}
+
+			if let Some(css) = chunk.css_bundle() {
+				head.push(format!(r#"<link rel="stylesheet" href="{}">"#, relative_url(&out_dir, css)));
+			}
+			// preload the whole static import graph of the script, so the browser doesn't discover it one level at a time
+			preloaded.insert(chunk.path());
+			for import in graph.transitive_imports(chunk.path()) {
+				if preloaded.insert(import) {
+					head.push(format!(r#"<link rel="modulepreload" href="{}">"#, relative_url(&out_dir, import)));
+				}
+			}
+		}
+
+		let mut replacements = urls.into_iter().map(|(range, url)| (range, escape_attr(&url))).collect::<Vec<_>>();
+		if !head.is_empty() {
+			let at = scan_tags(&self.source)
+				.iter()
+				.find(|tag| tag.closing && tag.name.eq_ignore_ascii_case("head"))
+				.map(|tag| tag.start)
+				.or_else(|| self.references.first().and_then(|r| self.source[..r.value.start].rfind('<')))
+				.unwrap_or(0);
+			replacements.push((at..at, head.iter().map(|tag| format!("{tag}\n")).collect()));
+		}
+
+		let mut html = self.source.clone();
+		replacements.sort_by_key(|(range, _)| range.start);
+		for (range, replacement) in replacements.into_iter().rev() {
+			html.replace_range(range, &replacement);
+		}
+		Ok(html)
+	}
+}
+
+/// Makes `path` relative to the working directory if it is absolute and inside it.
+fn cwd_relative(path: &Path) -> PathBuf {
+	let path = util::normalize_path(path);
+	match std::env::current_dir() {
+		Ok(cwd) if path.is_absolute() => path.strip_prefix(&cwd).map(Path::to_path_buf).unwrap_or(path),
+		_ => path
+	}
+}
+
+/// Resolves a `src`/`href` to a path relative to the working directory, or `None` if it doesn't point to a local file.
+fn resolve_local_url(dir: &Path, url: &str) -> Option<String> {
+	let url = url.trim();
+	if url.is_empty() || url.starts_with("//") || url.starts_with("data:") || url.contains("://") {
+		return None;
+	}
+	let url = &url[..url.find(['?', '#']).unwrap_or(url.len())];
+	// root-relative URLs are resolved against the working directory, like Vite does
+	let path = match url.strip_prefix('/') {
+		Some(url) => util::normalize_path(Path::new(url)),
+		None => util::normalize_path(&dir.join(url))
+	};
+	Some(path.to_string_lossy().replace('\\', "/"))
+}
+
+fn relative_url(from_dir: &Path, to: &str) -> String {
+	let to = cwd_relative(Path::new(to));
+	let from = from_dir.components().filter(|c| *c != Component::CurDir).collect::<Vec<_>>();
+	let to_components = to.components().collect::<Vec<_>>();
+	let common = from.iter().zip(&to_components).take_while(|(a, b)| a == b).count();
+	let mut parts = vec![".."; from.len() - common].into_iter().map(str::to_string).collect::<Vec<_>>();
+	parts.extend(to_components[common..].iter().map(|c| c.as_os_str().to_string_lossy().into_owned()));
+	parts.join("/")
+}
+
+fn escape_attr(value: &str) -> String {
+	value.replace('&', "&amp;").replace('"', "&quot;").replace('\'', "&#39;")
+}
+
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+	haystack
+		.as_bytes()
+		.windows(needle.len())
+		.position(|w| w.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+struct Tag<'s> {
+	/// Byte offset of the tag's `<`.
+	start: usize,
+	name: &'s str,
+	closing: bool,
+	/// Attribute names & the byte ranges of their values.
+	attrs: Vec<(&'s str, Range<usize>)>
+}
+
+/// A deliberately small HTML tokenizer that only extracts tags and the attributes of start tags, skipping comments and
+/// the contents of `<script>` & `<style>` elements.
+fn scan_tags(source: &str) -> Vec<Tag<'_>> {
+	let bytes = source.as_bytes();
+	let mut tags = Vec::new();
+	let mut i = 0;
+	while let Some(offset) = source[i..].find('<') {
+		let tag_start = i + offset;
+		i = tag_start + 1;
+		if source[i..].starts_with("!--") {
+			i = source[i..].find("-->").map(|end| i + end + 3).unwrap_or(source.len());
+			continue;
+		}
+		let closing = source[i..].starts_with('/');
+		let name_start = i + usize::from(closing);
+		let name_len = bytes[name_start..]
+			.iter()
+			.take_while(|b| b.is_ascii_alphanumeric() || **b == b'-')
+			.count();
+		if name_len == 0 {
+			continue;
+		}
+		let name = &source[name_start..name_start + name_len];
+		i = name_start + name_len;
+		if closing {
+			i = source[i..].find('>').map(|end| i + end + 1).unwrap_or(source.len());
+			tags.push(Tag {
+				start: tag_start,
+				name,
+				closing,
+				attrs: Vec::new()
+			});
+			continue;
+		}
+
+		let mut attrs = Vec::new();
+		loop {
+			while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+				i += 1;
+			}
+			if i >= bytes.len() || bytes[i] == b'>' {
+				i = (i + 1).min(bytes.len());
+				break;
+			}
+			let start = i;
+			while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'=' | b'>' | b'/') {
+				i += 1;
+			}
+			let attr_name = &source[start..i];
+			while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+				i += 1;
+			}
+			if i < bytes.len() && bytes[i] == b'=' {
+				i += 1;
+				while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+					i += 1;
+				}
+				let value = match bytes.get(i) {
+					Some(&quote @ (b'"' | b'\'')) => {
+						let end = bytes[i + 1..].iter().position(|b| *b == quote).map(|p| i + 1 + p).unwrap_or(bytes.len());
+						let value = i + 1..end;
+						i = (end + 1).min(bytes.len());
+						value
+					}
+					_ => {
+						let start = i;
+						while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+							i += 1;
+						}
+						start..i
+					}
+				};
+				attrs.push((attr_name, value));
+			} else if !attr_name.is_empty() {
+				attrs.push((attr_name, i..i));
+			} else {
+				i += 1;
+			}
+		}
+
+		// skip raw text so e.g. `"<link"` in an inline script isn't mistaken for a tag
+		if name.eq_ignore_ascii_case("script") || name.eq_ignore_ascii_case("style") {
+			let close = format!("</{name}");
+			i = find_ignore_ascii_case(&source[i..], &close).map(|end| i + end).unwrap_or(source.len());
+		}
+		tags.push(Tag {
+			start: tag_start,
+			name,
+			closing,
+			attrs
+		});
+	}
+	tags
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const HTML: &str = r#"<!doctype html>
+<html>
+<head>
+	<!-- <script type="module" src="./commented.ts"></script></head> -->
+	<link rel="stylesheet" href="./style.css">
+	<link rel="icon" href="./favicon.ico">
+</head>
+<body>
+	<script type="module" src="./main.ts"></script>
+	<script src="https://cdn.example.com/lib.js"></script>
+	<script>const html = '<link rel="stylesheet" href="./nope.css">';</script>
+</body>
+</html>"#;
+
+	#[test]
+	fn test_html_entry() {
+		let entry = HtmlEntry::new("src/index.html", HTML);
+		assert_eq!(entry.entry_points().collect::<Vec<_>>(), ["src/style.css", "src/main.ts"]);
+
+		let metafile = Metafile::from_json(
+			r#"{
+				"inputs": {},
+				"outputs": {
+					"dist/assets/main-AAAA.js": {
+						"bytes": 1,
+						"imports": [{ "path": "dist/assets/chunk-CCCC.js", "kind": "import-statement" }, { "path": "dist/assets/lazy-DDDD.js", "kind": "dynamic-import" }],
+						"entryPoint": "src/main.ts",
+						"cssBundle": "dist/assets/main-BBBB.css"
+					},
+					"dist/assets/chunk-CCCC.js": { "bytes": 1 },
+					"dist/assets/lazy-DDDD.js": { "bytes": 1, "entryPoint": "src/lazy.ts" },
+					"dist/assets/style-EEEE.css": { "bytes": 1, "entryPoint": "src/style.css" }
+				}
+			}"#
+		)
+		.unwrap();
+		let html = entry.render_with_metafile(&metafile, Path::new("dist/index.html")).unwrap();
+		assert!(html.contains(r#"<link rel="stylesheet" href="assets/style-EEEE.css">"#));
+		assert!(html.contains(r#"<script type="module" src="assets/main-AAAA.js">"#));
+		assert!(html.contains("<link rel=\"stylesheet\" href=\"assets/main-BBBB.css\">\n<link rel=\"modulepreload\" href=\"assets/chunk-CCCC.js\">\n</head>"));
+		assert!(!html.contains("lazy-DDDD"));
+		assert!(html.contains("./nope.css") && html.contains("./commented.ts"));
+
+		assert!(matches!(HtmlEntry::new("index.html", HTML).render_with_metafile(&metafile, Path::new("index.html")), Err(HtmlError::MissingOutput(_))));
+	}
+
+	#[test]
+	fn test_truncated_html() {
+		for source in ["<", "<div", "<div ", "<div a", "<div a=", "<div a=b", "<div a=\"b", "</div", "<!-- a"] {
+			HtmlEntry::new("index.html", source);
+		}
+		let tags = scan_tags("<p></p><script src=\"./main.ts");
+		assert_eq!(tags.iter().map(|tag| tag.name).collect::<Vec<_>>(), ["p", "p", "script"]);
+		assert_eq!(tags[2].attrs, [("src", 20..29)]);
+	}
+}
@@ -0,0 +1,50 @@
+use std::fmt;
+
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::util;
+
+/// The hash algorithm used for [Subresource Integrity](https://www.w3.org/TR/SRI/) digests.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SriAlgorithm {
+	Sha256,
+	#[default]
+	Sha384,
+	Sha512
+}
+
+impl SriAlgorithm {
+	/// Computes the integrity metadata for `data` (the algorithm name followed by the base64-encoded digest, e.g.
+	/// `sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO`), suitable for an `integrity`
+	/// attribute.
+	pub fn digest(&self, data: &[u8]) -> String {
+		let hash = match self {
+			Self::Sha256 => util::base64_encode(&Sha256::digest(data)),
+			Self::Sha384 => util::base64_encode(&Sha384::digest(data)),
+			Self::Sha512 => util::base64_encode(&Sha512::digest(data))
+		};
+		format!("{self}-{hash}")
+	}
+}
+
+impl fmt::Display for SriAlgorithm {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Sha256 => "sha256",
+			Self::Sha384 => "sha384",
+			Self::Sha512 => "sha512"
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_sri_digest() {
+		// https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity#tools_for_generating_sri_hashes
+		assert_eq!(SriAlgorithm::Sha384.digest(b"alert('Hello, world.');"), "sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO");
+		assert_eq!(SriAlgorithm::Sha256.digest(b""), "sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=");
+	}
+}
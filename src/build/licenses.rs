@@ -0,0 +1,231 @@
+use std::{
+	collections::BTreeMap,
+	fmt::Write,
+	fs,
+	path::{Component, Path, PathBuf}
+};
+
+use serde::Deserialize;
+
+use super::{OwnedOutputFile, metafile::Metafile};
+
+/// A third-party package that contributed at least one input to a build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageLicense {
+	name: String,
+	version: Option<String>,
+	license: Option<String>,
+	license_text: Option<String>,
+	path: PathBuf
+}
+
+impl PackageLicense {
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn version(&self) -> Option<&str> {
+		self.version.as_deref()
+	}
+
+	/// The SPDX expression from the `license` field of the package's `package.json`, e.g. `MIT` or
+	/// `(MIT OR Apache-2.0)`.
+	pub fn license(&self) -> Option<&str> {
+		self.license.as_deref()
+	}
+
+	/// The contents of the package's `LICENSE`, `LICENCE` or `COPYING` file, if it has one.
+	pub fn license_text(&self) -> Option<&str> {
+		self.license_text.as_deref()
+	}
+
+	/// The directory containing the package's `package.json`.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LicenseField {
+	Spdx(String),
+	Object {
+		#[serde(rename = "type")]
+		kind: String
+	}
+}
+
+impl LicenseField {
+	fn into_string(self) -> String {
+		match self {
+			Self::Spdx(spdx) => spdx,
+			Self::Object { kind } => kind
+		}
+	}
+}
+
+#[derive(Deserialize)]
+pub(super) struct PackageJson {
+	pub(super) name: Option<String>,
+	pub(super) version: Option<String>,
+	license: Option<LicenseField>,
+	/// Deprecated, but still found in older packages.
+	licenses: Option<Vec<LicenseField>>
+}
+
+/// The licenses of the `node_modules` packages bundled into a build.
+///
+/// esbuild's [`legal_comments`](crate::BuildOptions::legal_comments) only preserves license comments that are present
+/// in the source code, which many packages don't include. This instead collects the `license` field and license file of
+/// each bundled package, which can be shipped as a `THIRD_PARTY_LICENSES` file alongside the outputs.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LicenseReport {
+	packages: Vec<PackageLicense>
+}
+
+impl LicenseReport {
+	/// The conventional name of the file generated by [`to_output_file`](Self::to_output_file).
+	pub const FILE_NAME: &'static str = "THIRD_PARTY_LICENSES";
+
+	/// Collects the packages of all inputs in `metafile`, resolving paths relative to the current working directory.
+	pub fn from_metafile(metafile: &Metafile) -> Self {
+		let cwd = std::env::current_dir().unwrap_or_default();
+		Self::from_inputs(metafile.inputs.keys().map(String::as_str), &cwd)
+	}
+
+	pub(crate) fn from_inputs<'a>(inputs: impl IntoIterator<Item = &'a str>, root: &Path) -> Self {
+		let mut packages = BTreeMap::new();
+		for input in inputs {
+			// inputs from plugins are namespaced, i.e. `virtual:foo`
+			if input.contains(':') && !Path::new(input).is_absolute() {
+				continue;
+			}
+			let path = root.join(input);
+			if !path.components().any(|c| c == Component::Normal("node_modules".as_ref())) {
+				continue;
+			}
+			if let Some((dir, package)) = find_package(&path) {
+				packages.entry(dir).or_insert(package);
+			}
+		}
+
+		let mut packages = packages
+			.into_iter()
+			.map(|(path, package)| PackageLicense {
+				license_text: find_license_file(&path),
+				name: package.name.unwrap_or_default(),
+				version: package.version,
+				license: package.license.map(LicenseField::into_string).or_else(|| {
+					let licenses = package.licenses?.into_iter().map(LicenseField::into_string).collect::<Vec<_>>();
+					match licenses.len() {
+						0 => None,
+						1 => licenses.into_iter().next(),
+						_ => Some(format!("({})", licenses.join(" OR ")))
+					}
+				}),
+				path
+			})
+			.collect::<Vec<_>>();
+		packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+		Self { packages }
+	}
+
+	pub fn packages(&self) -> &[PackageLicense] {
+		&self.packages
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.packages.is_empty()
+	}
+
+	/// Packages that have neither a `license` field nor a license file.
+	pub fn unlicensed(&self) -> impl Iterator<Item = &PackageLicense> {
+		self.packages
+			.iter()
+			.filter(|package| package.license.is_none() && package.license_text.is_none())
+	}
+
+	/// Renders the report as plain text, with one section per package.
+	pub fn to_text(&self) -> String {
+		let mut text = String::new();
+		for (i, package) in self.packages.iter().enumerate() {
+			if i != 0 {
+				text.push_str("\n--------------------------------------------------------------------------------\n\n");
+			}
+			match &package.version {
+				Some(version) => writeln!(text, "{}@{version}", package.name),
+				None => writeln!(text, "{}", package.name)
+			}
+			.unwrap();
+			writeln!(text, "License: {}", package.license.as_deref().unwrap_or("UNKNOWN")).unwrap();
+			if let Some(license_text) = &package.license_text {
+				text.push('\n');
+				text.push_str(license_text.trim_end());
+				text.push('\n');
+			}
+		}
+		text
+	}
+
+	/// Creates a [`FILE_NAME`](Self::FILE_NAME) output file in `dir` containing the [text](Self::to_text) of the
+	/// report.
+	pub fn to_output_file(&self, dir: impl AsRef<Path>) -> OwnedOutputFile {
+		OwnedOutputFile::new(dir.as_ref().join(Self::FILE_NAME).to_string_lossy(), self.to_text())
+	}
+}
+
+/// Finds the `package.json` of the package containing `path`. Nested `package.json` files without a `name` (i.e. ones
+/// that only set `"type": "module"` for a subdirectory) are skipped.
+pub(super) fn find_package(path: &Path) -> Option<(PathBuf, PackageJson)> {
+	for dir in path.ancestors().skip(1) {
+		if dir.file_name().map_or(true, |name| name == "node_modules") {
+			return None;
+		}
+		let Ok(contents) = fs::read_to_string(dir.join("package.json")) else {
+			continue;
+		};
+		match serde_json::from_str::<PackageJson>(&contents) {
+			Ok(package) if package.name.is_some() => return Some((dir.to_path_buf(), package)),
+			_ => continue
+		}
+	}
+	None
+}
+
+fn find_license_file(dir: &Path) -> Option<String> {
+	let mut candidates = fs::read_dir(dir)
+		.ok()?
+		.filter_map(|entry| entry.ok())
+		.filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+		.map(|entry| entry.path())
+		.filter(|path| {
+			let name = path.file_name().unwrap_or_default().to_string_lossy().to_ascii_uppercase();
+			name.starts_with("LICENSE") || name.starts_with("LICENCE") || name.starts_with("COPYING")
+		})
+		.collect::<Vec<_>>();
+	candidates.sort();
+	candidates.into_iter().find_map(|path| fs::read_to_string(path).ok())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_license_report() {
+		let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+		let report = LicenseReport::from_inputs(["test/main.js", "test/node_modules/tiny-pkg/lib/index.js", "virtual:foo"], root);
+		assert_eq!(report.packages().len(), 1);
+
+		let package = &report.packages()[0];
+		assert_eq!(package.name(), "tiny-pkg");
+		assert_eq!(package.version(), Some("1.2.3"));
+		assert_eq!(package.license(), Some("MIT"));
+		assert!(package.license_text().unwrap().starts_with("MIT License"));
+		assert_eq!(report.unlicensed().count(), 0);
+
+		let text = report.to_text();
+		assert!(text.starts_with("tiny-pkg@1.2.3\nLicense: MIT\n\nMIT License\n"));
+		assert_eq!(report.to_output_file("dist").path(), Path::new("dist").join("THIRD_PARTY_LICENSES").to_string_lossy());
+	}
+}
@@ -0,0 +1,182 @@
+use std::{
+	collections::{BTreeMap, btree_map},
+	fmt
+};
+
+use serde::{
+	Deserialize, Serialize, Serializer,
+	de::{self, Deserializer, Visitor}
+};
+
+use crate::{sys, util};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MangleCacheEntry {
+	/// The property was renamed to the given name.
+	Mangled(String),
+	/// The property matched [`reserve_props`](crate::BuildOptions::reserve_props) (or was otherwise kept as-is) and
+	/// must not be renamed in future builds. This is `false` in esbuild's JSON representation.
+	Reserved
+}
+
+impl Serialize for MangleCacheEntry {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Self::Mangled(name) => serializer.serialize_str(name),
+			Self::Reserved => serializer.serialize_bool(false)
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for MangleCacheEntry {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct EntryVisitor;
+
+		impl<'de> Visitor<'de> for EntryVisitor {
+			type Value = MangleCacheEntry;
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("a mangled property name or `false`")
+			}
+
+			fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+				Ok(MangleCacheEntry::Mangled(v.to_string()))
+			}
+
+			fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+				Ok(MangleCacheEntry::Mangled(v))
+			}
+
+			fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+				if v {
+					Err(E::invalid_value(de::Unexpected::Bool(true), &self))
+				} else {
+					Ok(MangleCacheEntry::Reserved)
+				}
+			}
+		}
+
+		deserializer.deserialize_any(EntryVisitor)
+	}
+}
+
+/// The property mangling cache produced by a build with [`mangle_props`](crate::BuildOptions::mangle_props).
+///
+/// Feed it back into subsequent builds with [`mangle_cache`](crate::BuildOptions::mangle_cache) so properties are
+/// renamed consistently across builds. It (de)serializes to the same JSON format esbuild uses, so a cache written by
+/// the esbuild CLI can be loaded here and vice versa.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MangleCache(BTreeMap<String, MangleCacheEntry>);
+
+impl MangleCache {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn from_json(json: &str) -> serde_json::Result<Self> {
+		serde_json::from_str(json)
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).expect("mangle cache is always serializable")
+	}
+
+	pub fn get(&self, property: &str) -> Option<&MangleCacheEntry> {
+		self.0.get(property)
+	}
+
+	pub fn insert(&mut self, property: impl Into<String>, entry: MangleCacheEntry) -> Option<MangleCacheEntry> {
+		self.0.insert(property.into(), entry)
+	}
+
+	pub fn remove(&mut self, property: &str) -> Option<MangleCacheEntry> {
+		self.0.remove(property)
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn iter(&self) -> btree_map::Iter<'_, String, MangleCacheEntry> {
+		self.0.iter()
+	}
+
+	pub(crate) unsafe fn from_ffi(entries: *const sys::MangleCacheEntry, len: usize) -> Self {
+		let entries = util::slice_from_raw_parts_or_empty(entries, len);
+		Self(
+			entries
+				.iter()
+				.map(|entry| {
+					let key = util::as_str(entry.key, entry.key_len).to_string();
+					let value = match util::as_str_opt(entry.value, entry.value_len) {
+						Some(name) => MangleCacheEntry::Mangled(name.to_string()),
+						None => MangleCacheEntry::Reserved
+					};
+					(key, value)
+				})
+				.collect()
+		)
+	}
+
+	/// The returned entries borrow from `self`.
+	pub(crate) fn as_ffi(&self) -> Vec<sys::MangleCacheEntry> {
+		self.0
+			.iter()
+			.map(|(key, value)| {
+				let (value, value_len) = match value {
+					MangleCacheEntry::Mangled(name) => (name.as_ptr().cast(), name.len()),
+					MangleCacheEntry::Reserved => (std::ptr::null(), 0)
+				};
+				sys::MangleCacheEntry {
+					key: key.as_ptr().cast(),
+					key_len: key.len(),
+					value,
+					value_len
+				}
+			})
+			.collect()
+	}
+}
+
+impl FromIterator<(String, MangleCacheEntry)> for MangleCache {
+	fn from_iter<T: IntoIterator<Item = (String, MangleCacheEntry)>>(iter: T) -> Self {
+		Self(iter.into_iter().collect())
+	}
+}
+
+impl IntoIterator for MangleCache {
+	type Item = (String, MangleCacheEntry);
+	type IntoIter = btree_map::IntoIter<String, MangleCacheEntry>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
+
+impl<'a> IntoIterator for &'a MangleCache {
+	type Item = (&'a String, &'a MangleCacheEntry);
+	type IntoIter = btree_map::Iter<'a, String, MangleCacheEntry>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_mangle_cache_json_roundtrip() {
+		let cache = MangleCache::from_json(r#"{ "foo_": "a", "bar_": false }"#).unwrap();
+		assert_eq!(cache.get("foo_"), Some(&MangleCacheEntry::Mangled("a".to_string())));
+		assert_eq!(cache.get("bar_"), Some(&MangleCacheEntry::Reserved));
+		assert_eq!(MangleCache::from_json(&cache.to_json()).unwrap(), cache);
+		assert!(MangleCache::from_json(r#"{ "foo_": true }"#).is_err());
+	}
+}
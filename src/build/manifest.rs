@@ -0,0 +1,232 @@
+use std::{
+	collections::{BTreeMap, BTreeSet, btree_map},
+	path::Path
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+	OutputFile, OwnedOutputFile,
+	chunks::{Chunk, ChunkGraph},
+	integrity::SriAlgorithm,
+	metafile::Metafile
+};
+use crate::util;
+
+/// A chunk in an [`AssetManifest`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestChunk {
+	/// The output file of this chunk.
+	pub file: String,
+	/// The input this chunk was generated from, if it is an entry point.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub src: Option<String>,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub is_entry: bool,
+	/// Whether the chunk is the target of a dynamic `import()`.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub is_dynamic_entry: bool,
+	/// CSS files generated for this chunk.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub css: Vec<String>,
+	/// Keys of the chunks this chunk statically imports.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub imports: Vec<String>,
+	/// Keys of the chunks this chunk dynamically imports.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub dynamic_imports: Vec<String>,
+	/// [Subresource Integrity](https://www.w3.org/TR/SRI/) digests of `file` and each of `css`, keyed by path. Only
+	/// present if computed with [`AssetManifest::with_integrity`].
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub integrity: BTreeMap<String, String>
+}
+
+/// Maps entry points to their (hashed) output files, along with their CSS and the chunks they import, in the same
+/// format as Vite's `manifest.json`. This lets a backend render the right `<script>`, `<link rel="stylesheet">` and
+/// `<link rel="modulepreload">` tags for an entry point.
+///
+/// Entry points are keyed by their input path (e.g. `src/main.ts`), and shared chunks by their output file name
+/// prefixed with `_` (e.g. `_chunk-UVJSD4ZU.js`).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AssetManifest(BTreeMap<String, ManifestChunk>);
+
+impl AssetManifest {
+	pub fn from_metafile(metafile: &Metafile) -> Self {
+		Self::from_chunk_graph(&ChunkGraph::from_metafile(metafile))
+	}
+
+	pub fn from_chunk_graph(graph: &ChunkGraph) -> Self {
+		// CSS bundles are listed under the JS chunk that generated them rather than as chunks of their own.
+		let css_bundles = graph.iter().filter_map(Chunk::css_bundle).collect::<BTreeSet<_>>();
+		let dynamic_targets = graph
+			.iter()
+			.flat_map(|chunk| chunk.dynamic_imports())
+			.map(String::as_str)
+			.collect::<BTreeSet<_>>();
+
+		let keys = graph
+			.iter()
+			.filter(|chunk| !css_bundles.contains(chunk.path()))
+			.map(|chunk| {
+				let key = match chunk.entry_point() {
+					Some(entry_point) => entry_point.to_string(),
+					None => format!("_{}", Path::new(chunk.path()).file_name().map(|f| f.to_string_lossy()).unwrap_or_default())
+				};
+				(chunk.path(), key)
+			})
+			.collect::<BTreeMap<_, _>>();
+
+		let mut chunks = BTreeMap::new();
+		for (path, key) in &keys {
+			let chunk = graph.get(path).expect("keys are taken from the graph");
+			let imported_keys = |imports: &[String]| imports.iter().filter_map(|import| keys.get(import.as_str()).cloned()).collect::<Vec<_>>();
+			let is_dynamic_entry = dynamic_targets.contains(path);
+			chunks.insert(
+				key.clone(),
+				ManifestChunk {
+					file: path.to_string(),
+					src: chunk.entry_point().map(str::to_string),
+					is_entry: chunk.entry_point().is_some() && !is_dynamic_entry,
+					is_dynamic_entry,
+					css: chunk.css_bundle().iter().map(|css| css.to_string()).collect(),
+					imports: imported_keys(chunk.imports()),
+					dynamic_imports: imported_keys(chunk.dynamic_imports()),
+					integrity: BTreeMap::new()
+				}
+			);
+		}
+		Self(chunks)
+	}
+
+	/// Makes all output paths relative to `dir`, typically the [`outdir`](crate::BuildOptions::outdir) of the build.
+	/// Paths that are not inside `dir` are left as-is.
+	pub fn relative_to(mut self, dir: impl AsRef<Path>) -> Self {
+		let dir = dir.as_ref();
+		let strip = |path: &mut String| {
+			if let Ok(relative) = Path::new(path.as_str()).strip_prefix(dir) {
+				*path = relative.to_string_lossy().replace('\\', "/");
+			}
+		};
+		for chunk in self.0.values_mut() {
+			strip(&mut chunk.file);
+			chunk.css.iter_mut().for_each(strip);
+			chunk.integrity = std::mem::take(&mut chunk.integrity)
+				.into_iter()
+				.map(|(mut path, digest)| {
+					strip(&mut path);
+					(path, digest)
+				})
+				.collect();
+		}
+		self
+	}
+
+	/// Computes [Subresource Integrity](https://www.w3.org/TR/SRI/) digests for the files of each chunk from the
+	/// contents of `outputs`, typically [`BuildResult::outputs`](crate::BuildResult::outputs). Call this before
+	/// [`relative_to`](Self::relative_to), while the manifest's paths are still relative to the working directory.
+	pub fn with_integrity(mut self, outputs: &[OutputFile<'_>], algorithm: SriAlgorithm) -> Self {
+		// output paths are absolute, whereas manifest paths are relative to the working directory
+		let cwd = std::env::current_dir().unwrap_or_default();
+		let resolve = |path: &str| util::normalize_path(&cwd.join(path));
+		let outputs = outputs.iter().map(|output| (resolve(output.path()), output)).collect::<BTreeMap<_, _>>();
+		for chunk in self.0.values_mut() {
+			for file in std::iter::once(&chunk.file).chain(&chunk.css) {
+				if let Some(output) = outputs.get(&resolve(file)) {
+					chunk.integrity.insert(file.clone(), output.integrity(algorithm));
+				}
+			}
+		}
+		self
+	}
+
+	pub fn from_json(json: &str) -> serde_json::Result<Self> {
+		serde_json::from_str(json)
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).expect("asset manifest is always serializable")
+	}
+
+	/// Creates an output file at `path` containing the manifest as JSON.
+	pub fn to_output_file(&self, path: impl ToString) -> OwnedOutputFile {
+		OwnedOutputFile::new(path, self.to_json())
+	}
+
+	pub fn get(&self, key: &str) -> Option<&ManifestChunk> {
+		self.0.get(key)
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn iter(&self) -> btree_map::Iter<'_, String, ManifestChunk> {
+		self.0.iter()
+	}
+
+	/// Returns the entry point chunks (excluding dynamic entries).
+	pub fn entries(&self) -> impl Iterator<Item = (&String, &ManifestChunk)> {
+		self.0.iter().filter(|(_, chunk)| chunk.is_entry)
+	}
+}
+
+impl<'a> IntoIterator for &'a AssetManifest {
+	type Item = (&'a String, &'a ManifestChunk);
+	type IntoIter = btree_map::Iter<'a, String, ManifestChunk>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_asset_manifest() {
+		let metafile = Metafile::from_json(
+			r#"{
+				"inputs": {},
+				"outputs": {
+					"dist/main-AAAA.js": {
+						"bytes": 1,
+						"imports": [
+							{ "path": "dist/chunk-CCCC.js", "kind": "import-statement" },
+							{ "path": "dist/lazy-DDDD.js", "kind": "dynamic-import" },
+							{ "path": "react", "kind": "import-statement", "external": true }
+						],
+						"entryPoint": "src/main.ts",
+						"cssBundle": "dist/main-BBBB.css"
+					},
+					"dist/main-AAAA.js.map": { "bytes": 1 },
+					"dist/main-BBBB.css": { "bytes": 1 },
+					"dist/chunk-CCCC.js": { "bytes": 1 },
+					"dist/lazy-DDDD.js": { "bytes": 1, "imports": [{ "path": "dist/chunk-CCCC.js", "kind": "import-statement" }], "entryPoint": "src/lazy.ts" }
+				}
+			}"#
+		)
+		.unwrap();
+		let manifest = AssetManifest::from_metafile(&metafile).relative_to("dist");
+		assert_eq!(manifest.len(), 3);
+
+		let main = manifest.get("src/main.ts").unwrap();
+		assert!(main.is_entry && !main.is_dynamic_entry);
+		assert_eq!(main.file, "main-AAAA.js");
+		assert_eq!(main.css, ["main-BBBB.css"]);
+		assert_eq!(main.imports, ["_chunk-CCCC.js"]);
+		assert_eq!(main.dynamic_imports, ["src/lazy.ts"]);
+
+		let lazy = manifest.get("src/lazy.ts").unwrap();
+		assert!(!lazy.is_entry && lazy.is_dynamic_entry);
+		assert_eq!(manifest.entries().count(), 1);
+
+		assert_eq!(AssetManifest::from_json(&manifest.to_json()).unwrap(), manifest);
+	}
+}
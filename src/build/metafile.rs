@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The parsed metafile of a build with [`metafile`](crate::BuildOptions::metafile) enabled.
+///
+/// See <https://esbuild.github.io/api/#metafile> for the meaning of each field. Paths are relative to the working
+/// directory of the build.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metafile {
+	pub inputs: BTreeMap<String, MetafileInput>,
+	pub outputs: BTreeMap<String, MetafileOutput>
+}
+
+impl Metafile {
+	pub fn from_json(json: &str) -> serde_json::Result<Self> {
+		serde_json::from_str(json)
+	}
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetafileInput {
+	pub bytes: u64,
+	#[serde(default)]
+	pub imports: Vec<MetafileImport>,
+	/// `"cjs"` or `"esm"` for JavaScript inputs.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub format: Option<String>,
+	/// Import attributes, i.e. `with { type: "json" }`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub with: Option<BTreeMap<String, String>>
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetafileImport {
+	pub path: String,
+	/// The kind of import, i.e. `"import-statement"`, `"dynamic-import"`, `"import-rule"`, etc.
+	pub kind: String,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub external: bool,
+	/// The path as written in the source, if it differs from `path`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub original: Option<String>
+}
+
+impl MetafileImport {
+	pub fn is_dynamic(&self) -> bool {
+		self.kind == "dynamic-import"
+	}
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetafileOutput {
+	pub bytes: u64,
+	#[serde(default)]
+	pub inputs: BTreeMap<String, MetafileOutputInput>,
+	#[serde(default)]
+	pub imports: Vec<MetafileImport>,
+	#[serde(default)]
+	pub exports: Vec<String>,
+	/// The input this output was generated from, if it is an entry point.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub entry_point: Option<String>,
+	/// The CSS file generated alongside a JavaScript entry point that imports CSS.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub css_bundle: Option<String>
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetafileOutputInput {
+	pub bytes_in_output: u64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_metafile() {
+		let metafile = Metafile::from_json(
+			r#"{
+				"inputs": {
+					"src/index.ts": { "bytes": 120, "imports": [{ "path": "src/lazy.ts", "kind": "dynamic-import", "original": "./lazy" }], "format": "esm" },
+					"src/lazy.ts": { "bytes": 30, "imports": [] }
+				},
+				"outputs": {
+					"dist/index.js": {
+						"bytes": 200,
+						"inputs": { "src/index.ts": { "bytesInOutput": 100 } },
+						"imports": [{ "path": "dist/lazy.js", "kind": "dynamic-import" }],
+						"exports": [],
+						"entryPoint": "src/index.ts",
+						"cssBundle": "dist/index.css"
+					}
+				}
+			}"#
+		)
+		.unwrap();
+		assert!(metafile.inputs["src/index.ts"].imports[0].is_dynamic());
+		let output = &metafile.outputs["dist/index.js"];
+		assert_eq!(output.entry_point.as_deref(), Some("src/index.ts"));
+		assert_eq!(output.css_bundle.as_deref(), Some("dist/index.css"));
+		assert_eq!(output.inputs["src/index.ts"].bytes_in_output, 100);
+	}
+}
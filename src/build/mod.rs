@@ -1,14 +1,60 @@
-use std::{marker::PhantomData, slice, str::Utf8Error};
+use std::{
+	fs, io,
+	marker::PhantomData,
+	path::Path,
+	slice,
+	str::Utf8Error,
+	sync::{Arc, Mutex},
+	time::Duration
+};
 
-use crate::{error::Message, sys, util};
+use self::{
+	chunks::ChunkGraph,
+	graph::DependencyGraph,
+	integrity::SriAlgorithm,
+	licenses::LicenseReport,
+	manifest::AssetManifest,
+	metafile::Metafile,
+	precompress::Precompress,
+	sourcemap::{SourceMapConsumer, SourceMapError},
+	stats::BuildStats
+};
+use crate::{error::Message, plugin::PluginTiming, sys, util};
 
 pub mod r#async;
+pub mod budget;
+pub mod cancel;
+pub mod chunks;
+mod compress;
+pub mod define;
+pub mod graph;
+pub mod html;
+pub mod integrity;
+pub mod licenses;
+pub mod mangle;
+pub mod manifest;
+pub mod metafile;
+pub mod naming;
 pub mod options;
+pub mod precompress;
+pub mod resolve;
+pub mod sourcemap;
+pub mod stats;
+pub mod target;
+pub mod tsconfig;
 
 #[repr(transparent)]
 pub struct OutputFile<'s>(sys::OutputFile, PhantomData<&'s ()>);
 
 impl<'s> OutputFile<'s> {
+	pub fn path(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.path, self.0.path_len) }
+	}
+
+	pub fn hash(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.hash, self.0.hash_len) }
+	}
+
 	pub fn contents(&self) -> &[u8] {
 		unsafe { slice::from_raw_parts(self.0.contents.cast(), self.0.contents_len) }
 	}
@@ -20,6 +66,78 @@ impl<'s> OutputFile<'s> {
 				.unwrap_or(&[])
 		)
 	}
+
+	/// Computes the [Subresource Integrity](https://www.w3.org/TR/SRI/) digest of the output, for use in an
+	/// `integrity` attribute. This is unrelated to [`hash`](Self::hash), which is esbuild's internal content hash.
+	pub fn integrity(&self, algorithm: SriAlgorithm) -> String {
+		algorithm.digest(self.contents())
+	}
+
+	pub fn to_owned(&self) -> OwnedOutputFile {
+		OwnedOutputFile {
+			path: self.path().to_string(),
+			hash: self.hash().to_string(),
+			contents: self.contents().to_vec()
+		}
+	}
+
+	/// Loads the source map for this output, whether it is inlined into the file or a separate `.map` file (looked up
+	/// in `outputs`, then on disk).
+	pub fn source_map(&self, outputs: &[OutputFile<'_>]) -> Result<SourceMapConsumer, SourceMapError> {
+		SourceMapConsumer::locate(self.path(), self.contents(), |path| outputs.iter().find(|o| o.path() == path).map(|o| o.contents()))
+	}
+}
+
+/// An [`OutputFile`] that outlives its [`BuildResult`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedOutputFile {
+	path: String,
+	hash: String,
+	contents: Vec<u8>
+}
+
+impl OwnedOutputFile {
+	/// Creates an output file with no hash, e.g. to feed into a `PluginTester` in tests.
+	pub fn new(path: impl ToString, contents: impl Into<Vec<u8>>) -> Self {
+		Self {
+			path: path.to_string(),
+			hash: String::new(),
+			contents: contents.into()
+		}
+	}
+
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	pub fn hash(&self) -> &str {
+		&self.hash
+	}
+
+	pub fn contents(&self) -> &[u8] {
+		&self.contents
+	}
+
+	pub fn contents_str(&self) -> Result<&str, Utf8Error> {
+		std::str::from_utf8(&self.contents)
+	}
+
+	/// Computes the [Subresource Integrity](https://www.w3.org/TR/SRI/) digest of the output.
+	pub fn integrity(&self, algorithm: SriAlgorithm) -> String {
+		algorithm.digest(&self.contents)
+	}
+
+	pub fn source_map(&self, outputs: &[OwnedOutputFile]) -> Result<SourceMapConsumer, SourceMapError> {
+		SourceMapConsumer::locate(self.path(), self.contents(), |path| outputs.iter().find(|o| o.path() == path).map(|o| o.contents()))
+	}
+
+	/// Writes the file to its path, creating parent directories as needed.
+	pub fn write(&self) -> io::Result<()> {
+		if let Some(parent) = Path::new(&self.path).parent() {
+			fs::create_dir_all(parent)?;
+		}
+		fs::write(&self.path, &self.contents)
+	}
 }
 
 pub struct BuildResult(*mut sys::BuildResult);
@@ -40,6 +158,14 @@ impl BuildResult {
 		self.inner().errors_len > 0
 	}
 
+	/// Returns `true` if this build was cancelled before it could finish, either via a [`CancellationToken`], a
+	/// timeout, or [`Context::cancel_all`](crate::Context::cancel_all).
+	///
+	/// [`CancellationToken`]: self::cancel::CancellationToken
+	pub fn is_cancelled(&self) -> bool {
+		self.inner().cancelled
+	}
+
 	pub fn errors(&self) -> &[Message<'_>] {
 		unsafe { util::slice_from_raw_parts_or_empty(self.inner().errors.cast_const().cast::<Message>(), self.inner().errors_len) }
 	}
@@ -51,12 +177,95 @@ impl BuildResult {
 	pub fn outputs(&self) -> &[OutputFile<'_>] {
 		unsafe { util::slice_from_raw_parts_or_empty(self.inner().output_files.cast_const().cast::<OutputFile>(), self.inner().output_files_len) }
 	}
+
+	pub fn metafile(&self) -> Option<&str> {
+		unsafe { util::as_str_opt(self.inner().metafile, self.inner().metafile_len) }
+	}
+
+	/// Parses the [metafile](Self::metafile), if one was generated.
+	pub fn parse_metafile(&self) -> Option<Metafile> {
+		self.metafile().and_then(|metafile| Metafile::from_json(metafile).ok())
+	}
+
+	/// Builds a Vite-style [`AssetManifest`] from the metafile. Requires
+	/// [`metafile`](self::options::BuildOptions::metafile).
+	pub fn asset_manifest(&self) -> Option<AssetManifest> {
+		self.parse_metafile().map(|metafile| AssetManifest::from_metafile(&metafile))
+	}
+
+	/// Builds the import graph of this build's inputs. Requires [`metafile`](self::options::BuildOptions::metafile).
+	pub fn dependency_graph(&self) -> Option<DependencyGraph> {
+		self.parse_metafile().map(|metafile| DependencyGraph::from_metafile(&metafile))
+	}
+
+	/// Computes which outputs each output imports, i.e. the shared chunks each entry point needs with
+	/// [`splitting`](self::options::BuildOptions::splitting). Requires
+	/// [`metafile`](self::options::BuildOptions::metafile).
+	pub fn chunk_graph(&self) -> Option<ChunkGraph> {
+		self.parse_metafile().map(|metafile| ChunkGraph::from_metafile(&metafile))
+	}
+
+	/// Collects the licenses of the `node_modules` packages bundled into this build. Requires
+	/// [`metafile`](self::options::BuildOptions::metafile).
+	pub fn license_report(&self) -> Option<LicenseReport> {
+		self.parse_metafile().map(|metafile| LicenseReport::from_metafile(&metafile))
+	}
+
+	/// The wall-clock duration of the build. For results passed to [`on_end`](crate::PluginBuilder::on_end) callbacks,
+	/// this is the time since the build started.
+	pub fn duration(&self) -> Duration {
+		Duration::from_nanos(self.inner().duration_ns.max(0) as u64)
+	}
+
+	/// Computes statistics about this build, including the compressed size of each output.
+	///
+	/// Compressing every output is relatively expensive, so the result of this should be kept around rather than
+	/// calling it repeatedly.
+	pub fn stats(&self) -> BuildStats {
+		BuildStats::new(self)
+	}
+
+	/// Generates compressed variants of this build's outputs, e.g. `dist/index.js.gz` & `dist/index.js.br` for
+	/// `dist/index.js`. Each variant has the same hash as the output it was generated from.
+	///
+	/// The files are not written to disk, even if [`write`](self::options::BuildOptions::write) is enabled; use
+	/// [`OwnedOutputFile::write`] for that.
+	pub fn precompress(&self, options: &Precompress) -> Vec<OwnedOutputFile> {
+		options.apply(self.outputs())
+	}
+
+	/// Returns the updated property mangling cache, if [`mangle_props`](self::options::BuildOptions::mangle_props) or
+	/// [`mangle_cache`](self::options::BuildOptions::mangle_cache) was configured.
+	pub fn mangle_cache(&self) -> Option<self::mangle::MangleCache> {
+		let inner = self.inner();
+		inner
+			.has_mangle_cache
+			.then(|| unsafe { self::mangle::MangleCache::from_ffi(inner.mangle_cache, inner.mangle_cache_len) })
+	}
+
+	/// Returns how long each plugin's callbacks took during this build, aggregated per plugin, hook & filter.
+	///
+	/// For a build of a [`Context`](crate::Context), this also includes the callbacks of any watch mode or serve mode
+	/// rebuilds since its last build.
+	pub fn plugin_timings(&self) -> &[PluginTiming<'_>] {
+		unsafe { util::slice_from_raw_parts_or_empty(self.inner().plugin_timings.cast_const().cast::<PluginTiming>(), self.inner().plugin_timings_len) }
+	}
 }
 
 pub fn build(options: &self::options::BuildOptions) -> BuildResult {
 	BuildResult::new(unsafe { sys::Zsb_Build(options.handle()) })
 }
 
+pub fn build_async(options: &self::options::BuildOptions) -> self::r#async::BuildFuture {
+	let state = Arc::new(Mutex::new(self::r#async::BuildFutureState::default()));
+	let _state = state.clone();
+	self::r#async::build_async_inner(Arc::clone(options.shared_handle()), move |res| {
+		let mut state = _state.lock().unwrap();
+		state.set_and_wake(res);
+	});
+	self::r#async::BuildFuture::new(state, None)
+}
+
 impl Drop for BuildResult {
 	fn drop(&mut self) {
 		tracing::trace!("Dropping BuildResult");
@@ -0,0 +1,136 @@
+use std::{
+	error::Error,
+	fmt::{self, Display},
+	str::FromStr
+};
+
+const PLACEHOLDERS: [&str; 4] = ["dir", "name", "hash", "ext"];
+
+/// A placeholder in a [`PathTemplate`] that esbuild doesn't know about. esbuild itself would leave it in the file name
+/// as-is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathTemplateError {
+	template: String,
+	placeholder: String
+}
+
+impl PathTemplateError {
+	pub fn template(&self) -> &str {
+		&self.template
+	}
+
+	/// The unknown placeholder, including the brackets, i.e. `[id]`.
+	pub fn placeholder(&self) -> &str {
+		&self.placeholder
+	}
+}
+
+impl Display for PathTemplateError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Invalid placeholder {:?} in path template {:?} (valid: [dir], [name], [hash], [ext])", self.placeholder, self.template)
+	}
+}
+
+impl Error for PathTemplateError {}
+
+/// A template for the paths of output files relative to the [`outdir`](crate::BuildOptions::outdir), for
+/// [`entry_names`](crate::BuildOptions::entry_names), [`chunk_names`](crate::BuildOptions::chunk_names) &
+/// [`asset_names`](crate::BuildOptions::asset_names). The extension is added automatically.
+///
+/// Templates may contain these placeholders:
+/// - `[dir]`: the directory of the input file, relative to the [`outbase`](crate::BuildOptions::outbase).
+/// - `[name]`: the file name of the input file without its extension (always `chunk` for chunks).
+/// - `[hash]`: a hash of the contents of the output and the outputs it imports, for cache busting.
+/// - `[ext]`: the extension of the output file, without the leading `.`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathTemplate(String);
+
+impl PathTemplate {
+	/// Parses a template like `[dir]/[name]-[hash]`, rejecting unknown placeholders.
+	pub fn new(template: impl Into<String>) -> Result<Self, PathTemplateError> {
+		let template = template.into();
+		let mut rest = template.as_str();
+		while let Some(start) = rest.find('[') {
+			rest = &rest[start..];
+			let Some(end) = rest.find(']') else {
+				break;
+			};
+			let name = &rest[1..end];
+			// anything else in brackets can't be a placeholder, so it's kept as literal text, like in esbuild
+			let is_placeholder = !name.is_empty() && name.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'-');
+			if is_placeholder && !PLACEHOLDERS.contains(&name) {
+				return Err(PathTemplateError {
+					placeholder: rest[..=end].to_string(),
+					template
+				});
+			}
+			rest = &rest[1..];
+		}
+		Ok(Self(template))
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	/// Returns `true` if the template contains `[hash]`, so output paths change whenever their contents do.
+	pub fn is_hashed(&self) -> bool {
+		self.0.contains("[hash]")
+	}
+}
+
+impl FromStr for PathTemplate {
+	type Err = PathTemplateError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::new(s)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{BuildOptions, Loader, build};
+
+	#[test]
+	fn test_path_template() {
+		for template in ["[dir]/[name]-[hash]", "assets/[name].[hash].[ext]", "[name]-[x", "chunks/[ ]/[name]"] {
+			assert_eq!(PathTemplate::new(template).unwrap().as_str(), template);
+		}
+		assert!(PathTemplate::new("[name]-[hash]").unwrap().is_hashed());
+
+		let error = PathTemplate::new("[name]-[contenthash]").unwrap_err();
+		assert_eq!(error.placeholder(), "[contenthash]");
+		assert_eq!(error.to_string(), "Invalid placeholder \"[contenthash]\" in path template \"[name]-[contenthash]\" (valid: [dir], [name], [hash], [ext])");
+		assert!("[Name]".parse::<PathTemplate>().is_err());
+	}
+
+	#[test]
+	fn test_output_naming() {
+		let res = build(
+			&BuildOptions::new()
+				.entry_points(["test/naming/src/main.js"])
+				.bundle(true)
+				.loader(".svg", Loader::File)
+				.outdir("test/naming/out")
+				.outbase("test/naming")
+				.entry_names("[dir]/[name]-[hash]".parse().unwrap())
+				.asset_names("assets/[name]-[hash]".parse().unwrap())
+				.public_path("/static")
+				.out_extension(".js", ".mjs")
+				.write(false)
+		);
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+		let paths = res.outputs().iter().map(|output| output.path().replace('\\', "/")).collect::<Vec<_>>();
+		let main = paths.iter().position(|path| path.contains("/test/naming/out/src/main-")).unwrap();
+		assert!(paths[main].ends_with(".mjs"), "{}", paths[main]);
+		let asset = paths.iter().find(|path| path.contains("/test/naming/out/assets/logo-")).unwrap();
+		assert!(asset.ends_with(".svg"));
+
+		let code = res.outputs()[main].contents_str().unwrap();
+		let asset_name = asset.rsplit('/').next().unwrap();
+		assert!(code.contains(&format!("\"/static/assets/{asset_name}\"")), "{code}");
+	}
+}
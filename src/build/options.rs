@@ -1,52 +1,661 @@
-use crate::{plugin::IntoPluginDescriptor, sys};
+use std::{
+	fmt::{self, Debug},
+	sync::Arc
+};
 
-pub struct BuildOptions(u64);
+use serde::Serialize;
+
+use super::{
+	budget::SizeBudgets,
+	define::DefineEnv,
+	mangle::MangleCache,
+	naming::PathTemplate,
+	resolve::{Alias, ExternalPattern, ResolveExtension},
+	target::{EsVersion, Target},
+	tsconfig::TsconfigRaw
+};
+use crate::{log::LogLevel, plugin::IntoPluginDescriptor, sys};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Format {
+	#[default]
+	Default = 0,
+	Iife = 1,
+	CommonJs = 2,
+	EsModule = 3
+}
+
+impl Format {
+	pub fn from_name(name: &str) -> Option<Self> {
+		Some(match name {
+			"iife" => Self::Iife,
+			"cjs" => Self::CommonJs,
+			"esm" => Self::EsModule,
+			_ => return None
+		})
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Platform {
+	#[default]
+	Browser = 0,
+	Node = 1,
+	Neutral = 2
+}
+
+impl Platform {
+	pub fn from_name(name: &str) -> Option<Self> {
+		Some(match name {
+			"browser" => Self::Browser,
+			"node" => Self::Node,
+			"neutral" => Self::Neutral,
+			_ => return None
+		})
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Loader {
+	Js = 0,
+	Jsx = 1,
+	Ts = 2,
+	Tsx = 3,
+	Css = 4,
+	LocalCss = 5,
+	GlobalCss = 6,
+	Json = 7,
+	Text = 8,
+	Base64 = 9,
+	DataUrl = 10,
+	File = 11,
+	Binary = 12,
+	Copy = 13,
+	Empty = 14,
+	#[default]
+	Default = 15
+}
+
+impl Loader {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Js => "js",
+			Self::Jsx => "jsx",
+			Self::Ts => "ts",
+			Self::Tsx => "tsx",
+			Self::Css => "css",
+			Self::LocalCss => "local-css",
+			Self::GlobalCss => "global-css",
+			Self::Json => "json",
+			Self::Text => "text",
+			Self::Base64 => "base64",
+			Self::DataUrl => "dataurl",
+			Self::File => "file",
+			Self::Binary => "binary",
+			Self::Copy => "copy",
+			Self::Empty => "empty",
+			Self::Default => "default"
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Self> {
+		Some(match name {
+			"js" => Self::Js,
+			"jsx" => Self::Jsx,
+			"ts" => Self::Ts,
+			"tsx" => Self::Tsx,
+			"css" => Self::Css,
+			"local-css" => Self::LocalCss,
+			"global-css" => Self::GlobalCss,
+			"json" => Self::Json,
+			"text" => Self::Text,
+			"base64" => Self::Base64,
+			"dataurl" => Self::DataUrl,
+			"file" => Self::File,
+			"binary" => Self::Binary,
+			"copy" => Self::Copy,
+			"empty" => Self::Empty,
+			"default" => Self::Default,
+			_ => return None
+		})
+	}
+}
+
+/// What to do with legal comments (`/*! ... */`, or comments containing `@license` or `@preserve`). See
+/// [`LicenseReport`](crate::LicenseReport) for the licenses of packages that don't include any.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum LegalComments {
+	/// `EndOfFile` when bundling, `Inline` otherwise.
+	#[default]
+	Default = 0,
+	None = 1,
+	Inline = 2,
+	EndOfFile = 3,
+	/// Extracts them to a `.LEGAL.txt` file, linked to with a comment.
+	Linked = 4,
+	/// Extracts them to a `.LEGAL.txt` file which is not linked to.
+	External = 5
+}
+
+impl LegalComments {
+	pub fn from_name(name: &str) -> Option<Self> {
+		Some(match name {
+			"none" => Self::None,
+			"inline" => Self::Inline,
+			"eof" => Self::EndOfFile,
+			"linked" => Self::Linked,
+			"external" => Self::External,
+			_ => return None
+		})
+	}
+}
+
+/// How JSX syntax is handled. See [`BuildOptions::jsx`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Jsx {
+	/// Transforms JSX into calls to the [`jsx_factory`](BuildOptions::jsx_factory), i.e. `React.createElement`.
+	#[default]
+	Transform = 0,
+	/// Leaves JSX in the output as-is.
+	Preserve = 1,
+	/// Transforms JSX into calls to React's "automatic" runtime, imported from
+	/// [`jsx_import_source`](BuildOptions::jsx_import_source).
+	Automatic = 2
+}
+
+impl Jsx {
+	pub fn from_name(name: &str) -> Option<Self> {
+		Some(match name {
+			"transform" => Self::Transform,
+			"preserve" => Self::Preserve,
+			"automatic" => Self::Automatic,
+			_ => return None
+		})
+	}
+}
+
+/// Whether to bundle the dependencies in `node_modules`. See [`BuildOptions::packages`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Packages {
+	#[default]
+	Bundle = 0,
+	/// Marks every import of a package (any path that isn't relative or absolute) as external.
+	External = 1
+}
+
+impl Packages {
+	pub fn from_name(name: &str) -> Option<Self> {
+		Some(match name {
+			"bundle" => Self::Bundle,
+			"external" => Self::External,
+			_ => return None
+		})
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SourceMap {
+	#[default]
+	None = 0,
+	Linked = 1,
+	Inline = 2,
+	External = 3,
+	InlineAndExternal = 4
+}
+
+pub(crate) struct SharedBuildOptionsHandle(u64);
+
+impl SharedBuildOptionsHandle {
+	pub(crate) fn handle(&self) -> u64 {
+		self.0
+	}
+}
+
+impl Debug for SharedBuildOptionsHandle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "<cgo:{}>", self.0)
+	}
+}
+
+impl Drop for SharedBuildOptionsHandle {
+	fn drop(&mut self) {
+		tracing::trace!("Dropping BuildOptions");
+		unsafe { sys::Zsb_BuildOptions_Destroy(self.0) };
+	}
+}
+
+pub struct BuildOptions {
+	handle: Arc<SharedBuildOptionsHandle>
+}
 
 impl BuildOptions {
 	#[inline]
 	#[must_use]
 	pub fn new() -> BuildOptions {
-		BuildOptions(unsafe { sys::Zsb_BuildOptions_Create() })
+		BuildOptions {
+			handle: Arc::new(SharedBuildOptionsHandle(unsafe { sys::Zsb_BuildOptions_Create() }))
+		}
 	}
 
 	pub fn entry_point(self, in_path: impl AsRef<str>, out_path: impl AsRef<str>) -> Self {
 		let in_path = in_path.as_ref();
 		let out_path = out_path.as_ref();
-		if unsafe { sys::Zsb_BuildOptions_AppendEntryPoint(self.0, in_path.as_ptr() as *mut _, in_path.len(), out_path.as_ptr() as *mut _, out_path.len()) }
-			!= 0
+		if unsafe {
+			sys::Zsb_BuildOptions_AppendEntryPoint(self.handle(), in_path.as_ptr() as *mut _, in_path.len(), out_path.as_ptr() as *mut _, out_path.len())
+		} != 0
 		{
 			panic!("");
 		}
 		self
 	}
 
+	pub fn entry_points<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, in_paths: I) -> Self {
+		for in_path in in_paths {
+			self = self.entry_point(in_path, "");
+		}
+		self
+	}
+
 	pub fn plugin<P: IntoPluginDescriptor>(self, plugin: P) -> Self {
 		let descriptor = plugin.into_descriptor();
-		if unsafe { sys::Zsb_BuildOptions_AddPlugin(self.0, descriptor.handle) } != 0 {
+		if unsafe { sys::Zsb_BuildOptions_AddPlugin(self.handle(), descriptor.handle) } != 0 {
 			panic!("");
 		}
 		self
 	}
 
+	/// Checks the size of the outputs against `budgets` after each build, reporting violations as errors or warnings.
+	/// This also enables [`metafile`](Self::metafile), which is used to find the inputs contributing to each output.
+	pub fn size_budgets(self, budgets: SizeBudgets) -> Self {
+		self.metafile(true).plugin(budgets)
+	}
+
 	pub fn bundle(self, enable: bool) -> Self {
-		unsafe { sys::Zsb_BuildOptions_Bundle(self.0, enable.into()) };
+		unsafe { sys::Zsb_BuildOptions_Bundle(self.handle(), enable.into()) };
+		self
+	}
+
+	pub fn splitting(self, enable: bool) -> Self {
+		unsafe { sys::Zsb_BuildOptions_Splitting(self.handle(), enable.into()) };
+		self
+	}
+
+	pub fn write(self, enable: bool) -> Self {
+		unsafe { sys::Zsb_BuildOptions_Write(self.handle(), enable.into()) };
+		self
+	}
+
+	pub fn metafile(self, enable: bool) -> Self {
+		unsafe { sys::Zsb_BuildOptions_Metafile(self.handle(), enable.into()) };
+		self
+	}
+
+	pub fn minify(self, enable: bool) -> Self {
+		self.minify_each(enable, enable, enable)
+	}
+
+	pub fn minify_each(self, whitespace: bool, identifiers: bool, syntax: bool) -> Self {
+		unsafe { sys::Zsb_BuildOptions_Minify(self.handle(), whitespace.into(), identifiers.into(), syntax.into()) };
+		self
+	}
+
+	pub fn format(self, format: Format) -> Self {
+		if unsafe { sys::Zsb_BuildOptions_Format(self.handle(), format as u8) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	pub fn sourcemap(self, source_map: SourceMap) -> Self {
+		if unsafe { sys::Zsb_BuildOptions_Sourcemap(self.handle(), source_map as u8) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	pub fn legal_comments(self, legal_comments: LegalComments) -> Self {
+		if unsafe { sys::Zsb_BuildOptions_LegalComments(self.handle(), legal_comments as u8) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	pub fn loader(self, ext: impl AsRef<str>, loader: Loader) -> Self {
+		let ext = ext.as_ref();
+		if unsafe { sys::Zsb_BuildOptions_Loader(self.handle(), ext.as_ptr() as *mut _, ext.len(), loader as u8) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	pub fn platform(self, platform: Platform) -> Self {
+		if unsafe { sys::Zsb_BuildOptions_Platform(self.handle(), platform as u8) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	/// Sets the language version and/or engines to target, replacing any previously configured targets. If multiple
+	/// language versions are given, the last one wins.
+	pub fn target<I: IntoIterator<Item = T>, T: Into<Target>>(self, targets: I) -> Self {
+		let mut es_version = EsVersion::EsNext;
+		let mut engines = Vec::new();
+		for target in targets {
+			match target.into() {
+				Target::Es(version) => es_version = version,
+				Target::Engine(engine) => engines.push(engine)
+			}
+		}
+		if unsafe { sys::Zsb_BuildOptions_Target(self.handle(), es_version as u8) } != 0 {
+			panic!("");
+		}
+		for engine in engines {
+			let version = engine.version().to_string();
+			if unsafe { sys::Zsb_BuildOptions_AddEngine(self.handle(), engine.discriminant(), version.as_ptr() as *mut _, version.len()) } != 0 {
+				panic!("");
+			}
+		}
+		self
+	}
+
+	/// Replaces the global identifier or property access `key` (e.g. `DEBUG` or `process.env.NODE_ENV`) with
+	/// `expression`, which must be an identifier or JSON. See [`define_value`](Self::define_value) to define a value
+	/// from Rust data without writing the JSON by hand.
+	pub fn define(self, key: impl AsRef<str>, expression: impl AsRef<str>) -> Self {
+		let key = key.as_ref();
+		let expression = expression.as_ref();
+		if unsafe { sys::Zsb_BuildOptions_Define(self.handle(), key.as_ptr() as *mut _, key.len(), expression.as_ptr() as *mut _, expression.len()) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	/// Replaces `key` with `value` encoded as a JS literal, e.g. a string, number, array or object.
+	///
+	/// # Panics
+	/// Panics if `value` can't be serialized as JSON, i.e. if it is a map with non-string keys.
+	pub fn define_value<T: Serialize + ?Sized>(self, key: impl AsRef<str>, value: &T) -> Self {
+		let key = key.as_ref();
+		match serde_json::to_string(value) {
+			Ok(expression) => self.define(key, expression),
+			Err(e) => panic!("value for define {key:?} could not be serialized: {e}")
+		}
+	}
+
+	/// Inlines the environment variables of `env` as `process.env.X` & `import.meta.env.X`. References to variables
+	/// that aren't defined are reported as warnings, unless disabled with [`DefineEnv::warn_undefined`].
+	pub fn define_env(mut self, env: DefineEnv) -> Self {
+		for (key, expression) in env.defines() {
+			self = self.define(key, expression);
+		}
+		match env.undefined_plugin() {
+			Some(plugin) => self.plugin(plugin),
+			None => self
+		}
+	}
+
+	/// Renames all properties matching the given (Go syntax) regular expression, e.g. `_$`.
+	pub fn mangle_props(self, regex: impl AsRef<str>) -> Self {
+		let regex = regex.as_ref();
+		unsafe { sys::Zsb_BuildOptions_MangleProps(self.handle(), regex.as_ptr() as *mut _, regex.len()) };
+		self
+	}
+
+	/// Excludes properties matching the given regular expression from [`mangle_props`](Self::mangle_props).
+	pub fn reserve_props(self, regex: impl AsRef<str>) -> Self {
+		let regex = regex.as_ref();
+		unsafe { sys::Zsb_BuildOptions_ReserveProps(self.handle(), regex.as_ptr() as *mut _, regex.len()) };
+		self
+	}
+
+	pub fn mangle_quoted(self, enable: bool) -> Self {
+		unsafe { sys::Zsb_BuildOptions_MangleQuoted(self.handle(), enable.into()) };
+		self
+	}
+
+	/// Seeds property mangling with a cache from a previous build (see [`BuildResult::mangle_cache`]), replacing any
+	/// previously set cache.
+	///
+	/// [`BuildResult::mangle_cache`]: crate::BuildResult::mangle_cache
+	pub fn mangle_cache(self, cache: &MangleCache) -> Self {
+		let mut entries = cache.as_ffi();
+		unsafe { sys::Zsb_BuildOptions_MangleCache(self.handle(), entries.as_mut_ptr(), entries.len()) };
+		self
+	}
+
+	pub fn outfile(self, path: impl AsRef<str>) -> Self {
+		let path = path.as_ref();
+		unsafe { sys::Zsb_BuildOptions_Outfile(self.handle(), path.as_ptr() as *mut _, path.len()) };
+		self
+	}
+
+	pub fn outdir(self, path: impl AsRef<str>) -> Self {
+		let path = path.as_ref();
+		unsafe { sys::Zsb_BuildOptions_Outdir(self.handle(), path.as_ptr() as *mut _, path.len()) };
+		self
+	}
+
+	/// The directory that [`entry_names`](Self::entry_names) & [`asset_names`](Self::asset_names) compute `[dir]`
+	/// relative to. Defaults to the lowest common ancestor of all entry points.
+	pub fn outbase(self, path: impl AsRef<str>) -> Self {
+		let path = path.as_ref();
+		unsafe { sys::Zsb_BuildOptions_Outbase(self.handle(), path.as_ptr() as *mut _, path.len()) };
+		self
+	}
+
+	/// The paths of entry point outputs, relative to the [`outdir`](Self::outdir). Defaults to `[dir]/[name]`.
+	/// Include `[hash]` for cache busting, i.e. `[dir]/[name]-[hash]`.
+	pub fn entry_names(self, template: PathTemplate) -> Self {
+		let template = template.as_str();
+		unsafe { sys::Zsb_BuildOptions_EntryNames(self.handle(), template.as_ptr() as *mut _, template.len()) };
+		self
+	}
+
+	/// The paths of the shared chunks generated by [`splitting`](Self::splitting). Defaults to `[name]-[hash]`.
+	pub fn chunk_names(self, template: PathTemplate) -> Self {
+		let template = template.as_str();
+		unsafe { sys::Zsb_BuildOptions_ChunkNames(self.handle(), template.as_ptr() as *mut _, template.len()) };
+		self
+	}
+
+	/// The paths of files emitted by the [`File`](Loader::File) & [`Copy`](Loader::Copy) loaders. Defaults to
+	/// `[name]-[hash]`.
+	pub fn asset_names(self, template: PathTemplate) -> Self {
+		let template = template.as_str();
+		unsafe { sys::Zsb_BuildOptions_AssetNames(self.handle(), template.as_ptr() as *mut _, template.len()) };
+		self
+	}
+
+	/// A URL prefix for the paths of assets referenced from outputs, i.e. `/static` or `https://cdn.example.com`.
+	pub fn public_path(self, path: impl AsRef<str>) -> Self {
+		let path = path.as_ref();
+		unsafe { sys::Zsb_BuildOptions_PublicPath(self.handle(), path.as_ptr() as *mut _, path.len()) };
+		self
+	}
+
+	/// Uses the extension `replacement` instead of `ext` for output files, i.e. `.mjs` instead of `.js`. `ext` must be
+	/// `.js` or `.css`; other extensions are reported as build errors.
+	pub fn out_extension(self, ext: impl AsRef<str>, replacement: impl AsRef<str>) -> Self {
+		let ext = ext.as_ref();
+		let replacement = replacement.as_ref();
+		unsafe { sys::Zsb_BuildOptions_OutExtension(self.handle(), ext.as_ptr() as *mut _, ext.len(), replacement.as_ptr() as *mut _, replacement.len()) };
+		self
+	}
+
+	/// Adds fields of `package.json` to check for a package's entry point, in order of preference. Setting any replaces
+	/// esbuild's default of `browser,module,main` (or `main,module` for the `node` platform).
+	pub fn main_fields<I: IntoIterator<Item = S>, S: AsRef<str>>(self, fields: I) -> Self {
+		for field in fields {
+			let field = field.as_ref();
+			unsafe { sys::Zsb_BuildOptions_AppendMainField(self.handle(), field.as_ptr() as *mut _, field.len()) };
+		}
+		self
+	}
+
+	/// Adds custom conditions to match in the `exports` & `imports` fields of `package.json`, in addition to the
+	/// `default`, `import`/`require` & platform conditions.
+	pub fn conditions<I: IntoIterator<Item = S>, S: AsRef<str>>(self, conditions: I) -> Self {
+		for condition in conditions {
+			let condition = condition.as_ref();
+			unsafe { sys::Zsb_BuildOptions_AppendCondition(self.handle(), condition.as_ptr() as *mut _, condition.len()) };
+		}
+		self
+	}
+
+	/// Adds directories to search for packages that aren't found in any `node_modules` directory, like `NODE_PATH`.
+	pub fn node_paths<I: IntoIterator<Item = S>, S: AsRef<str>>(self, paths: I) -> Self {
+		for path in paths {
+			let path = path.as_ref();
+			unsafe { sys::Zsb_BuildOptions_AppendNodePath(self.handle(), path.as_ptr() as *mut _, path.len()) };
+		}
+		self
+	}
+
+	/// Adds extensions to try, in order, for imports without one. Setting any replaces esbuild's default of
+	/// `.tsx,.ts,.jsx,.js,.css,.json`.
+	pub fn resolve_extensions<I: IntoIterator<Item = ResolveExtension>>(self, extensions: I) -> Self {
+		for ext in extensions {
+			let ext = ext.as_str();
+			unsafe { sys::Zsb_BuildOptions_AppendResolveExtension(self.handle(), ext.as_ptr() as *mut _, ext.len()) };
+		}
+		self
+	}
+
+	/// Resolves symlinks to the path of the link rather than the file they point to, like node's `--preserve-symlinks`.
+	pub fn preserve_symlinks(self, enable: bool) -> Self {
+		unsafe { sys::Zsb_BuildOptions_PreserveSymlinks(self.handle(), enable.into()) };
+		self
+	}
+
+	pub fn packages(self, packages: Packages) -> Self {
+		if unsafe { sys::Zsb_BuildOptions_Packages(self.handle(), packages as u8) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	pub fn alias(self, alias: Alias) -> Self {
+		let (from, to) = (alias.from(), alias.to());
+		unsafe { sys::Zsb_BuildOptions_Alias(self.handle(), from.as_ptr() as *mut _, from.len(), to.as_ptr() as *mut _, to.len()) };
+		self
+	}
+
+	/// Excludes imports matching `pattern` from the bundle, leaving them as imports in the output.
+	pub fn external(self, pattern: ExternalPattern) -> Self {
+		let pattern = pattern.as_str();
+		unsafe { sys::Zsb_BuildOptions_AppendExternal(self.handle(), pattern.as_ptr() as *mut _, pattern.len()) };
+		self
+	}
+
+	/// Uses the tsconfig at `path` instead of the `tsconfig.json` closest to each file. Errors in the file are reported
+	/// as build errors.
+	pub fn tsconfig(self, path: impl AsRef<str>) -> Self {
+		let path = path.as_ref();
+		unsafe { sys::Zsb_BuildOptions_Tsconfig(self.handle(), path.as_ptr() as *mut _, path.len()) };
+		self
+	}
+
+	/// Uses `tsconfig` as the tsconfig of every file, instead of reading them from disk. Use [`TsconfigRaw::load`] to
+	/// base this on an existing file.
+	pub fn tsconfig_raw(self, tsconfig: &TsconfigRaw) -> Self {
+		let json = tsconfig.to_json();
+		unsafe { sys::Zsb_BuildOptions_TsconfigRaw(self.handle(), json.as_ptr() as *mut _, json.len()) };
+		self
+	}
+
+	/// Sets how JSX is handled. Overrides the `jsx` option of any tsconfig.
+	pub fn jsx(self, mode: Jsx) -> Self {
+		if unsafe { sys::Zsb_BuildOptions_Jsx(self.handle(), mode as u8) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	/// The function to call for each JSX element with [`Jsx::Transform`], e.g. `h`. Defaults to `React.createElement`.
+	pub fn jsx_factory(self, factory: impl AsRef<str>) -> Self {
+		let factory = factory.as_ref();
+		unsafe { sys::Zsb_BuildOptions_JsxFactory(self.handle(), factory.as_ptr() as *mut _, factory.len()) };
+		self
+	}
+
+	/// The component to use for fragments with [`Jsx::Transform`], e.g. `Fragment`. Defaults to `React.Fragment`.
+	pub fn jsx_fragment(self, fragment: impl AsRef<str>) -> Self {
+		let fragment = fragment.as_ref();
+		unsafe { sys::Zsb_BuildOptions_JsxFragment(self.handle(), fragment.as_ptr() as *mut _, fragment.len()) };
+		self
+	}
+
+	/// The package the [`Jsx::Automatic`] runtime is imported from, e.g. `preact` to import `preact/jsx-runtime`.
+	/// Defaults to `react`. When bundling, the package must be resolvable, otherwise the build fails with a
+	/// `Could not resolve` error.
+	pub fn jsx_import_source(self, source: impl AsRef<str>) -> Self {
+		let source = source.as_ref();
+		unsafe { sys::Zsb_BuildOptions_JsxImportSource(self.handle(), source.as_ptr() as *mut _, source.len()) };
+		self
+	}
+
+	/// Uses the development runtime (`jsx-dev-runtime`) with [`Jsx::Automatic`], which includes the source location of
+	/// each element.
+	pub fn jsx_dev(self, enable: bool) -> Self {
+		unsafe { sys::Zsb_BuildOptions_JsxDev(self.handle(), enable.into()) };
+		self
+	}
+
+	/// Treats JSX expressions as having side effects, so unused elements aren't removed when minifying.
+	pub fn jsx_side_effects(self, enable: bool) -> Self {
+		unsafe { sys::Zsb_BuildOptions_JsxSideEffects(self.handle(), enable.into()) };
+		self
+	}
+
+	pub fn log_level(self, level: LogLevel) -> Self {
+		if unsafe { sys::Zsb_BuildOptions_LogLevel(self.handle(), level as u8) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	pub fn log_limit(self, limit: u32) -> Self {
+		unsafe { sys::Zsb_BuildOptions_LogLimit(self.handle(), limit.min(i32::MAX as u32) as i32) };
+		self
+	}
+
+	pub fn log_override(self, id: impl AsRef<str>, level: LogLevel) -> Self {
+		let id = id.as_ref();
+		if unsafe { sys::Zsb_BuildOptions_LogOverride(self.handle(), id.as_ptr() as *mut _, id.len(), level as u8) } != 0 {
+			panic!("");
+		}
+		self
+	}
+
+	/// Reports each build as [`tracing`] events (with the `zsbuild::log` target) instead of printing esbuild's log to
+	/// stderr: an info-level "build started" (or "rebuild started", e.g. in watch mode) event when it starts, followed
+	/// by its errors and warnings, including those added by plugins. Events below [`log_level`](Self::log_level) are
+	/// dropped, and [`log_limit`](Self::log_limit) caps the number of errors and warnings forwarded per build.
+	pub fn log_to_tracing(self) -> Self {
+		if unsafe { sys::Zsb_BuildOptions_LogCallback(self.handle(), Some(crate::log::forward_to_tracing)) } != 0 {
+			panic!("");
+		}
 		self
 	}
 
 	pub(crate) fn handle(&self) -> u64 {
-		self.0
+		self.handle.handle()
 	}
-}
 
-impl Default for BuildOptions {
-	fn default() -> Self {
-		BuildOptions(unsafe { sys::Zsb_BuildOptions_Create() })
+	pub(crate) fn shared_handle(&self) -> &Arc<SharedBuildOptionsHandle> {
+		&self.handle
 	}
 }
 
-impl Drop for BuildOptions {
-	fn drop(&mut self) {
-		tracing::trace!("Dropping BuildOptions");
-		unsafe { sys::Zsb_BuildOptions_Destroy(self.0) };
+impl Default for BuildOptions {
+	fn default() -> Self {
+		BuildOptions::new()
 	}
 }
@@ -0,0 +1,127 @@
+use std::path::Path;
+
+use super::{OutputFile, OwnedOutputFile, compress};
+
+// text-based formats esbuild emits which benefit from compression; images, fonts & the like are already compressed.
+const DEFAULT_EXTENSIONS: [&str; 11] = ["js", "mjs", "cjs", "css", "map", "json", "html", "svg", "txt", "xml", "wasm"];
+
+/// Options for generating pre-compressed `.gz` & `.br` variants of a build's outputs with
+/// [`BuildResult::precompress`](crate::BuildResult::precompress), so they can be served as-is with the corresponding
+/// `Content-Encoding`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Precompress {
+	gzip: Option<u32>,
+	brotli: Option<u32>,
+	min_size: usize,
+	extensions: Vec<String>
+}
+
+impl Default for Precompress {
+	fn default() -> Self {
+		Self {
+			gzip: Some(9),
+			brotli: Some(11),
+			min_size: 1024,
+			extensions: DEFAULT_EXTENSIONS.iter().map(|ext| ext.to_string()).collect()
+		}
+	}
+}
+
+impl Precompress {
+	/// Creates options which generate both gzip (level 9) & brotli (quality 11) variants of JavaScript, CSS, source
+	/// map, JSON, HTML, SVG, text, XML & WebAssembly outputs that are at least 1 KiB large.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the gzip compression level (`0..=9`), or `None` to not generate `.gz` files.
+	pub fn gzip(mut self, level: Option<u32>) -> Self {
+		self.gzip = level.map(|l| l.min(9));
+		self
+	}
+
+	/// Sets the brotli compression quality (`0..=11`), or `None` to not generate `.br` files.
+	pub fn brotli(mut self, quality: Option<u32>) -> Self {
+		self.brotli = quality.map(|q| q.min(11));
+		self
+	}
+
+	/// Outputs smaller than this many bytes are not compressed.
+	pub fn min_size(mut self, bytes: usize) -> Self {
+		self.min_size = bytes;
+		self
+	}
+
+	/// Sets the extensions (without the leading `.`) of outputs that should be compressed.
+	pub fn extensions<S: ToString>(mut self, extensions: impl IntoIterator<Item = S>) -> Self {
+		self.extensions = extensions.into_iter().map(|ext| ext.to_string()).collect();
+		self
+	}
+
+	fn is_selected(&self, output: &OutputFile<'_>) -> bool {
+		output.contents().len() >= self.min_size
+			&& Path::new(output.path())
+				.extension()
+				.and_then(|ext| ext.to_str())
+				.map(|ext| self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+				.unwrap_or(false)
+	}
+
+	pub(crate) fn apply(&self, outputs: &[OutputFile<'_>]) -> Vec<OwnedOutputFile> {
+		let mut compressed = Vec::new();
+		for output in outputs.iter().filter(|output| self.is_selected(output)) {
+			let variants = [
+				self.gzip.map(|level| ("gz", compress::gzip(output.contents(), level))),
+				self.brotli.map(|quality| ("br", compress::brotli(output.contents(), quality)))
+			];
+			for (ext, contents) in variants.into_iter().flatten() {
+				// not worth serving if compression didn't help
+				if contents.len() >= output.contents().len() {
+					continue;
+				}
+				compressed.push(OwnedOutputFile {
+					path: format!("{}.{ext}", output.path()),
+					hash: output.hash().to_string(),
+					contents
+				});
+			}
+		}
+		compressed
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::marker::PhantomData;
+
+	use super::*;
+	use crate::sys;
+
+	#[test]
+	fn test_precompress() {
+		let js = "console.log('hello world');\n".repeat(100);
+		let png = vec![0u8; 4096];
+		let outputs = [("dist/index.js", js.as_bytes()), ("dist/small.js", b"1;".as_slice()), ("dist/logo.png", &png)].map(|(path, contents)| {
+			OutputFile(
+				sys::OutputFile {
+					path: path.as_ptr().cast(),
+					path_len: path.len(),
+					hash: "HASH".as_ptr().cast(),
+					hash_len: 4,
+					contents: contents.as_ptr().cast(),
+					contents_len: contents.len()
+				},
+				PhantomData
+			)
+		});
+
+		let compressed = Precompress::new().apply(&outputs);
+		let paths = compressed.iter().map(|o| o.path()).collect::<Vec<_>>();
+		assert_eq!(paths, ["dist/index.js.gz", "dist/index.js.br"]);
+		assert!(compressed.iter().all(|o| o.hash() == "HASH" && o.contents().len() < js.len()));
+
+		// `small.js` is now eligible, but its gzip output isn't smaller, so it is still skipped
+		let compressed = Precompress::new().brotli(None).min_size(0).apply(&outputs);
+		assert_eq!(compressed.iter().map(|o| o.path()).collect::<Vec<_>>(), ["dist/index.js.gz"]);
+	}
+}
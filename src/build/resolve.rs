@@ -0,0 +1,210 @@
+use std::{
+	error::Error,
+	fmt::{self, Display},
+	path::Path,
+	str::FromStr
+};
+
+/// An invalid value for one of the resolution options of [`BuildOptions`](crate::BuildOptions). The messages match the
+/// ones esbuild would report for the same value at build time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveOptionError {
+	/// An external path with more than one `*` wildcard.
+	ExternalWildcards(String),
+	/// An alias name that is a relative or absolute path, or isn't normalized (i.e. `foo/` or `foo/../bar`).
+	InvalidAliasName(String),
+	/// An alias with an empty substitution.
+	InvalidAliasSubstitution(String),
+	/// A resolve extension that doesn't start with `.`, or is just `.`.
+	InvalidExtension(String)
+}
+
+impl Display for ResolveOptionError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::ExternalWildcards(path) => write!(f, "External path {path:?} cannot have more than one \"*\" wildcard"),
+			Self::InvalidAliasName(name) => write!(f, "Invalid alias name: {name:?}"),
+			Self::InvalidAliasSubstitution(name) => write!(f, "Invalid alias substitution for {name:?}: \"\""),
+			Self::InvalidExtension(ext) => write!(f, "Invalid file extension: {ext:?}")
+		}
+	}
+}
+
+impl Error for ResolveOptionError {}
+
+/// A module path to exclude from the bundle, for [`BuildOptions::external`](crate::BuildOptions::external). The path
+/// may contain one `*` wildcard, i.e. `@mui/*` or `*.png`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExternalPattern(String);
+
+impl ExternalPattern {
+	pub fn new(pattern: impl Into<String>) -> Result<Self, ResolveOptionError> {
+		let pattern = pattern.into();
+		if pattern.matches('*').count() > 1 {
+			return Err(ResolveOptionError::ExternalWildcards(pattern));
+		}
+		Ok(Self(pattern))
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	/// Checks whether an import path is matched by this pattern.
+	pub fn matches(&self, path: &str) -> bool {
+		match self.0.split_once('*') {
+			Some((prefix, suffix)) => path.len() >= prefix.len() + suffix.len() && path.starts_with(prefix) && path.ends_with(suffix),
+			None => path == self.0
+		}
+	}
+}
+
+impl FromStr for ExternalPattern {
+	type Err = ResolveOptionError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::new(s)
+	}
+}
+
+/// Substitutes one package for another, for [`BuildOptions::alias`](crate::BuildOptions::alias). Imports of `from`
+/// (and its subpaths, i.e. `from/utils`) are resolved as if they imported `to` instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Alias {
+	from: String,
+	to: String
+}
+
+impl Alias {
+	/// `from` must be a package name (or path within a package), like `oldpkg` or `@scope/pkg/sub`. `to` may be a
+	/// package name or a path, where relative paths are resolved relative to the working directory.
+	pub fn new(from: impl Into<String>, to: impl Into<String>) -> Result<Self, ResolveOptionError> {
+		let (from, to) = (from.into(), to.into());
+		let is_normalized = !from.contains('\\') && from.split('/').all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+		if from.starts_with('.') || from.starts_with('/') || Path::new(&from).is_absolute() || !is_normalized {
+			return Err(ResolveOptionError::InvalidAliasName(from));
+		}
+		if to.is_empty() {
+			return Err(ResolveOptionError::InvalidAliasSubstitution(from));
+		}
+		Ok(Self { from, to })
+	}
+
+	pub fn from(&self) -> &str {
+		&self.from
+	}
+
+	pub fn to(&self) -> &str {
+		&self.to
+	}
+}
+
+/// A file extension to try when resolving extensionless imports, for
+/// [`BuildOptions::resolve_extensions`](crate::BuildOptions::resolve_extensions), i.e. `.ts`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResolveExtension(String);
+
+impl ResolveExtension {
+	pub fn new(ext: impl Into<String>) -> Result<Self, ResolveOptionError> {
+		let ext = ext.into();
+		if ext.len() < 2 || !ext.starts_with('.') || ext.ends_with('.') {
+			return Err(ResolveOptionError::InvalidExtension(ext));
+		}
+		Ok(Self(ext))
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
+
+impl FromStr for ResolveExtension {
+	type Err = ResolveOptionError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::new(s)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{BuildOptions, Context, Packages};
+
+	/// Builds `entry` and returns the inputs esbuild resolved, excluding the entry point itself.
+	fn resolved_inputs(entry: &str, options: BuildOptions) -> Vec<String> {
+		let context = Context::new(&options.entry_point(entry, "out.js").bundle(true).metafile(true).write(false)).unwrap();
+		let res = context.build();
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+		let metafile = res.parse_metafile().unwrap();
+		metafile.inputs.into_keys().filter(|input| input != entry).collect()
+	}
+
+	#[test]
+	fn test_validation() {
+		assert!(ExternalPattern::new("@scope/*").unwrap().matches("@scope/pkg"));
+		assert!(!ExternalPattern::new("*.png").unwrap().matches("a.svg"));
+		assert_eq!(ExternalPattern::new("*/*"), Err(ResolveOptionError::ExternalWildcards("*/*".to_string())));
+
+		assert!(Alias::new("@scope/pkg/sub", "./src/shim.js").is_ok());
+		for name in ["./foo", "/foo", ".foo", "foo/", "foo/../bar", "foo\\bar"] {
+			assert_eq!(Alias::new(name, "bar"), Err(ResolveOptionError::InvalidAliasName(name.to_string())));
+		}
+		assert_eq!(Alias::new("foo", ""), Err(ResolveOptionError::InvalidAliasSubstitution("foo".to_string())));
+
+		assert!(ResolveExtension::new(".mts").is_ok());
+		for ext in ["ts", ".", ".ts."] {
+			assert_eq!(ResolveExtension::new(ext), Err(ResolveOptionError::InvalidExtension(ext.to_string())));
+		}
+	}
+
+	#[test]
+	fn test_main_fields() {
+		assert_eq!(resolved_inputs("test/resolve/fields.js", BuildOptions::new()), ["test/resolve/node_modules/fields-pkg/browser.js"]);
+		assert_eq!(
+			resolved_inputs("test/resolve/fields.js", BuildOptions::new().main_fields(["module", "main"])),
+			["test/resolve/node_modules/fields-pkg/module.js"]
+		);
+	}
+
+	#[test]
+	fn test_conditions() {
+		assert_eq!(resolved_inputs("test/resolve/conditions.js", BuildOptions::new()), ["test/resolve/node_modules/cond-pkg/default.js"]);
+		assert_eq!(resolved_inputs("test/resolve/conditions.js", BuildOptions::new().conditions(["custom"])), ["test/resolve/node_modules/cond-pkg/custom.js"]);
+	}
+
+	#[test]
+	fn test_node_paths() {
+		assert_eq!(
+			resolved_inputs("test/resolve/node-paths.js", BuildOptions::new().node_paths(["test/resolve/extra"])),
+			["test/resolve/extra/np-pkg/index.js"]
+		);
+	}
+
+	#[test]
+	fn test_resolve_extensions() {
+		assert_eq!(resolved_inputs("test/resolve/extensions.js", BuildOptions::new()), ["test/resolve/ext-target.ts"]);
+		let extensions = [".js", ".ts"].map(|ext| ext.parse::<ResolveExtension>().unwrap());
+		assert_eq!(resolved_inputs("test/resolve/extensions.js", BuildOptions::new().resolve_extensions(extensions)), ["test/resolve/ext-target.js"]);
+	}
+
+	// git only checks out the fixture as a symlink on Unix
+	#[cfg(unix)]
+	#[test]
+	fn test_preserve_symlinks() {
+		assert_eq!(resolved_inputs("test/resolve/symlinks.js", BuildOptions::new()), ["test/resolve/linked-src/index.js"]);
+		assert_eq!(resolved_inputs("test/resolve/symlinks.js", BuildOptions::new().preserve_symlinks(true)), ["test/resolve/node_modules/linked/index.js"]);
+	}
+
+	#[test]
+	fn test_packages_alias_external() {
+		assert!(resolved_inputs("test/resolve/fields.js", BuildOptions::new().packages(Packages::External)).is_empty());
+		assert!(resolved_inputs("test/resolve/fields.js", BuildOptions::new().external("fields-*".parse().unwrap())).is_empty());
+		assert_eq!(
+			resolved_inputs("test/resolve/fields.js", BuildOptions::new().alias(Alias::new("fields-pkg", "./test/resolve/ext-target.js").unwrap())),
+			["test/resolve/ext-target.js"]
+		);
+	}
+}
@@ -0,0 +1,349 @@
+use std::{
+	error::Error,
+	fmt::{self, Display},
+	fs, io,
+	path::Path
+};
+
+use serde::Deserialize;
+
+use crate::{error::LocationBuilder, util};
+
+#[derive(Debug)]
+pub enum SourceMapError {
+	/// No source map could be found for the output file, either because source maps weren't enabled or because an
+	/// external map isn't among the given outputs or on disk.
+	NotFound(String),
+	InvalidDataUrl,
+	InvalidJson(serde_json::Error),
+	UnsupportedVersion(u32),
+	InvalidMappings,
+	Io(io::Error)
+}
+
+impl Display for SourceMapError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::NotFound(path) => write!(f, "no source map found for {path:?}"),
+			Self::InvalidDataUrl => f.write_str("inline source map is not a base64-encoded JSON data URL"),
+			Self::InvalidJson(e) => write!(f, "invalid source map: {e}"),
+			Self::UnsupportedVersion(v) => write!(f, "unsupported source map version {v}"),
+			Self::InvalidMappings => f.write_str("invalid source map: malformed `mappings`"),
+			Self::Io(e) => write!(f, "failed to read source map: {e}")
+		}
+	}
+}
+
+impl Error for SourceMapError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::InvalidJson(e) => Some(e),
+			Self::Io(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSourceMap {
+	version: u32,
+	#[serde(default)]
+	source_root: Option<String>,
+	#[serde(default)]
+	sources: Vec<Option<String>>,
+	#[serde(default)]
+	sources_content: Vec<Option<String>>,
+	#[serde(default)]
+	names: Vec<String>,
+	mappings: String
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+	generated_column: u32,
+	source: u32,
+	line: u32,
+	column: u32,
+	name: Option<u32>
+}
+
+/// A position in an original source file, as found by [`SourceMapConsumer::lookup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping<'m> {
+	source: &'m str,
+	line: usize,
+	column: usize,
+	name: Option<&'m str>
+}
+
+impl<'m> Mapping<'m> {
+	/// The path of the original source file. For maps located via an output file, this is resolved relative to the
+	/// map's own location, so it usually points at the file on disk.
+	pub fn source(&self) -> &'m str {
+		self.source
+	}
+
+	/// The 1-based line in the original source.
+	pub fn line(&self) -> usize {
+		self.line
+	}
+
+	/// The 0-based column in the original source.
+	pub fn column(&self) -> usize {
+		self.column
+	}
+
+	/// The original identifier at this position, if it was renamed (e.g. by minification).
+	pub fn name(&self) -> Option<&'m str> {
+		self.name
+	}
+}
+
+/// A parsed (v3) source map that can map positions in generated code back to the original sources.
+///
+/// Positions follow esbuild's [`Location`](crate::Location) convention: lines are 1-based and columns are 0-based.
+#[derive(Debug, Clone)]
+pub struct SourceMapConsumer {
+	sources: Vec<String>,
+	sources_content: Vec<Option<String>>,
+	names: Vec<String>,
+	// segments of each generated line, sorted by generated column
+	lines: Vec<Vec<Segment>>
+}
+
+impl SourceMapConsumer {
+	pub fn from_json(json: &str) -> Result<Self, SourceMapError> {
+		Self::parse(json, None)
+	}
+
+	fn parse(json: &str, map_path: Option<&str>) -> Result<Self, SourceMapError> {
+		let raw: RawSourceMap = serde_json::from_str(json).map_err(SourceMapError::InvalidJson)?;
+		if raw.version != 3 {
+			return Err(SourceMapError::UnsupportedVersion(raw.version));
+		}
+
+		let map_dir = map_path.and_then(|p| Path::new(p).parent());
+		let sources = raw
+			.sources
+			.into_iter()
+			.map(|source| resolve_source(raw.source_root.as_deref(), &source.unwrap_or_default(), map_dir))
+			.collect::<Vec<_>>();
+		let lines = decode_mappings(&raw.mappings, sources.len(), raw.names.len()).ok_or(SourceMapError::InvalidMappings)?;
+		Ok(Self {
+			sources,
+			sources_content: raw.sources_content,
+			names: raw.names,
+			lines
+		})
+	}
+
+	/// Finds the source map belonging to a generated file, given its `path` and `contents`. `sibling` is used to look
+	/// up other outputs of the same build by path, falling back to the file system.
+	pub(crate) fn locate<'o>(path: &str, contents: &[u8], sibling: impl Fn(&str) -> Option<&'o [u8]>) -> Result<Self, SourceMapError> {
+		let read = |map_path: &str| -> Result<Self, SourceMapError> {
+			let json = match sibling(map_path) {
+				Some(contents) => String::from_utf8_lossy(contents).into_owned(),
+				None => match fs::read_to_string(map_path) {
+					Ok(json) => json,
+					Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(SourceMapError::NotFound(path.to_string())),
+					Err(e) => return Err(SourceMapError::Io(e))
+				}
+			};
+			Self::parse(&json, Some(map_path))
+		};
+
+		match source_mapping_url(&String::from_utf8_lossy(contents)) {
+			// `SourceMap::Inline` (or `InlineAndExternal`)
+			Some(url) if url.starts_with("data:") => {
+				let (header, data) = url.split_once(',').ok_or(SourceMapError::InvalidDataUrl)?;
+				if !header.ends_with(";base64") {
+					return Err(SourceMapError::InvalidDataUrl);
+				}
+				let json = util::base64_decode(data)
+					.and_then(|d| String::from_utf8(d).ok())
+					.ok_or(SourceMapError::InvalidDataUrl)?;
+				Self::parse(&json, Some(&format!("{path}.map")))
+			}
+			// `SourceMap::Linked`; the URL is relative to the generated file
+			Some(url) => {
+				let map_path = match Path::new(path).parent() {
+					Some(dir) => util::normalize_path(&dir.join(url)).to_string_lossy().into_owned(),
+					None => url.to_string()
+				};
+				read(&map_path)
+			}
+			// `SourceMap::External` writes the map next to the output without referencing it
+			None => read(&format!("{path}.map"))
+		}
+	}
+
+	pub fn sources(&self) -> &[String] {
+		&self.sources
+	}
+
+	pub fn source_content(&self, source: &str) -> Option<&str> {
+		let index = self.sources.iter().position(|s| s == source)?;
+		self.sources_content.get(index)?.as_deref()
+	}
+
+	/// Maps a 1-based `line` and 0-based `column` in the generated file to the original source position. The closest
+	/// mapping at or before `column` on the same line is used.
+	pub fn lookup(&self, line: usize, column: usize) -> Option<Mapping<'_>> {
+		let segments = self.lines.get(line.checked_sub(1)?)?;
+		let index = segments.partition_point(|s| s.generated_column as usize <= column).checked_sub(1)?;
+		let segment = &segments[index];
+		Some(Mapping {
+			source: &self.sources[segment.source as usize],
+			line: segment.line as usize + 1,
+			column: segment.column as usize,
+			name: segment.name.map(|n| self.names[n as usize].as_str())
+		})
+	}
+
+	/// Like [`lookup`](Self::lookup), but returns the original position as a [`LocationBuilder`] (including the
+	/// original line's text, if the map has `sourcesContent`) so it can be attached to a diagnostic.
+	pub fn original_location(&self, line: usize, column: usize) -> Option<LocationBuilder> {
+		let mapping = self.lookup(line, column)?;
+		let len = mapping.name().map(str::len).unwrap_or(0);
+		let location = LocationBuilder::new(mapping.source(), mapping.line() as i64, mapping.column() as i64, len as i64);
+		let line_text = self
+			.source_content(mapping.source())
+			.and_then(|content| content.lines().nth(mapping.line() - 1));
+		Some(match line_text {
+			Some(line_text) => location.with_line_text(line_text),
+			None => location
+		})
+	}
+}
+
+/// Returns the URL of the last `sourceMappingURL` comment in a JS or CSS file.
+fn source_mapping_url(contents: &str) -> Option<&str> {
+	let (_, rest) = contents.rsplit_once("# sourceMappingURL=")?;
+	let url = rest.split(|c: char| c.is_whitespace() || c == '*').next()?;
+	(!url.is_empty()).then_some(url)
+}
+
+fn resolve_source(source_root: Option<&str>, source: &str, map_dir: Option<&Path>) -> String {
+	let source = match source_root {
+		Some(root) if !root.is_empty() => format!("{}/{source}", root.trim_end_matches('/')),
+		_ => source.to_string()
+	};
+	// leave URLs (and esbuild's `<stdin>`-style virtual sources) alone
+	if source.contains("://") || source.starts_with('<') || Path::new(&source).is_absolute() {
+		return source;
+	}
+	match map_dir {
+		Some(dir) => util::normalize_path(&dir.join(&source)).to_string_lossy().into_owned(),
+		None => source
+	}
+}
+
+fn decode_mappings(mappings: &str, sources_len: usize, names_len: usize) -> Option<Vec<Vec<Segment>>> {
+	let mut lines = Vec::new();
+	// all fields but the generated column are relative to the previous segment, even across lines
+	let (mut source, mut line, mut column, mut name) = (0i64, 0i64, 0i64, 0i64);
+	for encoded_line in mappings.split(';') {
+		let mut segments = Vec::new();
+		let mut generated_column = 0i64;
+		for encoded_segment in encoded_line.split(',').filter(|s| !s.is_empty()) {
+			let fields = decode_vlq(encoded_segment)?;
+			generated_column += fields[0];
+			match fields.len() {
+				// a generated position without an original one; nothing to map back to
+				1 => continue,
+				4 | 5 => {
+					source += fields[1];
+					line += fields[2];
+					column += fields[3];
+				}
+				_ => return None
+			}
+			let segment_name = match fields.get(4) {
+				Some(delta) => {
+					name += delta;
+					Some(u32::try_from(name).ok().filter(|&n| (n as usize) < names_len)?)
+				}
+				None => None
+			};
+			if generated_column < 0 || line < 0 || column < 0 || source < 0 || source as usize >= sources_len {
+				return None;
+			}
+			segments.push(Segment {
+				generated_column: generated_column as u32,
+				source: source as u32,
+				line: line as u32,
+				column: column as u32,
+				name: segment_name
+			});
+		}
+		segments.sort_by_key(|s| s.generated_column);
+		lines.push(segments);
+	}
+	Some(lines)
+}
+
+fn decode_vlq(segment: &str) -> Option<Vec<i64>> {
+	let mut values = Vec::with_capacity(5);
+	let mut value = 0i64;
+	let mut shift = 0;
+	for c in segment.bytes() {
+		let digit = match c {
+			b'A'..=b'Z' => c - b'A',
+			b'a'..=b'z' => c - b'a' + 26,
+			b'0'..=b'9' => c - b'0' + 52,
+			b'+' => 62,
+			b'/' => 63,
+			_ => return None
+		} as i64;
+		if shift > 60 {
+			return None;
+		}
+		value |= (digit & 0b11111) << shift;
+		if digit & 0b100000 != 0 {
+			shift += 5;
+		} else {
+			values.push(if value & 1 != 0 { -(value >> 1) } else { value >> 1 });
+			value = 0;
+			shift = 0;
+		}
+	}
+	(shift == 0).then_some(values)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// `let answer = 42;\nconsole.log(answer);` in src/app.ts, minified to `let a=42;console.log(a);`
+	const MAP: &str = r#"{
+		"version": 3,
+		"sources": ["../src/app.ts"],
+		"sourcesContent": ["let answer = 42;\nconsole.log(answer);\n"],
+		"names": ["answer"],
+		"mappings": "AAAA,IAAIA,EAAS,GACb,QAAQ,IAAIA"
+	}"#;
+
+	#[test]
+	fn test_lookup() {
+		let map = SourceMapConsumer::from_json(MAP).unwrap();
+		let mapping = map.lookup(1, 4).unwrap();
+		assert_eq!((mapping.source(), mapping.line(), mapping.column(), mapping.name()), ("../src/app.ts", 1, 4, Some("answer")));
+		let mapping = map.lookup(1, 23).unwrap();
+		assert_eq!((mapping.line(), mapping.column(), mapping.name()), (2, 12, Some("answer")));
+		assert!(map.lookup(2, 0).is_none());
+	}
+
+	#[test]
+	fn test_locate_inline_and_linked() {
+		let inline = format!("let a=42;\n//# sourceMappingURL=data:application/json;base64,{}\n", util::base64_encode(MAP.as_bytes()));
+		let map = SourceMapConsumer::locate("dist/app.js", inline.as_bytes(), |_| None).unwrap();
+		assert_eq!(map.sources(), ["src/app.ts"]);
+
+		let linked = "let a=42;\n//# sourceMappingURL=app.js.map\n";
+		let map = SourceMapConsumer::locate("dist/app.js", linked.as_bytes(), |p| (p == "dist/app.js.map").then_some(MAP.as_bytes())).unwrap();
+		let location = map.original_location(1, 4).unwrap();
+		assert_eq!(location.file(), "src/app.ts");
+		assert!(matches!(SourceMapConsumer::locate("dist/missing.js", b"", |_| None), Err(SourceMapError::NotFound(_))));
+	}
+}
@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use super::{BuildResult, compress};
+
+/// Size statistics for one output file. See [`BuildStats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputStats {
+	path: String,
+	bytes: u64,
+	gzip_bytes: Option<u64>,
+	brotli_bytes: Option<u64>
+}
+
+impl OutputStats {
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	pub fn bytes(&self) -> u64 {
+		self.bytes
+	}
+
+	/// The size of the output after gzip compression at level 9, or `None` if the output's contents weren't available
+	/// (i.e. it was only known from the metafile).
+	pub fn gzip_bytes(&self) -> Option<u64> {
+		self.gzip_bytes
+	}
+
+	/// The size of the output after brotli compression at quality 11, or `None` if the output's contents weren't
+	/// available.
+	pub fn brotli_bytes(&self) -> Option<u64> {
+		self.brotli_bytes
+	}
+}
+
+/// Statistics about a build, computed with [`BuildResult::stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildStats {
+	duration: Duration,
+	input_files: Option<usize>,
+	input_bytes: Option<u64>,
+	output_bytes: u64,
+	outputs: Vec<OutputStats>
+}
+
+impl BuildStats {
+	pub(crate) fn new(result: &BuildResult) -> Self {
+		let metafile = result.parse_metafile();
+		let mut outputs = result
+			.outputs()
+			.iter()
+			.map(|output| OutputStats {
+				path: output.path().to_string(),
+				bytes: output.contents().len() as u64,
+				gzip_bytes: Some(compress::gzip(output.contents(), 9).len() as u64),
+				brotli_bytes: Some(compress::brotli(output.contents(), 11).len() as u64)
+			})
+			.collect::<Vec<_>>();
+		if outputs.is_empty() {
+			if let Some(metafile) = &metafile {
+				outputs = metafile
+					.outputs
+					.iter()
+					.map(|(path, output)| OutputStats {
+						path: path.clone(),
+						bytes: output.bytes,
+						gzip_bytes: None,
+						brotli_bytes: None
+					})
+					.collect();
+			}
+		}
+
+		Self {
+			duration: result.duration(),
+			input_files: metafile.as_ref().map(|m| m.inputs.len()),
+			input_bytes: metafile.as_ref().map(|m| m.inputs.values().map(|i| i.bytes).sum()),
+			output_bytes: outputs.iter().map(|o| o.bytes).sum(),
+			outputs
+		}
+	}
+
+	/// The wall-clock duration of the build.
+	pub fn duration(&self) -> Duration {
+		self.duration
+	}
+
+	/// The number of input files that went into the build. Requires [`metafile`](crate::BuildOptions::metafile).
+	pub fn input_files(&self) -> Option<usize> {
+		self.input_files
+	}
+
+	/// The total size of all input files. Requires [`metafile`](crate::BuildOptions::metafile).
+	pub fn input_bytes(&self) -> Option<u64> {
+		self.input_bytes
+	}
+
+	pub fn output_bytes(&self) -> u64 {
+		self.output_bytes
+	}
+
+	pub fn outputs(&self) -> &[OutputStats] {
+		&self.outputs
+	}
+}
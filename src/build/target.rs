@@ -0,0 +1,368 @@
+use std::{
+	cmp::Ordering,
+	collections::BTreeMap,
+	error::Error,
+	fmt::{self, Display},
+	str::FromStr
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTargetError {
+	InvalidVersion(String),
+	UnknownTarget(String),
+	UnsupportedEngine(String)
+}
+
+impl Display for ParseTargetError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidVersion(v) => write!(f, "invalid version {v:?}"),
+			Self::UnknownTarget(t) => write!(f, "unknown target {t:?}"),
+			Self::UnsupportedEngine(e) => write!(f, "engine {e:?} is not supported by esbuild")
+		}
+	}
+}
+
+impl Error for ParseTargetError {}
+
+/// A `major[.minor[.patch]]` engine version. Missing components are treated as `0` when comparing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Version {
+	major: u32,
+	minor: Option<u32>,
+	patch: Option<u32>
+}
+
+impl Version {
+	pub const fn new(major: u32) -> Self {
+		Self { major, minor: None, patch: None }
+	}
+
+	pub const fn with_minor(mut self, minor: u32) -> Self {
+		self.minor = Some(minor);
+		self
+	}
+
+	pub const fn with_patch(mut self, patch: u32) -> Self {
+		if self.minor.is_none() {
+			self.minor = Some(0);
+		}
+		self.patch = Some(patch);
+		self
+	}
+
+	pub fn major(&self) -> u32 {
+		self.major
+	}
+
+	pub fn minor(&self) -> u32 {
+		self.minor.unwrap_or(0)
+	}
+
+	pub fn patch(&self) -> u32 {
+		self.patch.unwrap_or(0)
+	}
+}
+
+impl PartialOrd for Version {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Version {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(self.major, self.minor(), self.patch()).cmp(&(other.major, other.minor(), other.patch()))
+	}
+}
+
+impl Display for Version {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.major)?;
+		if let Some(minor) = self.minor {
+			write!(f, ".{minor}")?;
+		}
+		if let Some(patch) = self.patch {
+			write!(f, ".{patch}")?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for Version {
+	type Err = ParseTargetError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let err = || ParseTargetError::InvalidVersion(s.to_string());
+		let mut parts = s.split('.');
+		let major = parts.next().filter(|p| !p.is_empty()).ok_or_else(err)?.parse().map_err(|_| err())?;
+		let minor = parts.next().map(|p| p.parse().map_err(|_| err())).transpose()?;
+		let patch = parts.next().map(|p| p.parse().map_err(|_| err())).transpose()?;
+		if parts.next().is_some() {
+			return Err(err());
+		}
+		Ok(Version { major, minor, patch })
+	}
+}
+
+/// An ECMAScript language version, mirroring esbuild's `ES*` targets.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum EsVersion {
+	Es5 = 0,
+	Es2015 = 1,
+	Es2016 = 2,
+	Es2017 = 3,
+	Es2018 = 4,
+	Es2019 = 5,
+	Es2020 = 6,
+	Es2021 = 7,
+	Es2022 = 8,
+	Es2023 = 9,
+	#[default]
+	EsNext = 10
+}
+
+impl EsVersion {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Es5 => "es5",
+			Self::Es2015 => "es2015",
+			Self::Es2016 => "es2016",
+			Self::Es2017 => "es2017",
+			Self::Es2018 => "es2018",
+			Self::Es2019 => "es2019",
+			Self::Es2020 => "es2020",
+			Self::Es2021 => "es2021",
+			Self::Es2022 => "es2022",
+			Self::Es2023 => "es2023",
+			Self::EsNext => "esnext"
+		}
+	}
+}
+
+impl Display for EsVersion {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for EsVersion {
+	type Err = ParseTargetError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match &*s.to_ascii_lowercase() {
+			"es5" => Self::Es5,
+			"es6" | "es2015" => Self::Es2015,
+			"es2016" => Self::Es2016,
+			"es2017" => Self::Es2017,
+			"es2018" => Self::Es2018,
+			"es2019" => Self::Es2019,
+			"es2020" => Self::Es2020,
+			"es2021" => Self::Es2021,
+			"es2022" => Self::Es2022,
+			"es2023" => Self::Es2023,
+			"esnext" => Self::EsNext,
+			_ => return Err(ParseTargetError::UnknownTarget(s.to_string()))
+		})
+	}
+}
+
+/// A JavaScript engine & the minimum version of it to support, mirroring esbuild's `Engine*` names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Engine {
+	Chrome(Version),
+	Deno(Version),
+	Edge(Version),
+	Firefox(Version),
+	Hermes(Version),
+	Ie(Version),
+	Ios(Version),
+	Node(Version),
+	Opera(Version),
+	Rhino(Version),
+	Safari(Version)
+}
+
+impl Engine {
+	/// Parses an engine name in either esbuild (`chrome`, `ios`) or browserslist (`and_chr`, `ios_saf`) form.
+	fn from_name(name: &str, version: Version) -> Result<Self, ParseTargetError> {
+		Ok(match &*name.to_ascii_lowercase() {
+			"chrome" | "and_chr" => Self::Chrome(version),
+			"deno" => Self::Deno(version),
+			"edge" => Self::Edge(version),
+			"firefox" | "and_ff" => Self::Firefox(version),
+			"hermes" => Self::Hermes(version),
+			"ie" | "ie_mob" => Self::Ie(version),
+			"ios" | "ios_saf" => Self::Ios(version),
+			"node" => Self::Node(version),
+			"opera" | "op_mob" => Self::Opera(version),
+			"rhino" => Self::Rhino(version),
+			"safari" => Self::Safari(version),
+			_ => return Err(ParseTargetError::UnsupportedEngine(name.to_string()))
+		})
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Chrome(_) => "chrome",
+			Self::Deno(_) => "deno",
+			Self::Edge(_) => "edge",
+			Self::Firefox(_) => "firefox",
+			Self::Hermes(_) => "hermes",
+			Self::Ie(_) => "ie",
+			Self::Ios(_) => "ios",
+			Self::Node(_) => "node",
+			Self::Opera(_) => "opera",
+			Self::Rhino(_) => "rhino",
+			Self::Safari(_) => "safari"
+		}
+	}
+
+	pub fn version(&self) -> Version {
+		match self {
+			Self::Chrome(v)
+			| Self::Deno(v)
+			| Self::Edge(v)
+			| Self::Firefox(v)
+			| Self::Hermes(v)
+			| Self::Ie(v)
+			| Self::Ios(v)
+			| Self::Node(v)
+			| Self::Opera(v)
+			| Self::Rhino(v)
+			| Self::Safari(v) => *v
+		}
+	}
+
+	pub(crate) fn discriminant(&self) -> u8 {
+		match self {
+			Self::Chrome(_) => 0,
+			Self::Deno(_) => 1,
+			Self::Edge(_) => 2,
+			Self::Firefox(_) => 3,
+			Self::Hermes(_) => 4,
+			Self::Ie(_) => 5,
+			Self::Ios(_) => 6,
+			Self::Node(_) => 7,
+			Self::Opera(_) => 8,
+			Self::Rhino(_) => 9,
+			Self::Safari(_) => 10
+		}
+	}
+}
+
+impl Display for Engine {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}{}", self.name(), self.version())
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+	Es(EsVersion),
+	Engine(Engine)
+}
+
+impl Target {
+	/// Converts the output of browserslist (e.g. `["chrome 120", "ios_saf 16.6-16.7", "op_mini all"]`) into a list of
+	/// targets, keeping only the oldest version of each engine.
+	///
+	/// Engines esbuild doesn't know about (such as `samsung` or `op_mini`) are skipped.
+	pub fn from_browserslist<I: IntoIterator<Item = S>, S: AsRef<str>>(queries: I) -> Result<Vec<Target>, ParseTargetError> {
+		let mut oldest = BTreeMap::<&'static str, Engine>::new();
+		for query in queries {
+			let query = query.as_ref().trim();
+			let Some((name, version)) = query.split_once(' ') else {
+				return Err(ParseTargetError::UnknownTarget(query.to_string()));
+			};
+			// Safari Technology Preview has no version number to target
+			if Engine::from_name(name, Version::default()).is_err() || version == "TP" {
+				continue;
+			}
+			// ranges like `16.6-16.7` are inclusive; the low end is the one that matters
+			let version = version.split('-').next().unwrap_or(version);
+			let engine = Engine::from_name(name, version.parse()?)?;
+			oldest
+				.entry(engine.name())
+				.and_modify(|e| {
+					if engine.version() < e.version() {
+						*e = engine;
+					}
+				})
+				.or_insert(engine);
+		}
+		Ok(oldest.into_values().map(Target::Engine).collect())
+	}
+
+	/// Parses a comma-separated list of targets, as accepted by esbuild's `--target` flag.
+	pub fn parse_list(s: &str) -> Result<Vec<Target>, ParseTargetError> {
+		s.split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::parse).collect()
+	}
+}
+
+impl From<EsVersion> for Target {
+	fn from(value: EsVersion) -> Self {
+		Target::Es(value)
+	}
+}
+
+impl From<Engine> for Target {
+	fn from(value: Engine) -> Self {
+		Target::Engine(value)
+	}
+}
+
+impl Display for Target {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Es(es) => es.fmt(f),
+			Self::Engine(engine) => engine.fmt(f)
+		}
+	}
+}
+
+impl FromStr for Target {
+	type Err = ParseTargetError;
+
+	/// Parses a single target in esbuild (`es2020`, `chrome58`, `node12.19`) or browserslist (`chrome 58`,
+	/// `ios_saf 12.2`) form.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		if let Ok(es) = s.parse::<EsVersion>() {
+			return Ok(Target::Es(es));
+		}
+		let (name, version) = match s.split_once(' ') {
+			Some((name, version)) => (name, version.trim()),
+			None => match s.find(|c: char| c.is_ascii_digit()) {
+				Some(idx) => s.split_at(idx),
+				None => return Err(ParseTargetError::UnknownTarget(s.to_string()))
+			}
+		};
+		Ok(Target::Engine(Engine::from_name(name, version.parse()?)?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_targets() {
+		assert_eq!("es2020".parse(), Ok(Target::Es(EsVersion::Es2020)));
+		assert_eq!("chrome58".parse(), Ok(Target::Engine(Engine::Chrome(Version::new(58)))));
+		assert_eq!("node12.19.0".parse(), Ok(Target::Engine(Engine::Node(Version::new(12).with_minor(19).with_patch(0)))));
+		assert_eq!("ios_saf 12.2".parse(), Ok(Target::Engine(Engine::Ios(Version::new(12).with_minor(2)))));
+		assert!(matches!("samsung 20".parse::<Target>(), Err(ParseTargetError::UnsupportedEngine(_))));
+		assert!(matches!("chrome".parse::<Target>(), Err(ParseTargetError::UnknownTarget(_))));
+		assert_eq!(Target::parse_list("es2017, firefox57").unwrap().len(), 2);
+	}
+
+	#[test]
+	fn test_from_browserslist() {
+		let targets =
+			Target::from_browserslist(["chrome 120", "chrome 119", "and_chr 121", "ios_saf 16.6-16.7", "op_mini all", "samsung 23", "safari TP"]).unwrap();
+		assert_eq!(targets, vec![Target::Engine(Engine::Chrome(Version::new(119))), Target::Engine(Engine::Ios(Version::new(16).with_minor(6)))]);
+		assert_eq!(targets[1].to_string(), "ios16.6");
+	}
+}
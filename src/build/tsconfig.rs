@@ -0,0 +1,388 @@
+use std::{
+	collections::BTreeMap,
+	error::Error,
+	fmt::{self, Display},
+	fs, io,
+	path::{Component, Path, PathBuf}
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+	error::{LocationBuilder, MessageBuilder},
+	util
+};
+
+#[derive(Debug)]
+pub enum TsconfigError {
+	Io(io::Error),
+	/// The tsconfig is not valid JSON, or an option has the wrong type. The message is located at the error.
+	Parse(Box<MessageBuilder>),
+	/// A config in the `extends` chain could not be found or extends itself, or `extends` was used without a file to
+	/// resolve it against. The message is located at the `extends` field.
+	Extends(Box<MessageBuilder>)
+}
+
+impl TsconfigError {
+	/// Converts the error to a message, i.e. to report it from a plugin.
+	pub fn to_message(&self) -> MessageBuilder {
+		match self {
+			Self::Io(_) => MessageBuilder::new(self.to_string()),
+			Self::Parse(message) | Self::Extends(message) => (**message).clone()
+		}
+	}
+}
+
+impl Display for TsconfigError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Io(e) => write!(f, "failed to read tsconfig: {e}"),
+			Self::Parse(message) | Self::Extends(message) => match message.location() {
+				Some(location) => write!(f, "{}:{}:{}: {}", location.file(), location.line(), location.column(), message.text()),
+				None => f.write_str(message.text())
+			}
+		}
+	}
+}
+
+impl Error for TsconfigError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::Io(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+/// The `jsx` compiler option of a tsconfig.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TsconfigJsx {
+	#[serde(rename = "preserve")]
+	Preserve,
+	#[serde(rename = "react-native")]
+	ReactNative,
+	/// Transforms JSX into calls to `jsxFactory`, i.e. `React.createElement`.
+	#[serde(rename = "react")]
+	React,
+	/// Transforms JSX into calls to the automatic runtime, i.e. `react/jsx-runtime`.
+	#[serde(rename = "react-jsx")]
+	ReactJsx,
+	/// Like `ReactJsx`, but uses the development runtime (`react/jsx-dev-runtime`).
+	#[serde(rename = "react-jsxdev")]
+	ReactJsxDev
+}
+
+/// The subset of TypeScript's `compilerOptions` that esbuild respects.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerOptions {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub jsx: Option<TsconfigJsx>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub jsx_factory: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub jsx_fragment_factory: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub jsx_import_source: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub experimental_decorators: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub use_define_for_class_fields: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub verbatim_module_syntax: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub paths: Option<BTreeMap<String, Vec<String>>>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub base_url: Option<String>,
+	/// i.e. `ES2022`. Only affects `useDefineForClassFields`; use [`target`](crate::BuildOptions::target) to set the
+	/// output language version.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub target: Option<String>
+}
+
+/// The contents of a tsconfig, passed to esbuild with [`tsconfig_raw`](crate::BuildOptions::tsconfig_raw) instead of
+/// being read from disk.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TsconfigRaw {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub compiler_options: Option<CompilerOptions>
+}
+
+/// The `extends` field of a tsconfig, which isn't part of [`TsconfigRaw`] since esbuild doesn't resolve it.
+#[derive(Deserialize)]
+struct TsconfigExtends {
+	#[serde(default)]
+	extends: Option<Extends>
+}
+
+/// `extends` is either a single config or (since TypeScript 5.0) a list of configs, applied in order.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Extends {
+	One(String),
+	Many(Vec<String>)
+}
+
+impl Extends {
+	fn into_vec(self) -> Vec<String> {
+		match self {
+			Self::One(specifier) => vec![specifier],
+			Self::Many(specifiers) => specifiers
+		}
+	}
+}
+
+impl TsconfigRaw {
+	pub fn new(compiler_options: CompilerOptions) -> Self {
+		Self {
+			compiler_options: Some(compiler_options)
+		}
+	}
+
+	/// Parses a tsconfig. Comments & trailing commas are allowed, like in TypeScript. Unknown options are ignored.
+	///
+	/// Parse errors are located in `tsconfig.json`; use [`load`](Self::load) for errors located in the actual file.
+	/// `extends` can't be resolved without a file to resolve it against, so it is rejected.
+	pub fn from_json(json: &str) -> Result<Self, TsconfigError> {
+		let tsconfig = Self::parse("tsconfig.json", json)?;
+		if Self::parse::<TsconfigExtends>("tsconfig.json", json)?.extends.is_some() {
+			return Err(extends_error("tsconfig.json", json, "\"extends\" requires a file to resolve against; use `TsconfigRaw::load`".to_string()));
+		}
+		Ok(tsconfig)
+	}
+
+	/// Reads & parses the tsconfig at `path`, along with the configs it `extends`. Options set in the file override
+	/// those it inherits. Relative `extends` paths are resolved against the directory of the file, and package names
+	/// against the `node_modules` directories above it.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, TsconfigError> {
+		Self::load_extended(path.as_ref(), &mut Vec::new())
+	}
+
+	fn load_extended(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Self, TsconfigError> {
+		let file = path.to_string_lossy();
+		let source = fs::read_to_string(path).map_err(TsconfigError::Io)?;
+		let tsconfig = Self::parse::<Self>(&file, &source)?;
+		let Some(extends) = Self::parse::<TsconfigExtends>(&file, &source)?.extends else {
+			return Ok(tsconfig);
+		};
+
+		let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+		chain.push(path.canonicalize().map_err(TsconfigError::Io)?);
+		let mut base = TsconfigRaw::default();
+		for specifier in extends.into_vec() {
+			let Some(base_path) = resolve_extends(dir, &specifier) else {
+				return Err(extends_error(&file, &source, format!("Cannot find base config file {specifier:?}")));
+			};
+			if chain.contains(&base_path) {
+				return Err(extends_error(&file, &source, format!("Base config file {specifier:?} extends itself")));
+			}
+			let mut extended = Self::load_extended(&base_path, chain)?;
+			// a relative `baseUrl` is relative to the config that sets it
+			if let Some(base_url) = extended.compiler_options.as_mut().and_then(|options| options.base_url.as_mut()) {
+				if let (Ok(dir), Some(base_dir)) = (dir.canonicalize(), base_path.parent()) {
+					if Path::new(base_url.as_str()).is_relative() {
+						*base_url = relative_path(&dir, &util::normalize_path(&base_dir.join(&*base_url)))
+							.to_string_lossy()
+							.replace('\\', "/");
+					}
+				}
+			}
+			base = base.extend(extended);
+		}
+		chain.pop();
+		Ok(base.extend(tsconfig))
+	}
+
+	/// Overrides the options of `self` with those set in `other`.
+	fn extend(self, other: Self) -> Self {
+		let compiler_options = match (self.compiler_options, other.compiler_options) {
+			(Some(base), Some(options)) => Some(CompilerOptions {
+				jsx: options.jsx.or(base.jsx),
+				jsx_factory: options.jsx_factory.or(base.jsx_factory),
+				jsx_fragment_factory: options.jsx_fragment_factory.or(base.jsx_fragment_factory),
+				jsx_import_source: options.jsx_import_source.or(base.jsx_import_source),
+				experimental_decorators: options.experimental_decorators.or(base.experimental_decorators),
+				use_define_for_class_fields: options.use_define_for_class_fields.or(base.use_define_for_class_fields),
+				verbatim_module_syntax: options.verbatim_module_syntax.or(base.verbatim_module_syntax),
+				paths: options.paths.or(base.paths),
+				base_url: options.base_url.or(base.base_url),
+				target: options.target.or(base.target)
+			}),
+			(base, options) => options.or(base)
+		};
+		Self { compiler_options }
+	}
+
+	fn parse<T: DeserializeOwned>(file: &str, source: &str) -> Result<T, TsconfigError> {
+		serde_json::from_str(&strip_jsonc(source)).map_err(|e| {
+			let line_text = source.lines().nth(e.line().saturating_sub(1)).unwrap_or_default();
+			// serde_json reports the 1-based column of the character that caused the error (or 0 at the end of a line)
+			let column = e.column().saturating_sub(1).min(line_text.len());
+			TsconfigError::Parse(Box::new(
+				MessageBuilder::new(format!("Invalid tsconfig: {}", strip_position(&e)))
+					.at(LocationBuilder::new(file, e.line() as i64, column as i64, 0).with_line_text(line_text))
+			))
+		})
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(self).expect("tsconfig is always serializable")
+	}
+}
+
+/// Resolves an `extends` specifier the way TypeScript does, minus `package.json` `exports` & `tsconfig` fields.
+fn resolve_extends(dir: &Path, specifier: &str) -> Option<PathBuf> {
+	let find = |path: PathBuf| {
+		let mut with_json = path.clone().into_os_string();
+		with_json.push(".json");
+		[path.clone(), PathBuf::from(with_json), path.join("tsconfig.json")]
+			.into_iter()
+			.find(|path| path.is_file())
+			.and_then(|path| path.canonicalize().ok())
+	};
+	if specifier.starts_with("./") || specifier.starts_with("../") || Path::new(specifier).is_absolute() {
+		return find(dir.join(specifier));
+	}
+	let dir = dir.canonicalize().ok()?;
+	dir.ancestors().find_map(|dir| find(dir.join("node_modules").join(specifier)))
+}
+
+/// Returns the path to `to` from the directory `from`; both must be absolute.
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+	let from = from.components().collect::<Vec<_>>();
+	let to = to.components().collect::<Vec<_>>();
+	let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+	let path = std::iter::repeat(Component::ParentDir)
+		.take(from.len() - common)
+		.chain(to[common..].iter().copied())
+		.collect::<PathBuf>();
+	if path.as_os_str().is_empty() { PathBuf::from(".") } else { path }
+}
+
+/// Creates an error located at the `extends` field of `source`.
+fn extends_error(file: &str, source: &str, text: String) -> TsconfigError {
+	let stripped = strip_jsonc(source);
+	let offset = stripped.find("\"extends\"").unwrap_or(0);
+	let line = source[..offset].matches('\n').count();
+	let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+	let line_text = source.lines().nth(line).unwrap_or_default();
+	let location = LocationBuilder::new(file, line as i64 + 1, (offset - line_start) as i64, "\"extends\"".len() as i64).with_line_text(line_text);
+	TsconfigError::Extends(Box::new(MessageBuilder::new(text).at(location)))
+}
+
+/// Formats a serde_json error without the ` at line X column Y` suffix, since the location is reported separately.
+fn strip_position(e: &serde_json::Error) -> String {
+	let message = e.to_string();
+	match message.rfind(" at line ") {
+		Some(i) => message[..i].to_string(),
+		None => message
+	}
+}
+
+/// Replaces comments & trailing commas with whitespace so the source can be parsed as JSON, without changing the
+/// position of anything else.
+fn strip_jsonc(source: &str) -> String {
+	let mut out = source.as_bytes().to_vec();
+	let mut i = 0;
+	let mut in_string = false;
+	while i < out.len() {
+		match (out[i], in_string) {
+			(b'\\', true) => i += 1,
+			(b'"', _) => in_string = !in_string,
+			(b'/', false) if matches!(out.get(i + 1), Some(b'/' | b'*')) => {
+				let end = if out[i + 1] == b'/' {
+					source[i..].find('\n').map_or(out.len(), |end| i + end)
+				} else {
+					source[i + 2..].find("*/").map_or(out.len(), |end| i + 2 + end + 2)
+				};
+				for c in &mut out[i..end] {
+					if *c != b'\n' {
+						*c = b' ';
+					}
+				}
+				i = end;
+				continue;
+			}
+			_ => {}
+		}
+		i += 1;
+	}
+
+	// comments are gone, so a comma is trailing if the next non-whitespace character closes an object or array
+	in_string = false;
+	i = 0;
+	while i < out.len() {
+		match (out[i], in_string) {
+			(b'\\', true) => i += 1,
+			(b'"', _) => in_string = !in_string,
+			(b',', false) => {
+				if matches!(out[i + 1..].iter().find(|c| !c.is_ascii_whitespace()), Some(b'}' | b']')) {
+					out[i] = b' ';
+				}
+			}
+			_ => {}
+		}
+		i += 1;
+	}
+	// only ASCII bytes were replaced, so the result is still valid UTF-8
+	String::from_utf8(out).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_tsconfig_raw() {
+		let tsconfig = TsconfigRaw::from_json(
+			r#"{
+				// comments are allowed
+				"compilerOptions": {
+					"jsx": "react-jsx",
+					"jsxImportSource": "preact", /* inline comment */
+					"experimentalDecorators": true,
+					"paths": { "@/*": ["./src/*"], },
+					"baseUrl": ".",
+					"strict": true,
+				},
+			}"#
+		)
+		.unwrap();
+		let compiler_options = tsconfig.compiler_options.as_ref().unwrap();
+		assert_eq!(compiler_options.jsx, Some(TsconfigJsx::ReactJsx));
+		assert_eq!(compiler_options.jsx_import_source.as_deref(), Some("preact"));
+		assert_eq!(compiler_options.paths.as_ref().unwrap()["@/*"], ["./src/*"]);
+		assert_eq!(TsconfigRaw::from_json(&tsconfig.to_json()).unwrap(), tsconfig);
+
+		let error = TsconfigRaw::from_json("{\n\t\"compilerOptions\": {\n\t\t\"jsx\": \"vue\"\n\t}\n}")
+			.unwrap_err()
+			.to_message();
+		assert!(error.text().starts_with("Invalid tsconfig: unknown variant `vue`"), "{}", error.text());
+		let location = error.location().unwrap();
+		assert_eq!((location.file(), location.line()), ("tsconfig.json", 3));
+
+		let error = TsconfigRaw::from_json(r#"{ "extends": "./base.json" }"#).unwrap_err();
+		assert!(matches!(error, TsconfigError::Extends(_)));
+	}
+
+	#[test]
+	fn test_tsconfig_extends() {
+		let tsconfig = TsconfigRaw::load("test/tsconfig/app/tsconfig.json").unwrap();
+		let compiler_options = tsconfig.compiler_options.unwrap();
+		assert_eq!(compiler_options.jsx, Some(TsconfigJsx::React));
+		assert_eq!(compiler_options.jsx_factory.as_deref(), Some("preact.h"));
+		assert_eq!(compiler_options.experimental_decorators, Some(true));
+		assert_eq!(compiler_options.use_define_for_class_fields, Some(true));
+		// relative to `app/`, where the options are now used
+		assert_eq!(compiler_options.base_url.as_deref(), Some(".."));
+
+		let error = TsconfigRaw::load("test/tsconfig/missing.json").unwrap_err().to_message();
+		assert_eq!(error.text(), "Cannot find base config file \"./nope.json\"");
+		let location = error.location().unwrap();
+		assert_eq!((location.file(), location.line(), location.column()), ("test/tsconfig/missing.json", 3, 1));
+
+		let error = TsconfigRaw::load("test/tsconfig/cycle.json").unwrap_err();
+		assert!(matches!(error, TsconfigError::Extends(_)), "{error}");
+	}
+}
@@ -1,13 +1,17 @@
 use std::{
-	fmt::{self, Debug, Write},
-	sync::{Arc, Mutex}
+	error::Error,
+	fmt::{self, Debug, Display, Write},
+	ptr,
+	sync::{Arc, Mutex},
+	time::Duration
 };
 
 use crate::{
 	build::{
+		BuildResult,
 		r#async::{BuildFuture, BuildFutureState},
-		options::BuildOptions,
-		BuildResult
+		cancel::{self, CancellationToken},
+		options::BuildOptions
 	},
 	error::Message,
 	sys, util
@@ -43,12 +47,125 @@ impl Debug for ContextError {
 	}
 }
 
+impl Display for ContextError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		Debug::fmt(self, f)
+	}
+}
+
+impl Error for ContextError {}
+
 impl Drop for ContextError {
 	fn drop(&mut self) {
 		unsafe { sys::Zsb_ContextResult_Destroy(self.0) };
 	}
 }
 
+#[derive(Debug, Clone)]
+pub struct WatchError(String);
+
+impl Display for WatchError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl Error for WatchError {}
+
+#[derive(Debug, Clone)]
+pub struct ServeError(String);
+
+impl Display for ServeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl Error for ServeError {}
+
+#[derive(Default, Debug, Clone)]
+pub struct ServeOptions {
+	host: Option<String>,
+	port: u16,
+	servedir: Option<String>,
+	keyfile: Option<String>,
+	certfile: Option<String>,
+	fallback: Option<String>
+}
+
+impl ServeOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn with_host(mut self, host: impl ToString) -> Self {
+		self.host = Some(host.to_string());
+		self
+	}
+
+	pub fn with_port(mut self, port: u16) -> Self {
+		self.port = port;
+		self
+	}
+
+	pub fn with_servedir(mut self, servedir: impl ToString) -> Self {
+		self.servedir = Some(servedir.to_string());
+		self
+	}
+
+	pub fn with_tls(mut self, keyfile: impl ToString, certfile: impl ToString) -> Self {
+		self.keyfile = Some(keyfile.to_string());
+		self.certfile = Some(certfile.to_string());
+		self
+	}
+
+	pub fn with_fallback(mut self, fallback: impl ToString) -> Self {
+		self.fallback = Some(fallback.to_string());
+		self
+	}
+
+	fn as_ffi(&self) -> sys::ServeOptions {
+		fn str_parts(s: &Option<String>) -> (*const i8, usize) {
+			s.as_deref().map(|s| (s.as_ptr().cast(), s.len())).unwrap_or((ptr::null(), 0))
+		}
+
+		let (host, host_len) = str_parts(&self.host);
+		let (servedir, servedir_len) = str_parts(&self.servedir);
+		let (keyfile, keyfile_len) = str_parts(&self.keyfile);
+		let (certfile, certfile_len) = str_parts(&self.certfile);
+		let (fallback, fallback_len) = str_parts(&self.fallback);
+		sys::ServeOptions {
+			host,
+			host_len,
+			port: self.port,
+			servedir,
+			servedir_len,
+			keyfile,
+			keyfile_len,
+			certfile,
+			certfile_len,
+			fallback,
+			fallback_len
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct ServeResult {
+	host: String,
+	port: u16
+}
+
+impl ServeResult {
+	pub fn host(&self) -> &str {
+		&self.host
+	}
+
+	pub fn port(&self) -> u16 {
+		self.port
+	}
+}
+
 pub(crate) struct SharedContextHandle(pub(crate) u64);
 
 impl SharedContextHandle {
@@ -69,6 +186,14 @@ impl Drop for SharedContextHandle {
 	}
 }
 
+fn timeout_ms(timeout: Option<Duration>) -> i64 {
+	match timeout {
+		// a zero timeout is treated as "no timeout" by the Go side, so round sub-millisecond timeouts up instead.
+		Some(timeout) => timeout.as_millis().clamp(1, i64::MAX as u128) as i64,
+		None => 0
+	}
+}
+
 #[derive(Clone, Debug)]
 pub struct Context {
 	handle: Arc<SharedContextHandle>
@@ -95,17 +220,82 @@ impl Context {
 		unsafe { sys::Zsb_Context_Cancel(self.handle()) };
 	}
 
+	fn build_inner(&self, token: Option<&CancellationToken>, timeout: Option<Duration>) -> BuildResult {
+		let build_id = cancel::next_build_id();
+		unsafe { sys::Zsb_Context_PrepareBuild(self.handle(), build_id) };
+		if let Some(token) = token {
+			token.register(&self.handle, build_id);
+		}
+		let res = BuildResult::new(unsafe { sys::Zsb_Context_Build(self.handle(), build_id, timeout_ms(timeout)) });
+		if let Some(token) = token {
+			token.unregister(build_id);
+		}
+		res
+	}
+
 	pub fn build(&self) -> BuildResult {
-		BuildResult::new(unsafe { sys::Zsb_Context_Build(self.handle()) })
+		self.build_inner(None, None)
 	}
 
-	pub fn build_async(&self) -> BuildFuture {
+	pub fn build_with_token(&self, token: &CancellationToken) -> BuildResult {
+		self.build_inner(Some(token), None)
+	}
+
+	pub fn build_with_timeout(&self, timeout: Duration) -> BuildResult {
+		self.build_inner(None, Some(timeout))
+	}
+
+	pub fn watch(&self) -> Result<(), WatchError> {
+		match unsafe { util::take_error(sys::Zsb_Context_Watch(self.handle())) } {
+			Some(e) => Err(WatchError(e)),
+			None => Ok(())
+		}
+	}
+
+	pub fn serve(&self, options: &ServeOptions) -> Result<ServeResult, ServeError> {
+		let mut ffi_options = options.as_ffi();
+		let mut res = sys::ServeResult {
+			host: ptr::null_mut(),
+			host_len: 0,
+			port: 0
+		};
+		if let Some(e) = unsafe { util::take_error(sys::Zsb_Context_Serve(self.handle(), &mut ffi_options, &mut res)) } {
+			return Err(ServeError(e));
+		}
+		let host = unsafe { util::as_str_or_empty(res.host, res.host_len) }.to_string();
+		unsafe { sys::Zsb_ServeResult_Destroy(&mut res) };
+		Ok(ServeResult { host, port: res.port })
+	}
+
+	fn build_async_inner(&self, token: Option<&CancellationToken>, timeout: Option<Duration>) -> BuildFuture {
+		let build_id = cancel::next_build_id();
+		let token = token.cloned();
+		// prepared up front, so dropping the future before the build starts still cancels it
+		unsafe { sys::Zsb_Context_PrepareBuild(self.handle(), build_id) };
+		if let Some(token) = &token {
+			token.register(&self.handle, build_id);
+		}
 		let state = Arc::new(Mutex::new(BuildFutureState::default()));
 		let _state = state.clone();
-		crate::build::r#async::context_build_async_inner(Arc::clone(&self.handle), move |res| {
+		crate::build::r#async::context_build_async_inner(Arc::clone(&self.handle), build_id, timeout_ms(timeout), move |res| {
+			if let Some(token) = token {
+				token.unregister(build_id);
+			}
 			let mut state = _state.lock().unwrap();
 			state.set_and_wake(res);
 		});
-		BuildFuture::new(state, Some(&self.handle))
+		BuildFuture::new(state, Some((&self.handle, build_id)))
+	}
+
+	pub fn build_async(&self) -> BuildFuture {
+		self.build_async_inner(None, None)
+	}
+
+	pub fn build_async_with_token(&self, token: &CancellationToken) -> BuildFuture {
+		self.build_async_inner(Some(token), None)
+	}
+
+	pub fn build_async_with_timeout(&self, timeout: Duration) -> BuildFuture {
+		self.build_async_inner(None, Some(timeout))
 	}
 }
@@ -7,6 +7,11 @@ pub unsafe extern "C" fn Zsb_BuildAsyncCallback_Dispatch(callback: sys::BuildAsy
 	callback.unwrap()(result, data)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn Zsb_LogCallback_Dispatch(callback: sys::LogCallback, level: u8, message: *mut sys::Message) {
+	callback.unwrap()(level, message)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn Zsb_PluginBuildCallback_Dispatch(callback: sys::PluginBuildCallback, handle: u64, data: *mut c_void) {
 	callback.unwrap()(handle, data)
@@ -26,3 +31,45 @@ pub unsafe extern "C" fn Zsb_PluginCallbackOnStart_Dispatch(callback: sys::Plugi
 pub unsafe extern "C" fn Zsb_PluginOnStartResult_Destroy(res: *mut sys::PluginOnStartResult) {
 	crate::plugin::OnStartResult::drop_ffi(res)
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn Zsb_PluginCallbackOnResolve_Dispatch(
+	callback: sys::PluginCallbackOnResolve,
+	args: *mut sys::PluginOnResolveArgs,
+	data: *mut c_void
+) -> *mut sys::PluginOnResolveResult {
+	callback.unwrap()(args, data)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn Zsb_PluginOnResolveResult_Destroy(res: *mut sys::PluginOnResolveResult) {
+	crate::plugin::OnResolveResult::drop_ffi(res)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn Zsb_PluginCallbackOnLoad_Dispatch(
+	callback: sys::PluginCallbackOnLoad,
+	args: *mut sys::PluginOnLoadArgs,
+	data: *mut c_void
+) -> *mut sys::PluginOnLoadResult {
+	callback.unwrap()(args, data)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn Zsb_PluginOnLoadResult_Destroy(res: *mut sys::PluginOnLoadResult) {
+	crate::plugin::OnLoadResult::drop_ffi(res)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn Zsb_PluginCallbackOnEnd_Dispatch(
+	callback: sys::PluginCallbackOnEnd,
+	result: *mut sys::BuildResult,
+	data: *mut c_void
+) -> *mut sys::PluginOnEndResult {
+	callback.unwrap()(result, data)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn Zsb_PluginOnEndResult_Destroy(res: *mut sys::PluginOnEndResult) {
+	crate::plugin::OnEndResult::drop_ffi(res)
+}
@@ -67,8 +67,8 @@ impl<'s> Note<'s> {
 		unsafe { util::as_str_or_empty(self.0.text, self.0.text_len) }
 	}
 
-	pub fn location(&self) -> Location {
-		Location(self.0.location, PhantomData)
+	pub fn location(&self) -> Option<Location> {
+		(!self.0.location.is_null()).then_some(Location(self.0.location, PhantomData))
 	}
 }
 
@@ -160,6 +160,18 @@ impl LocationBuilder {
 		self.suggestion = Some(suggestion.to_string());
 		self
 	}
+
+	pub fn file(&self) -> &str {
+		&self.file
+	}
+
+	pub fn line(&self) -> i64 {
+		self.line
+	}
+
+	pub fn column(&self) -> i64 {
+		self.column
+	}
 }
 
 impl IntoFFI for LocationBuilder {
@@ -226,6 +238,14 @@ impl NoteBuilder {
 		self.location = Some(location);
 		self
 	}
+
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	pub fn location(&self) -> Option<&LocationBuilder> {
+		self.location.as_ref()
+	}
 }
 
 impl IntoFFI for NoteBuilder {
@@ -279,6 +299,31 @@ impl MessageBuilder {
 		self.plugin_name = Some(plugin.to_string());
 		self
 	}
+
+	pub fn at(mut self, location: LocationBuilder) -> Self {
+		self.location = Some(location);
+		self
+	}
+
+	pub fn id(&self) -> Option<&str> {
+		self.id.as_deref()
+	}
+
+	pub fn plugin_name(&self) -> Option<&str> {
+		self.plugin_name.as_deref()
+	}
+
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	pub fn location(&self) -> Option<&LocationBuilder> {
+		self.location.as_ref()
+	}
+
+	pub fn notes(&self) -> &[NoteBuilder] {
+		&self.notes
+	}
 }
 
 impl IntoFFI for MessageBuilder {
@@ -297,6 +342,12 @@ impl IntoFFI for MessageBuilder {
 			text_len: 0
 		};
 		(message.text_len, message.text) = util::decompose_string(self.text);
+		if let Some(id) = self.id {
+			(message.id_len, message.id) = util::decompose_string(id);
+		}
+		if let Some(plugin_name) = self.plugin_name {
+			(message.plugin_name_len, message.plugin_name) = util::decompose_string(plugin_name);
+		}
 		if !self.notes.is_empty() {
 			(message.notes_len, message.notes) = util::decompose_vec(self.notes.into_iter().map(NoteBuilder::into_ffi).collect());
 		}
@@ -305,6 +356,15 @@ impl IntoFFI for MessageBuilder {
 
 	unsafe fn drop_ffi(message: Self::FFIType) {
 		util::drop_decomposed_string(message.text, message.text_len);
+		if !message.id.is_null() {
+			util::drop_decomposed_string(message.id, message.id_len);
+		}
+		if !message.plugin_name.is_null() {
+			util::drop_decomposed_string(message.plugin_name, message.plugin_name_len);
+		}
+		if !message.location.is_null() {
+			LocationBuilder::drop_ffi(message.location);
+		}
 		if message.notes_len > 0 {
 			for note in util::recompose_vec(message.notes, message.notes_len) {
 				NoteBuilder::drop_ffi(note);
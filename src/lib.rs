@@ -2,16 +2,48 @@ mod build;
 mod context;
 mod dispatch;
 mod error;
+mod log;
 mod plugin;
 #[doc(hidden)]
 pub mod sys;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod transform;
 mod util;
 
 pub use self::{
-	build::{r#async::BuildFuture, build, options::BuildOptions, BuildResult},
-	context::{Context, ContextError},
-	error::{Location, Message, Note},
-	plugin::{IntoPluginDescriptor, Plugin, PluginBuilder, PluginDescriptor}
+	build::{
+		BuildResult, OutputFile, OwnedOutputFile,
+		r#async::BuildFuture,
+		budget::{BudgetCompression, BudgetTarget, SizeBudget, SizeBudgets},
+		build, build_async,
+		cancel::CancellationToken,
+		chunks::{Chunk, ChunkGraph},
+		define::DefineEnv,
+		graph::{DependencyGraph, DuplicatePackage, PackageInstance},
+		html::{HtmlEntry, HtmlError},
+		integrity::SriAlgorithm,
+		licenses::{LicenseReport, PackageLicense},
+		mangle::{MangleCache, MangleCacheEntry},
+		manifest::{AssetManifest, ManifestChunk},
+		metafile::{Metafile, MetafileImport, MetafileInput, MetafileOutput, MetafileOutputInput},
+		naming::{PathTemplate, PathTemplateError},
+		options::{BuildOptions, Format, Jsx, LegalComments, Loader, Packages, Platform, SourceMap},
+		precompress::Precompress,
+		resolve::{Alias, ExternalPattern, ResolveExtension, ResolveOptionError},
+		sourcemap::{Mapping, SourceMapConsumer, SourceMapError},
+		stats::{BuildStats, OutputStats},
+		target::{Engine, EsVersion, ParseTargetError, Target, Version},
+		tsconfig::{CompilerOptions, TsconfigError, TsconfigJsx, TsconfigRaw}
+	},
+	context::{Context, ContextError, ServeError, ServeOptions, ServeResult, WatchError},
+	error::{Location, LocationBuilder, Message, MessageBuilder, Note, NoteBuilder},
+	log::LogLevel,
+	plugin::{
+		Filter, IntoPluginDescriptor, OnEndResult, OnLoadArgs, OnLoadResult, OnResolveArgs, OnResolveResult, OnStartResult, Plugin, PluginBuilder, PluginData,
+		PluginDescriptor, PluginHook, PluginTiming, ResolveKind
+	},
+	transform::{TransformOptions, TransformResult, transform}
 };
 
 #[cfg(all(target_family = "windows", target_arch = "x86_64", target_env = "msvc"))]
@@ -30,13 +62,9 @@ pub static _ZSB_GORUNTIME_INIT: unsafe extern "C" fn() = {
 
 #[cfg(test)]
 mod tests {
-	use std::sync::Arc;
+	use std::{sync::Arc, time::Duration};
 
-	use super::{
-		error::{MessageBuilder, NoteBuilder},
-		plugin::OnStartResult,
-		*
-	};
+	use super::*;
 
 	pub struct TestPlugin {
 		pub the_number: Arc<usize>
@@ -96,6 +124,118 @@ mod tests {
 		assert_eq!(res.errors()[0].text(), "test error");
 	}
 
+	struct VirtualModulePlugin;
+
+	struct VirtualModule {
+		answer: u32
+	}
+
+	impl Plugin for VirtualModulePlugin {
+		fn name(&self) -> &str {
+			"virtual"
+		}
+
+		fn build(&self, builder: &mut PluginBuilder) {
+			builder.on_resolve("^virtual:answer$", |args| {
+				OnResolveResult::path(args.path())
+					.with_namespace("virtual")
+					.with_plugin_data(VirtualModule { answer: 42 })
+			});
+			builder.on_load(Filter::new(".*").with_namespace("virtual"), |args| match args.plugin_data_as::<VirtualModule>() {
+				Some(module) => OnLoadResult::contents(format!("export default {};", module.answer)).with_loader(Loader::Js),
+				None => OnLoadResult::error(MessageBuilder::new("missing plugin data"))
+			});
+		}
+	}
+
+	#[test]
+	fn test_plugin_data() {
+		let res = build(
+			&BuildOptions::new()
+				.entry_point("virtual:answer", "out.js")
+				.plugin(VirtualModulePlugin)
+				.bundle(true)
+				.write(false)
+		);
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+		assert!(res.outputs()[0].contents_str().unwrap().contains("42"));
+
+		let timings = res.plugin_timings();
+		assert!(timings.iter().all(|t| t.plugin() == "virtual"));
+		let on_load = timings.iter().find(|t| t.hook() == PluginHook::OnLoad).unwrap();
+		assert_eq!((on_load.filter(), on_load.calls()), (Some(".*"), 1));
+	}
+
+	#[test]
+	fn test_asset_manifest_integrity() {
+		let res = build(
+			&BuildOptions::new()
+				.entry_points(["test/chunks/a.js", "test/chunks/b.js"])
+				.bundle(true)
+				.splitting(true)
+				.format(Format::EsModule)
+				.outdir("test/chunks/out")
+				.metafile(true)
+				.write(false)
+		);
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+		let manifest = res.asset_manifest().unwrap().with_integrity(res.outputs(), SriAlgorithm::Sha384);
+		let a = manifest.get("test/chunks/a.js").unwrap();
+		let output = res.outputs().iter().find(|output| output.path().ends_with("out/a.js")).unwrap();
+		assert_eq!(a.integrity[&a.file], output.integrity(SriAlgorithm::Sha384));
+		assert_eq!(a.integrity.len(), 2);
+
+		let manifest = manifest.relative_to("test/chunks/out");
+		let a = manifest.get("test/chunks/a.js").unwrap();
+		assert_eq!(a.integrity.keys().collect::<Vec<_>>(), ["a.css", "a.js"]);
+	}
+
+	#[test]
+	fn test_build_stats() {
+		let res = build(
+			&BuildOptions::new()
+				.entry_point("test/main.js", "out.js")
+				.bundle(true)
+				.metafile(true)
+				.write(false)
+		);
+		let stats = res.stats();
+		assert_eq!(stats.input_files(), Some(2));
+		assert_eq!(stats.outputs().len(), 1);
+		assert_eq!(stats.output_bytes(), res.outputs()[0].contents().len() as u64);
+		assert!(stats.outputs()[0].gzip_bytes().is_some());
+	}
+
+	#[test]
+	fn test_define() {
+		#[derive(serde::Serialize)]
+		struct Config {
+			debug: bool
+		}
+
+		let res = build(
+			&BuildOptions::new()
+				.entry_point("test/env.js", "out.js")
+				.format(Format::EsModule)
+				.define_value("__CONFIG__", &Config { debug: true })
+				.define_env(DefineEnv::new().value("API_URL", "https://example.com"))
+				.write(false)
+		);
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+		let output = res.outputs()[0].contents_str().unwrap();
+		assert!(output.contains("\"https://example.com\"") && output.contains("true"));
+
+		let warning = res.warnings().iter().find(|w| w.id() == Some("undefined-env")).unwrap();
+		assert_eq!(warning.text(), "\"import.meta.env.MISSING\" is used but not defined");
+		assert_eq!(warning.location().unwrap().line(), 2);
+	}
+
 	#[test]
 	fn test_bad() {
 		let context = Context::new(&BuildOptions::new().entry_point("test/not_exist.js", "out.js").bundle(true)).unwrap();
@@ -107,6 +247,257 @@ mod tests {
 		assert_eq!(errors[0].text(), "Could not resolve \"test/not_exist.js\"");
 	}
 
+	#[test]
+	fn test_mangle_cache() {
+		let options = || {
+			BuildOptions::new()
+				.entry_point("test/mangle.js", "out.js")
+				.mangle_props("_$")
+				.write(false)
+		};
+		let res = build(&options());
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+		let cache = res.mangle_cache().unwrap();
+		assert_eq!(cache.len(), 3);
+		assert!(matches!(cache.get("count_"), Some(MangleCacheEntry::Mangled(_))));
+		assert!(!res.outputs()[0].contents_str().unwrap().contains("count_"));
+
+		// feed the cache (after a trip through JSON) back in, pinning one name and reserving another
+		let mut cache = MangleCache::from_json(&cache.to_json()).unwrap();
+		cache.insert("count_", MangleCacheEntry::Mangled("pinned".to_string()));
+		cache.insert("keep_", MangleCacheEntry::Reserved);
+		let res = build(&options().mangle_cache(&cache));
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+		let contents = res.outputs()[0].contents_str().unwrap();
+		assert!(contents.contains("state.pinned") && contents.contains("state.keep_"), "{contents}");
+		assert_eq!(res.mangle_cache().unwrap(), cache);
+	}
+
+	#[test]
+	fn test_bad_tsconfig() {
+		let res = build(
+			&BuildOptions::new()
+				.entry_point("test/main.js", "out.js")
+				.tsconfig("test/tsconfig/bad.json")
+				.write(false)
+		);
+		assert!(res.is_error());
+		let location = res.errors()[0].location().unwrap();
+		assert!(location.file().unwrap().ends_with("bad.json"), "{:?}", location.file());
+		assert_eq!(location.line(), 3);
+	}
+
+	#[test]
+	fn test_cancellation_token() {
+		let context = Context::new(&BuildOptions::new().entry_point("test/main.js", "out.js").bundle(true)).unwrap();
+
+		let token = CancellationToken::new();
+		let res = context.build_with_token(&token);
+		assert!(!res.is_cancelled());
+
+		token.cancel();
+		let res = context.build_with_token(&token);
+		assert!(res.is_cancelled());
+
+		// other builds on the same context are unaffected
+		let res = context.build();
+		assert!(!res.is_cancelled());
+		assert!(!res.is_error());
+	}
+
+	struct SlowPlugin {
+		delay: Duration
+	}
+
+	impl Plugin for SlowPlugin {
+		fn name(&self) -> &str {
+			"slow"
+		}
+
+		fn build(&self, builder: &mut PluginBuilder) {
+			let delay = self.delay;
+			builder.on_resolve("^slow$", |args| OnResolveResult::path(args.path()).with_namespace("slow"));
+			builder.on_load(Filter::new(".*").with_namespace("slow"), move |_| {
+				std::thread::sleep(delay);
+				OnLoadResult::contents("export default 1;").with_loader(Loader::Js)
+			});
+		}
+	}
+
+	#[test]
+	fn test_build_timeout() {
+		let context = Context::new(
+			&BuildOptions::new()
+				.entry_point("slow", "out.js")
+				.plugin(SlowPlugin { delay: Duration::from_millis(500) })
+				.bundle(true)
+				.write(false)
+		)
+		.unwrap();
+
+		let res = context.build_with_timeout(Duration::from_millis(50));
+		assert!(res.is_cancelled());
+
+		// the timeout must not leak into the next build on the same context
+		let res = context.build_with_timeout(Duration::from_secs(30));
+		assert!(!res.is_cancelled());
+		assert!(!res.is_error());
+	}
+
+	#[test]
+	fn test_cancel_running_build() {
+		let context = Context::new(
+			&BuildOptions::new()
+				.entry_point("slow", "out.js")
+				.plugin(SlowPlugin { delay: Duration::from_millis(500) })
+				.bundle(true)
+				.write(false)
+		)
+		.unwrap();
+
+		let token = CancellationToken::new();
+		let res = std::thread::scope(|s| {
+			let build = s.spawn(|| context.build_with_token(&token));
+			std::thread::sleep(Duration::from_millis(50));
+			token.cancel();
+			build.join().unwrap()
+		});
+		assert!(res.is_cancelled());
+
+		let res = context.build();
+		assert!(!res.is_cancelled());
+		assert!(!res.is_error());
+	}
+
+	#[test]
+	fn test_plugin_timings_per_context() {
+		let options = BuildOptions::new()
+			.entry_point("slow", "out.js")
+			.plugin(SlowPlugin { delay: Duration::from_millis(200) })
+			.bundle(true)
+			.write(false);
+		let a = Context::new(&options).unwrap();
+		let b = Context::new(&options).unwrap();
+
+		// overlapping builds of contexts created from the same options each only see their own timings
+		let results = std::thread::scope(|s| {
+			let a = s.spawn(|| a.build());
+			let b = s.spawn(|| b.build());
+			[a.join().unwrap(), b.join().unwrap()]
+		});
+		for res in &results {
+			let on_load = res.plugin_timings().iter().find(|t| t.hook() == PluginHook::OnLoad).unwrap();
+			assert_eq!(on_load.calls(), 1);
+		}
+	}
+
+	#[test]
+	#[tracing_test::traced_test]
+	fn test_log_to_tracing() {
+		let context = Context::new(
+			&BuildOptions::new()
+				.entry_point("test/not_exist.js", "out.js")
+				.bundle(true)
+				.log_to_tracing()
+				.log_level(LogLevel::Warning)
+		)
+		.unwrap();
+
+		let res = context.build();
+		assert!(res.is_error());
+		assert!(logs_contain("Could not resolve"));
+	}
+
+	#[test]
+	#[tracing_test::traced_test]
+	fn test_log_to_tracing_silent() {
+		let context = Context::new(
+			&BuildOptions::new()
+				.entry_point("test/not_exist.js", "out.js")
+				.bundle(true)
+				.log_to_tracing()
+				.log_level(LogLevel::Silent)
+		)
+		.unwrap();
+
+		let res = context.build();
+		assert!(res.is_error());
+		assert!(!logs_contain("Could not resolve"));
+	}
+
+	#[test]
+	#[tracing_test::traced_test]
+	fn test_log_to_tracing_plugin_messages() {
+		// plugins added after `log_to_tracing` still have their `on_end` messages forwarded
+		let context = Context::new(
+			&BuildOptions::new()
+				.entry_point("test/main.js", "main")
+				.outdir("test/out")
+				.bundle(true)
+				.write(false)
+				.log_to_tracing()
+				.log_level(LogLevel::Warning)
+				.size_budgets(SizeBudgets::new().budget(SizeBudget::entries(1)))
+		)
+		.unwrap();
+
+		let res = context.build();
+		assert!(res.is_error());
+		assert!(logs_contain("exceeds its size budget"));
+		assert!(!logs_contain("build started"));
+	}
+
+	#[test]
+	#[tracing_test::traced_test]
+	fn test_log_to_tracing_watch() {
+		let dir = std::env::temp_dir().join(format!("zsbuild-watch-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let entry = dir.join("main.js");
+		std::fs::write(&entry, "console.log(1);\n").unwrap();
+
+		let context = Context::new(
+			&BuildOptions::new()
+				.entry_point(entry.to_str().unwrap(), "out.js")
+				.bundle(true)
+				.write(false)
+				.log_to_tracing()
+				.log_level(LogLevel::Info)
+		)
+		.unwrap();
+		context.watch().unwrap();
+
+		let wait_for = |text: &str| {
+			let start = std::time::Instant::now();
+			while !logs_contain(text) {
+				assert!(start.elapsed() < Duration::from_secs(10), "no \"{text}\" event");
+				std::thread::sleep(Duration::from_millis(50));
+			}
+		};
+		wait_for("build started");
+		std::fs::write(&entry, "console.log(2);\n").unwrap();
+		wait_for("rebuild started");
+
+		drop(context);
+		std::fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_build_async() {
+		let future = {
+			let options = BuildOptions::new().entry_point("test/main.js", "out.js").bundle(true);
+			build_async(&options)
+		};
+		// the options were dropped before the build completed; the future must have kept them alive.
+		let res = future.await;
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+	}
+
 	#[tokio::test]
 	async fn test_async() {
 		let Ok(ctx) = Context::new(
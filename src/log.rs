@@ -0,0 +1,60 @@
+use tracing::Level;
+
+use crate::{error::Message, sys};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum LogLevel {
+	Verbose = 0,
+	Debug = 1,
+	Info = 2,
+	Warning = 3,
+	Error = 4,
+	#[default]
+	Silent = 5
+}
+
+impl LogLevel {
+	fn from_u8(level: u8) -> Option<Self> {
+		Some(match level {
+			0 => LogLevel::Verbose,
+			1 => LogLevel::Debug,
+			2 => LogLevel::Info,
+			3 => LogLevel::Warning,
+			4 => LogLevel::Error,
+			5 => LogLevel::Silent,
+			_ => return None
+		})
+	}
+}
+
+macro_rules! forward {
+	($level:expr, $message:expr) => {{
+		let message = $message;
+		let location = message.location();
+		tracing::event!(
+			$level,
+			file = location.as_ref().and_then(|l| l.file()),
+			line = location.as_ref().map(|l| l.line()),
+			column = location.as_ref().map(|l| l.column()),
+			id = message.id(),
+			plugin = message.plugin_name(),
+			"{}",
+			message.text()
+		)
+	}};
+}
+
+pub(crate) extern "C" fn forward_to_tracing(level: u8, message: *mut sys::Message) {
+	// SAFETY: `Message` is `repr(transparent)` over `sys::Message`, and the message is only borrowed for the duration of
+	// this call.
+	let message = unsafe { &*message.cast_const().cast::<Message>() };
+	match LogLevel::from_u8(level) {
+		Some(LogLevel::Verbose) => forward!(Level::TRACE, message),
+		Some(LogLevel::Debug) => forward!(Level::DEBUG, message),
+		Some(LogLevel::Info) => forward!(Level::INFO, message),
+		Some(LogLevel::Warning) => forward!(Level::WARN, message),
+		Some(LogLevel::Error) => forward!(Level::ERROR, message),
+		Some(LogLevel::Silent) | None => {}
+	}
+}
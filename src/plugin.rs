@@ -1,15 +1,51 @@
-use std::{ffi::c_void, ptr, slice};
+use std::{any::Any, ffi::c_void, fmt, marker::PhantomData, mem::ManuallyDrop, ptr, time::Duration};
 
 use tracing::Level;
 
-use crate::{error::MessageBuilder, sys, util::IntoFFI};
+use crate::{
+	build::{BuildResult, options::Loader},
+	error::MessageBuilder,
+	sys,
+	util::{self, IntoFFI}
+};
 
 pub trait OnStartCallback: FnMut() -> OnStartResult + Send + Sync + 'static {}
 impl<F: FnMut() -> OnStartResult + Send + Sync + 'static> OnStartCallback for F {}
 
+// resolve & load callbacks may be called concurrently from multiple esbuild goroutines, hence `Fn`.
+pub trait OnResolveCallback: Fn(&OnResolveArgs<'_>) -> OnResolveResult + Send + Sync + 'static {}
+impl<F: Fn(&OnResolveArgs<'_>) -> OnResolveResult + Send + Sync + 'static> OnResolveCallback for F {}
+
+pub trait OnLoadCallback: Fn(&OnLoadArgs<'_>) -> OnLoadResult + Send + Sync + 'static {}
+impl<F: Fn(&OnLoadArgs<'_>) -> OnLoadResult + Send + Sync + 'static> OnLoadCallback for F {}
+
+pub trait OnEndCallback: FnMut(&BuildResult) -> OnEndResult + Send + Sync + 'static {}
+impl<F: FnMut(&BuildResult) -> OnEndResult + Send + Sync + 'static> OnEndCallback for F {}
+
+/// Arbitrary data passed from an `on_resolve` callback to the `on_load` callback for the same module (or from an
+/// `on_load` callback to the `on_resolve` callbacks for that module's imports). It is dropped once the build ends.
+pub type PluginData = Box<dyn Any + Send + Sync>;
+
 type PluginDescriptorBuildCallback = dyn FnMut(&mut PluginBuilder) + Send;
-struct PluginCallbacks {
-	on_start: Vec<*mut Box<dyn OnStartCallback>>
+pub(crate) struct PluginCallbacks {
+	/// The name of the plugin the callbacks belong to, for tracing.
+	pub(crate) name: String,
+	pub(crate) on_start: Vec<*mut Box<dyn OnStartCallback>>,
+	pub(crate) on_resolve: Vec<(Filter, *mut Box<dyn OnResolveCallback>)>,
+	pub(crate) on_load: Vec<(Filter, *mut Box<dyn OnLoadCallback>)>,
+	pub(crate) on_end: Vec<*mut Box<dyn OnEndCallback>>
+}
+
+impl PluginCallbacks {
+	pub(crate) fn new(name: impl ToString) -> Self {
+		Self {
+			name: name.to_string(),
+			on_start: Vec::new(),
+			on_resolve: Vec::new(),
+			on_load: Vec::new(),
+			on_end: Vec::new()
+		}
+	}
 }
 
 impl Drop for PluginCallbacks {
@@ -21,6 +57,61 @@ impl Drop for PluginCallbacks {
 			tracing::trace!("Dropping on_start callback @ {:?}", cb);
 			drop(unsafe { Box::from_raw(cb) });
 		}
+		for (_, cb) in self.on_resolve.drain(..) {
+			tracing::trace!("Dropping on_resolve callback @ {:?}", cb);
+			drop(unsafe { Box::from_raw(cb) });
+		}
+		for (_, cb) in self.on_load.drain(..) {
+			tracing::trace!("Dropping on_load callback @ {:?}", cb);
+			drop(unsafe { Box::from_raw(cb) });
+		}
+		for cb in self.on_end.drain(..) {
+			tracing::trace!("Dropping on_end callback @ {:?}", cb);
+			drop(unsafe { Box::from_raw(cb) });
+		}
+	}
+}
+
+fn messages_into_ffi(messages: Vec<MessageBuilder>) -> (usize, *mut sys::Message) {
+	if messages.is_empty() {
+		return (0, ptr::null_mut());
+	}
+	util::decompose_vec(messages.into_iter().map(MessageBuilder::into_ffi).collect())
+}
+
+unsafe fn drop_messages_ffi(messages: *mut sys::Message, len: usize) {
+	if len > 0 {
+		for message in util::recompose_vec(messages, len) {
+			MessageBuilder::drop_ffi(message);
+		}
+	}
+}
+
+fn plugin_data_into_ffi(data: Option<PluginData>) -> (*mut c_void, sys::PluginDestructor) {
+	extern "C" fn drop_plugin_data(data: *mut c_void) {
+		drop(unsafe { Box::from_raw(data.cast::<PluginData>()) });
+	}
+
+	match data {
+		Some(data) => (Box::into_raw(Box::new(data)).cast(), Some(drop_plugin_data)),
+		None => (ptr::null_mut(), None)
+	}
+}
+
+unsafe fn plugin_data_ref<'a>(data: *mut c_void) -> Option<&'a (dyn Any + Send + Sync)> {
+	data.cast::<PluginData>().as_ref().map(|data| &**data)
+}
+
+fn string_into_ffi(s: Option<String>) -> (usize, *const i8) {
+	match s {
+		Some(s) => util::decompose_string(s),
+		None => (0, ptr::null())
+	}
+}
+
+unsafe fn drop_string_ffi(s: *const i8, len: usize) {
+	if !s.is_null() {
+		util::drop_decomposed_string(s, len);
 	}
 }
 
@@ -57,66 +148,556 @@ impl OnStartResult {
 	}
 }
 
+impl OnStartResult {
+	pub fn errors(&self) -> &[MessageBuilder] {
+		&self.errors
+	}
+
+	pub fn warnings(&self) -> &[MessageBuilder] {
+		&self.warnings
+	}
+}
+
 impl IntoFFI for OnStartResult {
 	type FFIType = *mut sys::PluginOnStartResult;
 
 	fn into_ffi(self) -> Self::FFIType {
-		let mut res = sys::PluginOnStartResult {
-			errors: ptr::null_mut(),
-			errors_len: 0,
-			warnings: ptr::null_mut(),
-			warnings_len: 0
-		};
-		if !self.errors.is_empty() {
-			let errors = self.errors.into_iter().map(|c| c.into_ffi()).collect::<Vec<_>>().into_boxed_slice();
-			(res.errors_len, res.errors) = (errors.len(), Box::into_raw(errors).cast::<sys::Message>());
+		let (errors_len, errors) = messages_into_ffi(self.errors);
+		let (warnings_len, warnings) = messages_into_ffi(self.warnings);
+		Box::into_raw(Box::new(sys::PluginOnStartResult {
+			errors,
+			errors_len,
+			warnings,
+			warnings_len
+		}))
+	}
+
+	unsafe fn drop_ffi(res: Self::FFIType) {
+		let res = Box::from_raw(res);
+		drop_messages_ffi(res.errors, res.errors_len);
+		drop_messages_ffi(res.warnings, res.warnings_len);
+	}
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct OnEndResult {
+	errors: Vec<MessageBuilder>,
+	warnings: Vec<MessageBuilder>
+}
+
+impl OnEndResult {
+	pub fn ok() -> Self {
+		OnEndResult::default()
+	}
+
+	pub fn error(message: MessageBuilder) -> Self {
+		OnEndResult {
+			errors: vec![message],
+			..Default::default()
 		}
-		if !self.warnings.is_empty() {
-			let warnings = self.warnings.into_iter().map(|c| c.into_ffi()).collect::<Vec<_>>().into_boxed_slice();
-			(res.warnings_len, res.warnings) = (warnings.len(), Box::into_raw(warnings).cast::<sys::Message>());
+	}
+
+	pub fn with_error(mut self, message: MessageBuilder) -> Self {
+		self.errors.push(message);
+		self
+	}
+
+	pub fn with_warning(mut self, message: MessageBuilder) -> Self {
+		self.warnings.push(message);
+		self
+	}
+
+	pub fn errors(&self) -> &[MessageBuilder] {
+		&self.errors
+	}
+
+	pub fn warnings(&self) -> &[MessageBuilder] {
+		&self.warnings
+	}
+}
+
+impl IntoFFI for OnEndResult {
+	type FFIType = *mut sys::PluginOnEndResult;
+
+	fn into_ffi(self) -> Self::FFIType {
+		let (errors_len, errors) = messages_into_ffi(self.errors);
+		let (warnings_len, warnings) = messages_into_ffi(self.warnings);
+		Box::into_raw(Box::new(sys::PluginOnEndResult {
+			errors,
+			errors_len,
+			warnings,
+			warnings_len
+		}))
+	}
+
+	unsafe fn drop_ffi(res: Self::FFIType) {
+		let res = Box::from_raw(res);
+		drop_messages_ffi(res.errors, res.errors_len);
+		drop_messages_ffi(res.warnings, res.warnings_len);
+	}
+}
+
+/// Which modules an `on_resolve` or `on_load` callback applies to: paths matching the (Go syntax) regular expression
+/// `filter`, optionally restricted to a single namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+	filter: String,
+	namespace: Option<String>
+}
+
+impl Filter {
+	pub fn new(filter: impl ToString) -> Self {
+		Self {
+			filter: filter.to_string(),
+			namespace: None
+		}
+	}
+
+	pub fn with_namespace(mut self, namespace: impl ToString) -> Self {
+		self.namespace = Some(namespace.to_string());
+		self
+	}
+
+	pub fn filter(&self) -> &str {
+		&self.filter
+	}
+
+	pub fn namespace(&self) -> Option<&str> {
+		self.namespace.as_deref()
+	}
+}
+
+impl From<&str> for Filter {
+	fn from(filter: &str) -> Self {
+		Filter::new(filter)
+	}
+}
+
+impl From<String> for Filter {
+	fn from(filter: String) -> Self {
+		Filter { filter, namespace: None }
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ResolveKind {
+	#[default]
+	None = 0,
+	EntryPoint = 1,
+	ImportStatement = 2,
+	RequireCall = 3,
+	DynamicImport = 4,
+	RequireResolve = 5,
+	CssImportRule = 6,
+	CssComposesFrom = 7,
+	CssUrlToken = 8
+}
+
+impl ResolveKind {
+	fn from_discriminant(kind: u8) -> Self {
+		match kind {
+			1 => Self::EntryPoint,
+			2 => Self::ImportStatement,
+			3 => Self::RequireCall,
+			4 => Self::DynamicImport,
+			5 => Self::RequireResolve,
+			6 => Self::CssImportRule,
+			7 => Self::CssComposesFrom,
+			8 => Self::CssUrlToken,
+			_ => Self::None
+		}
+	}
+}
+
+pub struct OnResolveArgs<'a>(pub(crate) &'a sys::PluginOnResolveArgs);
+
+impl<'a> OnResolveArgs<'a> {
+	pub fn path(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.path, self.0.path_len) }
+	}
+
+	pub fn importer(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.importer, self.0.importer_len) }
+	}
+
+	pub fn namespace(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.namespace_, self.0.namespace_len) }
+	}
+
+	pub fn resolve_dir(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.resolve_dir, self.0.resolve_dir_len) }
+	}
+
+	pub fn kind(&self) -> ResolveKind {
+		ResolveKind::from_discriminant(self.0.kind)
+	}
+
+	/// The plugin data returned by the `on_load` callback of the importing module, if any.
+	pub fn plugin_data(&self) -> Option<&(dyn Any + Send + Sync)> {
+		unsafe { plugin_data_ref(self.0.plugin_data) }
+	}
+
+	/// Like [`plugin_data`](Self::plugin_data), but only returns the data if it is of type `T`.
+	pub fn plugin_data_as<T: Any>(&self) -> Option<&T> {
+		self.plugin_data()?.downcast_ref()
+	}
+}
+
+/// The result of an `on_resolve` callback. If no path or errors are set, esbuild moves on to the next callback (and
+/// ultimately, its own resolver).
+#[derive(Default)]
+pub struct OnResolveResult {
+	pub(crate) path: Option<String>,
+	pub(crate) namespace: Option<String>,
+	pub(crate) suffix: Option<String>,
+	pub(crate) external: bool,
+	pub(crate) no_side_effects: bool,
+	pub(crate) plugin_data: Option<PluginData>,
+	pub(crate) errors: Vec<MessageBuilder>,
+	pub(crate) warnings: Vec<MessageBuilder>
+}
+
+impl OnResolveResult {
+	pub fn pass() -> Self {
+		OnResolveResult::default()
+	}
+
+	pub fn path(path: impl ToString) -> Self {
+		OnResolveResult {
+			path: Some(path.to_string()),
+			..Default::default()
 		}
-		Box::into_raw(Box::new(res))
+	}
+
+	pub fn error(message: MessageBuilder) -> Self {
+		OnResolveResult {
+			errors: vec![message],
+			..Default::default()
+		}
+	}
+
+	pub fn with_namespace(mut self, namespace: impl ToString) -> Self {
+		self.namespace = Some(namespace.to_string());
+		self
+	}
+
+	pub fn with_suffix(mut self, suffix: impl ToString) -> Self {
+		self.suffix = Some(suffix.to_string());
+		self
+	}
+
+	pub fn with_external(mut self, external: bool) -> Self {
+		self.external = external;
+		self
+	}
+
+	pub fn with_side_effects(mut self, side_effects: bool) -> Self {
+		self.no_side_effects = !side_effects;
+		self
+	}
+
+	/// Attaches data that is handed to the `on_load` callback for this module via [`OnLoadArgs::plugin_data`].
+	pub fn with_plugin_data<T: Any + Send + Sync>(mut self, data: T) -> Self {
+		self.plugin_data = Some(Box::new(data));
+		self
+	}
+
+	pub fn with_error(mut self, message: MessageBuilder) -> Self {
+		self.errors.push(message);
+		self
+	}
+
+	pub fn with_warning(mut self, message: MessageBuilder) -> Self {
+		self.warnings.push(message);
+		self
+	}
+}
+
+impl IntoFFI for OnResolveResult {
+	type FFIType = *mut sys::PluginOnResolveResult;
+
+	fn into_ffi(self) -> Self::FFIType {
+		let (path_len, path) = string_into_ffi(self.path);
+		let (namespace_len, namespace_) = string_into_ffi(self.namespace);
+		let (suffix_len, suffix) = string_into_ffi(self.suffix);
+		let (plugin_data, plugin_data_destructor) = plugin_data_into_ffi(self.plugin_data);
+		let (errors_len, errors) = messages_into_ffi(self.errors);
+		let (warnings_len, warnings) = messages_into_ffi(self.warnings);
+		Box::into_raw(Box::new(sys::PluginOnResolveResult {
+			path,
+			path_len,
+			namespace_,
+			namespace_len,
+			suffix,
+			suffix_len,
+			external: self.external,
+			no_side_effects: self.no_side_effects,
+			plugin_data,
+			plugin_data_destructor,
+			errors,
+			errors_len,
+			warnings,
+			warnings_len
+		}))
 	}
 
 	unsafe fn drop_ffi(res: Self::FFIType) {
-		let res = &mut *res;
-		if res.errors_len > 0 {
-			let errors = Box::from_raw(slice::from_raw_parts_mut(res.errors, res.errors_len));
-			for message in errors.into_vec() {
-				MessageBuilder::drop_ffi(message);
-			}
+		// `plugin_data` is owned by Go at this point.
+		let res = Box::from_raw(res);
+		drop_string_ffi(res.path, res.path_len);
+		drop_string_ffi(res.namespace_, res.namespace_len);
+		drop_string_ffi(res.suffix, res.suffix_len);
+		drop_messages_ffi(res.errors, res.errors_len);
+		drop_messages_ffi(res.warnings, res.warnings_len);
+	}
+}
+
+pub struct OnLoadArgs<'a>(pub(crate) &'a sys::PluginOnLoadArgs);
+
+impl<'a> OnLoadArgs<'a> {
+	pub fn path(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.path, self.0.path_len) }
+	}
+
+	pub fn namespace(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.namespace_, self.0.namespace_len) }
+	}
+
+	pub fn suffix(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.suffix, self.0.suffix_len) }
+	}
+
+	/// The plugin data returned by the `on_resolve` callback that resolved this module, if any.
+	pub fn plugin_data(&self) -> Option<&(dyn Any + Send + Sync)> {
+		unsafe { plugin_data_ref(self.0.plugin_data) }
+	}
+
+	/// Like [`plugin_data`](Self::plugin_data), but only returns the data if it is of type `T`.
+	pub fn plugin_data_as<T: Any>(&self) -> Option<&T> {
+		self.plugin_data()?.downcast_ref()
+	}
+}
+
+/// The result of an `on_load` callback. If no contents or errors are set, esbuild moves on to the next callback (and
+/// ultimately, reads the file from disk).
+#[derive(Default)]
+pub struct OnLoadResult {
+	pub(crate) contents: Option<Vec<u8>>,
+	pub(crate) resolve_dir: Option<String>,
+	pub(crate) loader: Option<Loader>,
+	pub(crate) plugin_data: Option<PluginData>,
+	pub(crate) errors: Vec<MessageBuilder>,
+	pub(crate) warnings: Vec<MessageBuilder>
+}
+
+impl OnLoadResult {
+	pub fn pass() -> Self {
+		OnLoadResult::default()
+	}
+
+	pub fn contents(contents: impl Into<Vec<u8>>) -> Self {
+		OnLoadResult {
+			contents: Some(contents.into()),
+			..Default::default()
 		}
-		if res.warnings_len > 0 {
-			let warnings = Box::from_raw(slice::from_raw_parts_mut(res.warnings, res.warnings_len));
-			for message in warnings.into_vec() {
-				MessageBuilder::drop_ffi(message);
-			}
+	}
+
+	pub fn error(message: MessageBuilder) -> Self {
+		OnLoadResult {
+			errors: vec![message],
+			..Default::default()
 		}
-		drop(Box::from_raw(res))
+	}
+
+	pub fn with_resolve_dir(mut self, resolve_dir: impl ToString) -> Self {
+		self.resolve_dir = Some(resolve_dir.to_string());
+		self
+	}
+
+	pub fn with_loader(mut self, loader: Loader) -> Self {
+		self.loader = Some(loader);
+		self
+	}
+
+	/// Attaches data that is handed to the `on_resolve` callbacks for this module's imports via
+	/// [`OnResolveArgs::plugin_data`].
+	pub fn with_plugin_data<T: Any + Send + Sync>(mut self, data: T) -> Self {
+		self.plugin_data = Some(Box::new(data));
+		self
+	}
+
+	pub fn with_error(mut self, message: MessageBuilder) -> Self {
+		self.errors.push(message);
+		self
+	}
+
+	pub fn with_warning(mut self, message: MessageBuilder) -> Self {
+		self.warnings.push(message);
+		self
+	}
+}
+
+impl IntoFFI for OnLoadResult {
+	type FFIType = *mut sys::PluginOnLoadResult;
+
+	fn into_ffi(self) -> Self::FFIType {
+		let has_contents = self.contents.is_some();
+		let (contents_len, contents) = match self.contents {
+			Some(contents) => util::decompose_vec(contents),
+			None => (0, ptr::null_mut())
+		};
+		let (resolve_dir_len, resolve_dir) = string_into_ffi(self.resolve_dir);
+		let (plugin_data, plugin_data_destructor) = plugin_data_into_ffi(self.plugin_data);
+		let (errors_len, errors) = messages_into_ffi(self.errors);
+		let (warnings_len, warnings) = messages_into_ffi(self.warnings);
+		Box::into_raw(Box::new(sys::PluginOnLoadResult {
+			contents: contents.cast_const().cast(),
+			contents_len,
+			has_contents,
+			resolve_dir,
+			resolve_dir_len,
+			has_loader: self.loader.is_some(),
+			loader: self.loader.map(|l| l as u8).unwrap_or_default(),
+			plugin_data,
+			plugin_data_destructor,
+			errors,
+			errors_len,
+			warnings,
+			warnings_len
+		}))
+	}
+
+	unsafe fn drop_ffi(res: Self::FFIType) {
+		// `plugin_data` is owned by Go at this point.
+		let res = Box::from_raw(res);
+		if res.has_contents {
+			drop(util::recompose_vec(res.contents.cast::<u8>(), res.contents_len));
+		}
+		drop_string_ffi(res.resolve_dir, res.resolve_dir_len);
+		drop_messages_ffi(res.errors, res.errors_len);
+		drop_messages_ffi(res.warnings, res.warnings_len);
 	}
 }
 
 pub struct PluginBuilder<'d> {
-	handle: u64,
+	// `None` if the callbacks are only collected on the Rust side, e.g. by `PluginTester`.
+	handle: Option<u64>,
 	callbacks: &'d mut PluginCallbacks
 }
 
 impl<'d> PluginBuilder<'d> {
-	pub fn on_start<F: OnStartCallback>(&mut self, callback: F) {
+	#[cfg_attr(not(feature = "testing"), allow(dead_code))]
+	pub(crate) fn detached(callbacks: &'d mut PluginCallbacks) -> Self {
+		PluginBuilder { handle: None, callbacks }
+	}
+
+	pub fn on_start<F: OnStartCallback>(&mut self, mut callback: F) {
+		let plugin = self.callbacks.name.clone();
+		let callback = move || {
+			let _span = tracing::debug_span!("plugin_callback", plugin = %plugin, hook = %PluginHook::OnStart).entered();
+			callback()
+		};
 		let callback = Box::into_raw(Box::new(Box::new(callback) as Box<dyn OnStartCallback>));
 		self.callbacks.on_start.push(callback);
-		unsafe { sys::Zsb_PluginBuilder_OnStart(self.handle, Some(Self::on_start_cb), callback as *mut _) }
+		if let Some(handle) = self.handle {
+			unsafe { sys::Zsb_PluginBuilder_OnStart(handle, Some(Self::on_start_cb), callback as *mut _) }
+		}
 		tracing::trace!("Registered on_start callback @ {:?}", callback);
 	}
 
 	extern "C" fn on_start_cb(callback: *mut c_void) -> *mut sys::PluginOnStartResult {
 		unsafe { (*callback.cast::<Box<dyn OnStartCallback>>())() }.into_ffi()
 	}
+
+	pub fn on_resolve<F: OnResolveCallback>(&mut self, filter: impl Into<Filter>, callback: F) {
+		let filter = filter.into();
+		let namespace = filter.namespace().unwrap_or("");
+		let (plugin, filter_src) = (self.callbacks.name.clone(), filter.filter().to_string());
+		let callback = move |args: &OnResolveArgs<'_>| {
+			let _span =
+				tracing::debug_span!("plugin_callback", plugin = %plugin, hook = %PluginHook::OnResolve, filter = %filter_src, path = args.path()).entered();
+			callback(args)
+		};
+		let callback = Box::into_raw(Box::new(Box::new(callback) as Box<dyn OnResolveCallback>));
+		if let Some(handle) = self.handle {
+			unsafe {
+				sys::Zsb_PluginBuilder_OnResolve(
+					handle,
+					filter.filter().as_ptr() as *mut _,
+					filter.filter().len(),
+					namespace.as_ptr() as *mut _,
+					namespace.len(),
+					Some(Self::on_resolve_cb),
+					callback as *mut _
+				)
+			}
+		}
+		self.callbacks.on_resolve.push((filter, callback));
+		tracing::trace!("Registered on_resolve callback @ {:?}", callback);
+	}
+
+	extern "C" fn on_resolve_cb(args: *mut sys::PluginOnResolveArgs, callback: *mut c_void) -> *mut sys::PluginOnResolveResult {
+		let args = OnResolveArgs(unsafe { &*args });
+		unsafe { (*callback.cast::<Box<dyn OnResolveCallback>>())(&args) }.into_ffi()
+	}
+
+	pub fn on_load<F: OnLoadCallback>(&mut self, filter: impl Into<Filter>, callback: F) {
+		let filter = filter.into();
+		let namespace = filter.namespace().unwrap_or("");
+		let (plugin, filter_src) = (self.callbacks.name.clone(), filter.filter().to_string());
+		let callback = move |args: &OnLoadArgs<'_>| {
+			let _span =
+				tracing::debug_span!("plugin_callback", plugin = %plugin, hook = %PluginHook::OnLoad, filter = %filter_src, path = args.path()).entered();
+			callback(args)
+		};
+		let callback = Box::into_raw(Box::new(Box::new(callback) as Box<dyn OnLoadCallback>));
+		if let Some(handle) = self.handle {
+			unsafe {
+				sys::Zsb_PluginBuilder_OnLoad(
+					handle,
+					filter.filter().as_ptr() as *mut _,
+					filter.filter().len(),
+					namespace.as_ptr() as *mut _,
+					namespace.len(),
+					Some(Self::on_load_cb),
+					callback as *mut _
+				)
+			}
+		}
+		self.callbacks.on_load.push((filter, callback));
+		tracing::trace!("Registered on_load callback @ {:?}", callback);
+	}
+
+	extern "C" fn on_load_cb(args: *mut sys::PluginOnLoadArgs, callback: *mut c_void) -> *mut sys::PluginOnLoadResult {
+		let args = OnLoadArgs(unsafe { &*args });
+		unsafe { (*callback.cast::<Box<dyn OnLoadCallback>>())(&args) }.into_ffi()
+	}
+
+	pub fn on_end<F: OnEndCallback>(&mut self, mut callback: F) {
+		let plugin = self.callbacks.name.clone();
+		let callback = move |result: &BuildResult| {
+			let _span = tracing::debug_span!("plugin_callback", plugin = %plugin, hook = %PluginHook::OnEnd).entered();
+			callback(result)
+		};
+		let callback = Box::into_raw(Box::new(Box::new(callback) as Box<dyn OnEndCallback>));
+		self.callbacks.on_end.push(callback);
+		if let Some(handle) = self.handle {
+			unsafe { sys::Zsb_PluginBuilder_OnEnd(handle, Some(Self::on_end_cb), callback as *mut _) }
+		}
+		tracing::trace!("Registered on_end callback @ {:?}", callback);
+	}
+
+	extern "C" fn on_end_cb(result: *mut sys::BuildResult, callback: *mut c_void) -> *mut sys::PluginOnEndResult {
+		// the result is owned (and destroyed) by Go
+		let result = ManuallyDrop::new(BuildResult::new(result));
+		unsafe { (*callback.cast::<Box<dyn OnEndCallback>>())(&result) }.into_ffi()
+	}
 }
 
 impl PluginDescriptor {
 	pub fn new(name: &str, builder: Box<PluginDescriptorBuildCallback>) -> PluginDescriptor {
-		let callbacks = PluginCallbacks { on_start: Vec::new() };
+		let callbacks = PluginCallbacks::new(name);
 		let data = Box::into_raw(Box::new((builder, callbacks)));
 		let handle = unsafe {
 			sys::Zsb_Plugin_Create(
@@ -132,7 +713,10 @@ impl PluginDescriptor {
 
 	extern "C" fn plugin_builder_callback(handle: u64, data: *mut c_void) {
 		let data = unsafe { &mut *data.cast::<(Box<PluginDescriptorBuildCallback>, PluginCallbacks)>() };
-		let mut builder = PluginBuilder { handle, callbacks: &mut data.1 };
+		let mut builder = PluginBuilder {
+			handle: Some(handle),
+			callbacks: &mut data.1
+		};
 		(data.0)(&mut builder)
 	}
 
@@ -151,6 +735,88 @@ impl Drop for PluginDescriptor {
 	}
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum PluginHook {
+	OnStart = 0,
+	OnResolve = 1,
+	OnLoad = 2,
+	OnEnd = 3
+}
+
+impl PluginHook {
+	fn from_ffi(hook: u8) -> Self {
+		match hook {
+			0 => Self::OnStart,
+			1 => Self::OnResolve,
+			2 => Self::OnLoad,
+			_ => Self::OnEnd
+		}
+	}
+}
+
+impl fmt::Display for PluginHook {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::OnStart => "on_start",
+			Self::OnResolve => "on_resolve",
+			Self::OnLoad => "on_load",
+			Self::OnEnd => "on_end"
+		})
+	}
+}
+
+/// How long a plugin's callbacks for one hook & filter took during a build. See
+/// [`BuildResult::plugin_timings`](crate::BuildResult::plugin_timings).
+#[repr(transparent)]
+pub struct PluginTiming<'s>(sys::PluginTiming, PhantomData<&'s ()>);
+
+impl<'s> PluginTiming<'s> {
+	pub fn plugin(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.0.plugin, self.0.plugin_len) }
+	}
+
+	pub fn hook(&self) -> PluginHook {
+		PluginHook::from_ffi(self.0.hook)
+	}
+
+	/// The filter the callbacks were registered with; `None` for `on_start` & `on_end`.
+	pub fn filter(&self) -> Option<&str> {
+		unsafe { util::as_str_opt(self.0.filter, self.0.filter_len) }
+	}
+
+	pub fn calls(&self) -> u64 {
+		self.0.calls
+	}
+
+	/// The total time spent in the callbacks, including the FFI overhead of calling into Rust.
+	pub fn total(&self) -> Duration {
+		Duration::from_nanos(self.0.total_ns)
+	}
+
+	/// The duration of the slowest call.
+	pub fn max(&self) -> Duration {
+		Duration::from_nanos(self.0.max_ns)
+	}
+
+	pub fn mean(&self) -> Duration {
+		Duration::from_nanos(self.0.total_ns.checked_div(self.0.calls).unwrap_or(0))
+	}
+}
+
+impl fmt::Debug for PluginTiming<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PluginTiming")
+			.field("plugin", &self.plugin())
+			.field("hook", &self.hook())
+			.field("filter", &self.filter())
+			.field("calls", &self.calls())
+			.field("total", &self.total())
+			.field("max", &self.max())
+			.finish()
+	}
+}
+
 pub trait Plugin {
 	fn name(&self) -> &str;
 	fn build(&self, builder: &mut PluginBuilder);
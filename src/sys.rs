@@ -53,18 +53,114 @@ pub struct OutputFile {
 }
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+pub struct MangleCacheEntry {
+	pub key: *const ::std::os::raw::c_char,
+	pub key_len: usize,
+	pub value: *const ::std::os::raw::c_char,
+	pub value_len: usize
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PluginTiming {
+	pub plugin: *const ::std::os::raw::c_char,
+	pub plugin_len: usize,
+	pub hook: u8,
+	pub filter: *const ::std::os::raw::c_char,
+	pub filter_len: usize,
+	pub calls: u64,
+	pub total_ns: u64,
+	pub max_ns: u64
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
 pub struct BuildResult {
 	pub output_files: *mut OutputFile,
 	pub output_files_len: usize,
 	pub errors: *mut Message,
 	pub errors_len: usize,
 	pub warnings: *mut Message,
+	pub warnings_len: usize,
+	pub metafile: *const ::std::os::raw::c_char,
+	pub metafile_len: usize,
+	pub cancelled: bool,
+	pub has_mangle_cache: bool,
+	pub mangle_cache: *mut MangleCacheEntry,
+	pub mangle_cache_len: usize,
+	pub plugin_timings: *mut PluginTiming,
+	pub plugin_timings_len: usize,
+	pub duration_ns: i64
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Error {
+	pub message: *mut ::std::os::raw::c_char,
+	pub message_len: usize
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ServeOptions {
+	pub host: *const ::std::os::raw::c_char,
+	pub host_len: usize,
+	pub port: u16,
+	pub servedir: *const ::std::os::raw::c_char,
+	pub servedir_len: usize,
+	pub keyfile: *const ::std::os::raw::c_char,
+	pub keyfile_len: usize,
+	pub certfile: *const ::std::os::raw::c_char,
+	pub certfile_len: usize,
+	pub fallback: *const ::std::os::raw::c_char,
+	pub fallback_len: usize
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ServeResult {
+	pub host: *mut ::std::os::raw::c_char,
+	pub host_len: usize,
+	pub port: u16
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TransformOptions {
+	pub loader: u8,
+	pub sourcefile: *const ::std::os::raw::c_char,
+	pub sourcefile_len: usize,
+	pub format: u8,
+	pub sourcemap: u8,
+	pub minify_whitespace: bool,
+	pub minify_identifiers: bool,
+	pub minify_syntax: bool,
+	pub tsconfig_raw: *const ::std::os::raw::c_char,
+	pub tsconfig_raw_len: usize,
+	pub jsx: u8,
+	pub jsx_factory: *const ::std::os::raw::c_char,
+	pub jsx_factory_len: usize,
+	pub jsx_fragment: *const ::std::os::raw::c_char,
+	pub jsx_fragment_len: usize,
+	pub jsx_import_source: *const ::std::os::raw::c_char,
+	pub jsx_import_source_len: usize,
+	pub jsx_dev: bool,
+	pub jsx_side_effects: bool
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TransformResult {
+	pub code: *const ::std::os::raw::c_char,
+	pub code_len: usize,
+	pub source_map: *const ::std::os::raw::c_char,
+	pub source_map_len: usize,
+	pub errors: *mut Message,
+	pub errors_len: usize,
+	pub warnings: *mut Message,
 	pub warnings_len: usize
 }
 pub type BuildAsyncCallback = ::std::option::Option<unsafe extern "C" fn(result: *mut BuildResult, data: *mut ::std::os::raw::c_void)>;
 extern "C" {
 	pub fn Zsb_BuildAsyncCallback_Dispatch(callback: BuildAsyncCallback, result: *mut BuildResult, data: *mut ::std::os::raw::c_void);
 }
+pub type LogCallback = ::std::option::Option<unsafe extern "C" fn(level: u8, message: *mut Message)>;
+extern "C" {
+	pub fn Zsb_LogCallback_Dispatch(callback: LogCallback, level: u8, message: *mut Message);
+}
 pub type PluginBuildCallback = ::std::option::Option<unsafe extern "C" fn(handle: u64, data: *mut ::std::os::raw::c_void)>;
 extern "C" {
 	pub fn Zsb_PluginBuildCallback_Dispatch(callback: PluginBuildCallback, handle: u64, data: *mut ::std::os::raw::c_void);
@@ -88,9 +184,130 @@ pub type PluginCallbackOnStart = ::std::option::Option<unsafe extern "C" fn(data
 extern "C" {
 	pub fn Zsb_PluginCallbackOnStart_Dispatch(callback: PluginCallbackOnStart, data: *mut ::std::os::raw::c_void) -> *mut PluginOnStartResult;
 }
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PluginOnResolveArgs {
+	pub path: *const ::std::os::raw::c_char,
+	pub path_len: usize,
+	pub importer: *const ::std::os::raw::c_char,
+	pub importer_len: usize,
+	pub namespace_: *const ::std::os::raw::c_char,
+	pub namespace_len: usize,
+	pub resolve_dir: *const ::std::os::raw::c_char,
+	pub resolve_dir_len: usize,
+	pub kind: u8,
+	pub plugin_data: *mut ::std::os::raw::c_void
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PluginOnResolveResult {
+	pub path: *const ::std::os::raw::c_char,
+	pub path_len: usize,
+	pub namespace_: *const ::std::os::raw::c_char,
+	pub namespace_len: usize,
+	pub suffix: *const ::std::os::raw::c_char,
+	pub suffix_len: usize,
+	pub external: bool,
+	pub no_side_effects: bool,
+	pub plugin_data: *mut ::std::os::raw::c_void,
+	pub plugin_data_destructor: PluginDestructor,
+	pub errors: *mut Message,
+	pub errors_len: usize,
+	pub warnings: *mut Message,
+	pub warnings_len: usize
+}
+extern "C" {
+	pub fn Zsb_PluginOnResolveResult_Destroy(res: *mut PluginOnResolveResult);
+}
+pub type PluginCallbackOnResolve =
+	::std::option::Option<unsafe extern "C" fn(args: *mut PluginOnResolveArgs, data: *mut ::std::os::raw::c_void) -> *mut PluginOnResolveResult>;
+extern "C" {
+	pub fn Zsb_PluginCallbackOnResolve_Dispatch(
+		callback: PluginCallbackOnResolve,
+		args: *mut PluginOnResolveArgs,
+		data: *mut ::std::os::raw::c_void
+	) -> *mut PluginOnResolveResult;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PluginOnLoadArgs {
+	pub path: *const ::std::os::raw::c_char,
+	pub path_len: usize,
+	pub namespace_: *const ::std::os::raw::c_char,
+	pub namespace_len: usize,
+	pub suffix: *const ::std::os::raw::c_char,
+	pub suffix_len: usize,
+	pub plugin_data: *mut ::std::os::raw::c_void
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PluginOnLoadResult {
+	pub contents: *const ::std::os::raw::c_char,
+	pub contents_len: usize,
+	pub has_contents: bool,
+	pub resolve_dir: *const ::std::os::raw::c_char,
+	pub resolve_dir_len: usize,
+	pub has_loader: bool,
+	pub loader: u8,
+	pub plugin_data: *mut ::std::os::raw::c_void,
+	pub plugin_data_destructor: PluginDestructor,
+	pub errors: *mut Message,
+	pub errors_len: usize,
+	pub warnings: *mut Message,
+	pub warnings_len: usize
+}
+extern "C" {
+	pub fn Zsb_PluginOnLoadResult_Destroy(res: *mut PluginOnLoadResult);
+}
+pub type PluginCallbackOnLoad =
+	::std::option::Option<unsafe extern "C" fn(args: *mut PluginOnLoadArgs, data: *mut ::std::os::raw::c_void) -> *mut PluginOnLoadResult>;
+extern "C" {
+	pub fn Zsb_PluginCallbackOnLoad_Dispatch(
+		callback: PluginCallbackOnLoad,
+		args: *mut PluginOnLoadArgs,
+		data: *mut ::std::os::raw::c_void
+	) -> *mut PluginOnLoadResult;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PluginOnEndResult {
+	pub errors: *mut Message,
+	pub errors_len: usize,
+	pub warnings: *mut Message,
+	pub warnings_len: usize
+}
+extern "C" {
+	pub fn Zsb_PluginOnEndResult_Destroy(res: *mut PluginOnEndResult);
+}
+pub type PluginCallbackOnEnd =
+	::std::option::Option<unsafe extern "C" fn(result: *mut BuildResult, data: *mut ::std::os::raw::c_void) -> *mut PluginOnEndResult>;
+extern "C" {
+	pub fn Zsb_PluginCallbackOnEnd_Dispatch(
+		callback: PluginCallbackOnEnd,
+		result: *mut BuildResult,
+		data: *mut ::std::os::raw::c_void
+	) -> *mut PluginOnEndResult;
+}
 pub type GoUint8 = ::std::os::raw::c_uchar;
 pub type GoUint16 = ::std::os::raw::c_ushort;
+pub type GoInt32 = ::std::os::raw::c_int;
+pub type GoInt64 = ::std::os::raw::c_longlong;
 pub type GoUint64 = ::std::os::raw::c_ulonglong;
+extern "C" {
+	pub fn Zsb_Error_Destroy(err: *mut Error);
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_LogLevel(handle: GoUint64, level: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_LogLimit(handle: GoUint64, limit: GoInt32) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_LogOverride(handle: GoUint64, id: *mut ::std::os::raw::c_char, idLen: usize, level: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_LogCallback(handle: GoUint64, callback: LogCallback) -> GoUint16;
+}
 extern "C" {
 	pub fn Zsb_Context_Create(optionsHandle: GoUint64, outHandle: *mut GoUint64) -> *mut ContextResult;
 }
@@ -98,13 +315,16 @@ extern "C" {
 	pub fn Zsb_ContextResult_Destroy(res: *mut ContextResult);
 }
 extern "C" {
-	pub fn Zsb_Context_Build(handle: GoUint64) -> *mut BuildResult;
+	pub fn Zsb_Context_PrepareBuild(handle: GoUint64, buildId: GoUint64);
+}
+extern "C" {
+	pub fn Zsb_Context_Build(handle: GoUint64, buildId: GoUint64, timeoutMs: GoInt64) -> *mut BuildResult;
 }
 extern "C" {
 	pub fn Zsb_Build(optionsHandle: GoUint64) -> *mut BuildResult;
 }
 extern "C" {
-	pub fn Zsb_Context_BuildAsync(handle: GoUint64, callback: BuildAsyncCallback, data: *mut ::std::os::raw::c_void);
+	pub fn Zsb_Context_BuildAsync(handle: GoUint64, buildId: GoUint64, timeoutMs: GoInt64, callback: BuildAsyncCallback, data: *mut ::std::os::raw::c_void);
 }
 extern "C" {
 	pub fn Zsb_BuildAsync(optionsHandle: GoUint64, callback: BuildAsyncCallback, data: *mut ::std::os::raw::c_void);
@@ -115,6 +335,18 @@ extern "C" {
 extern "C" {
 	pub fn Zsb_Context_Cancel(handle: GoUint64);
 }
+extern "C" {
+	pub fn Zsb_Context_CancelBuild(handle: GoUint64, buildId: GoUint64);
+}
+extern "C" {
+	pub fn Zsb_Context_Watch(handle: GoUint64) -> *mut Error;
+}
+extern "C" {
+	pub fn Zsb_Context_Serve(handle: GoUint64, options: *mut ServeOptions, outResult: *mut ServeResult) -> *mut Error;
+}
+extern "C" {
+	pub fn Zsb_ServeResult_Destroy(res: *mut ServeResult);
+}
 extern "C" {
 	pub fn Zsb_Context_Destroy(handle: GoUint64);
 }
@@ -124,6 +356,144 @@ extern "C" {
 extern "C" {
 	pub fn Zsb_BuildOptions_Bundle(handle: GoUint64, enable: GoUint8) -> GoUint16;
 }
+extern "C" {
+	pub fn Zsb_BuildOptions_Splitting(handle: GoUint64, enable: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Write(handle: GoUint64, enable: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Metafile(handle: GoUint64, enable: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Minify(handle: GoUint64, whitespace: GoUint8, identifiers: GoUint8, syntax: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Format(handle: GoUint64, format: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Sourcemap(handle: GoUint64, sourceMap: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_LegalComments(handle: GoUint64, legalComments: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Loader(handle: GoUint64, ext: *mut ::std::os::raw::c_char, extLen: usize, loader: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Platform(handle: GoUint64, platform: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Target(handle: GoUint64, esVersion: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_AddEngine(handle: GoUint64, engine: GoUint8, version: *mut ::std::os::raw::c_char, versionLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Define(
+		handle: GoUint64,
+		key: *mut ::std::os::raw::c_char,
+		keyLen: usize,
+		value: *mut ::std::os::raw::c_char,
+		valueLen: usize
+	) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_MangleProps(handle: GoUint64, regex: *mut ::std::os::raw::c_char, regexLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_ReserveProps(handle: GoUint64, regex: *mut ::std::os::raw::c_char, regexLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_MangleQuoted(handle: GoUint64, enable: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_MangleCache(handle: GoUint64, entries: *mut MangleCacheEntry, entriesLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Outfile(handle: GoUint64, path: *mut ::std::os::raw::c_char, pathLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Outdir(handle: GoUint64, path: *mut ::std::os::raw::c_char, pathLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Outbase(handle: GoUint64, path: *mut ::std::os::raw::c_char, pathLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_EntryNames(handle: GoUint64, template: *mut ::std::os::raw::c_char, templateLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_ChunkNames(handle: GoUint64, template: *mut ::std::os::raw::c_char, templateLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_AssetNames(handle: GoUint64, template: *mut ::std::os::raw::c_char, templateLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_PublicPath(handle: GoUint64, path: *mut ::std::os::raw::c_char, pathLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_OutExtension(
+		handle: GoUint64,
+		ext: *mut ::std::os::raw::c_char,
+		extLen: usize,
+		replacement: *mut ::std::os::raw::c_char,
+		replacementLen: usize
+	) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_AppendMainField(handle: GoUint64, value: *mut ::std::os::raw::c_char, valueLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_AppendCondition(handle: GoUint64, value: *mut ::std::os::raw::c_char, valueLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_AppendNodePath(handle: GoUint64, value: *mut ::std::os::raw::c_char, valueLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_AppendResolveExtension(handle: GoUint64, value: *mut ::std::os::raw::c_char, valueLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_AppendExternal(handle: GoUint64, value: *mut ::std::os::raw::c_char, valueLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Alias(
+		handle: GoUint64,
+		from: *mut ::std::os::raw::c_char,
+		fromLen: usize,
+		to: *mut ::std::os::raw::c_char,
+		toLen: usize
+	) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_PreserveSymlinks(handle: GoUint64, enable: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Packages(handle: GoUint64, mode: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Tsconfig(handle: GoUint64, path: *mut ::std::os::raw::c_char, pathLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_TsconfigRaw(handle: GoUint64, json: *mut ::std::os::raw::c_char, jsonLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_Jsx(handle: GoUint64, mode: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_JsxFactory(handle: GoUint64, factory: *mut ::std::os::raw::c_char, factoryLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_JsxFragment(handle: GoUint64, fragment: *mut ::std::os::raw::c_char, fragmentLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_JsxImportSource(handle: GoUint64, source: *mut ::std::os::raw::c_char, sourceLen: usize) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_JsxDev(handle: GoUint64, enable: GoUint8) -> GoUint16;
+}
+extern "C" {
+	pub fn Zsb_BuildOptions_JsxSideEffects(handle: GoUint64, enable: GoUint8) -> GoUint16;
+}
 extern "C" {
 	pub fn Zsb_BuildOptions_AppendEntryPoint(
 		handle: GoUint64,
@@ -142,6 +512,31 @@ extern "C" {
 extern "C" {
 	pub fn Zsb_PluginBuilder_OnStart(handle: GoUint64, cb: PluginCallbackOnStart, data: *mut ::std::os::raw::c_void);
 }
+extern "C" {
+	pub fn Zsb_PluginBuilder_OnResolve(
+		handle: GoUint64,
+		filter: *mut ::std::os::raw::c_char,
+		filterLen: usize,
+		namespace: *mut ::std::os::raw::c_char,
+		namespaceLen: usize,
+		cb: PluginCallbackOnResolve,
+		data: *mut ::std::os::raw::c_void
+	);
+}
+extern "C" {
+	pub fn Zsb_PluginBuilder_OnLoad(
+		handle: GoUint64,
+		filter: *mut ::std::os::raw::c_char,
+		filterLen: usize,
+		namespace: *mut ::std::os::raw::c_char,
+		namespaceLen: usize,
+		cb: PluginCallbackOnLoad,
+		data: *mut ::std::os::raw::c_void
+	);
+}
+extern "C" {
+	pub fn Zsb_PluginBuilder_OnEnd(handle: GoUint64, cb: PluginCallbackOnEnd, data: *mut ::std::os::raw::c_void);
+}
 extern "C" {
 	pub fn Zsb_Plugin_Create(
 		name: *mut ::std::os::raw::c_char,
@@ -154,3 +549,9 @@ extern "C" {
 extern "C" {
 	pub fn Zsb_Plugin_Destroy(handle: GoUint64);
 }
+extern "C" {
+	pub fn Zsb_Transform(code: *mut ::std::os::raw::c_char, codeLen: usize, options: *mut TransformOptions) -> *mut TransformResult;
+}
+extern "C" {
+	pub fn Zsb_TransformResult_Destroy(c: *mut TransformResult);
+}
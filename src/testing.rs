@@ -0,0 +1,542 @@
+//! Utilities for unit testing [`Plugin`]s without running a build.
+
+use std::{
+	any::Any,
+	collections::BTreeMap,
+	ffi::c_void,
+	mem::{self, ManuallyDrop},
+	path::Path,
+	ptr,
+	str::Utf8Error
+};
+
+use regex::Regex;
+
+use crate::{
+	Plugin,
+	build::{BuildResult, OwnedOutputFile, options::Loader},
+	error::MessageBuilder,
+	plugin::{Filter, OnEndResult, OnLoadArgs, OnResolveArgs, OnStartResult, PluginBuilder, PluginCallbacks, PluginData, ResolveKind},
+	sys,
+	util::{self, IntoFFI}
+};
+
+// esbuild's default `resolveExtensions`
+const RESOLVE_EXTENSIONS: [&str; 6] = [".tsx", ".ts", ".jsx", ".js", ".css", ".json"];
+
+/// A synthetic `on_resolve` event for [`PluginTester::resolve`].
+pub struct ResolveEvent {
+	path: String,
+	importer: String,
+	namespace: String,
+	resolve_dir: String,
+	kind: ResolveKind,
+	plugin_data: Option<PluginData>
+}
+
+impl ResolveEvent {
+	pub fn new(path: impl ToString) -> Self {
+		Self {
+			path: path.to_string(),
+			importer: String::new(),
+			namespace: "file".to_string(),
+			resolve_dir: String::new(),
+			kind: ResolveKind::ImportStatement,
+			plugin_data: None
+		}
+	}
+
+	/// Sets the importing module. Unless overridden with [`with_resolve_dir`](Self::with_resolve_dir), relative paths
+	/// are resolved against the importer's directory.
+	pub fn with_importer(mut self, importer: impl ToString) -> Self {
+		self.importer = importer.to_string();
+		if self.resolve_dir.is_empty() {
+			self.resolve_dir = Path::new(&self.importer)
+				.parent()
+				.map(|p| p.to_string_lossy().into_owned())
+				.unwrap_or_default();
+		}
+		self
+	}
+
+	pub fn with_namespace(mut self, namespace: impl ToString) -> Self {
+		self.namespace = namespace.to_string();
+		self
+	}
+
+	pub fn with_resolve_dir(mut self, resolve_dir: impl ToString) -> Self {
+		self.resolve_dir = resolve_dir.to_string();
+		self
+	}
+
+	pub fn with_kind(mut self, kind: ResolveKind) -> Self {
+		self.kind = kind;
+		self
+	}
+
+	/// Simulates plugin data returned by the importer's `on_load` callback.
+	pub fn with_plugin_data<T: Any + Send + Sync>(mut self, data: T) -> Self {
+		self.plugin_data = Some(Box::new(data));
+		self
+	}
+}
+
+impl From<&str> for ResolveEvent {
+	fn from(path: &str) -> Self {
+		ResolveEvent::new(path)
+	}
+}
+
+/// A synthetic `on_load` event for [`PluginTester::load`].
+pub struct LoadEvent {
+	path: String,
+	namespace: String,
+	suffix: String,
+	plugin_data: Option<PluginData>
+}
+
+impl LoadEvent {
+	pub fn new(path: impl ToString) -> Self {
+		Self {
+			path: path.to_string(),
+			namespace: "file".to_string(),
+			suffix: String::new(),
+			plugin_data: None
+		}
+	}
+
+	pub fn with_namespace(mut self, namespace: impl ToString) -> Self {
+		self.namespace = namespace.to_string();
+		self
+	}
+
+	pub fn with_suffix(mut self, suffix: impl ToString) -> Self {
+		self.suffix = suffix.to_string();
+		self
+	}
+
+	/// Simulates plugin data returned by the `on_resolve` callback that resolved this module.
+	pub fn with_plugin_data<T: Any + Send + Sync>(mut self, data: T) -> Self {
+		self.plugin_data = Some(Box::new(data));
+		self
+	}
+}
+
+impl From<&str> for LoadEvent {
+	fn from(path: &str) -> Self {
+		LoadEvent::new(path)
+	}
+}
+
+/// The outcome of [`PluginTester::resolve`].
+pub struct Resolved {
+	path: String,
+	namespace: String,
+	suffix: String,
+	external: bool,
+	side_effects: bool,
+	plugin_data: Option<PluginData>,
+	from_plugin: bool
+}
+
+impl Resolved {
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	pub fn namespace(&self) -> &str {
+		&self.namespace
+	}
+
+	pub fn suffix(&self) -> &str {
+		&self.suffix
+	}
+
+	pub fn is_external(&self) -> bool {
+		self.external
+	}
+
+	pub fn has_side_effects(&self) -> bool {
+		self.side_effects
+	}
+
+	/// Returns `true` if the path was resolved by one of the plugin's callbacks rather than the in-memory file set.
+	pub fn is_from_plugin(&self) -> bool {
+		self.from_plugin
+	}
+
+	pub fn plugin_data(&self) -> Option<&(dyn Any + Send + Sync)> {
+		self.plugin_data.as_deref()
+	}
+
+	pub fn plugin_data_as<T: Any>(&self) -> Option<&T> {
+		self.plugin_data()?.downcast_ref()
+	}
+}
+
+/// The outcome of [`PluginTester::load`].
+pub struct Loaded {
+	contents: Vec<u8>,
+	loader: Option<Loader>,
+	resolve_dir: Option<String>,
+	plugin_data: Option<PluginData>,
+	from_plugin: bool
+}
+
+impl Loaded {
+	pub fn contents(&self) -> &[u8] {
+		&self.contents
+	}
+
+	pub fn contents_str(&self) -> Result<&str, Utf8Error> {
+		std::str::from_utf8(&self.contents)
+	}
+
+	pub fn loader(&self) -> Option<Loader> {
+		self.loader
+	}
+
+	pub fn resolve_dir(&self) -> Option<&str> {
+		self.resolve_dir.as_deref()
+	}
+
+	/// Returns `true` if the module was loaded by one of the plugin's callbacks rather than from the in-memory file
+	/// set.
+	pub fn is_from_plugin(&self) -> bool {
+		self.from_plugin
+	}
+
+	pub fn plugin_data(&self) -> Option<&(dyn Any + Send + Sync)> {
+		self.plugin_data.as_deref()
+	}
+
+	pub fn plugin_data_as<T: Any>(&self) -> Option<&T> {
+		self.plugin_data()?.downcast_ref()
+	}
+}
+
+/// Drives a [`Plugin`]'s callbacks with synthetic events, without esbuild.
+///
+/// Callbacks are matched against events the same way esbuild would match them (by filter and namespace, in
+/// registration order), and paths no callback handles fall back to an in-memory file set. Every error and warning a
+/// callback returns is recorded and can be inspected with [`errors`](Self::errors) & [`warnings`](Self::warnings).
+///
+/// ```ignore
+/// let mut tester = PluginTester::new(MyPlugin).with_file("src/index.ts", "export {}");
+/// let loaded = tester.resolve_and_load(ResolveEvent::new("./index.ts").with_importer("src/main.ts")).unwrap();
+/// assert!(tester.errors().is_empty());
+/// ```
+pub struct PluginTester {
+	name: String,
+	callbacks: PluginCallbacks,
+	resolve_filters: Vec<Regex>,
+	load_filters: Vec<Regex>,
+	files: BTreeMap<String, Vec<u8>>,
+	errors: Vec<MessageBuilder>,
+	warnings: Vec<MessageBuilder>
+}
+
+impl PluginTester {
+	/// Runs the plugin's [`build`](Plugin::build) to collect its callbacks.
+	///
+	/// # Panics
+	/// Panics if a callback's filter is not a valid regular expression.
+	pub fn new<P: Plugin>(plugin: P) -> Self {
+		let mut callbacks = PluginCallbacks::new(plugin.name());
+		plugin.build(&mut PluginBuilder::detached(&mut callbacks));
+
+		let compile = |filter: &Filter| Regex::new(filter.filter()).unwrap_or_else(|e| panic!("invalid filter {:?}: {e}", filter.filter()));
+		Self {
+			name: plugin.name().to_string(),
+			resolve_filters: callbacks.on_resolve.iter().map(|(filter, _)| compile(filter)).collect(),
+			load_filters: callbacks.on_load.iter().map(|(filter, _)| compile(filter)).collect(),
+			callbacks,
+			files: BTreeMap::new(),
+			errors: Vec::new(),
+			warnings: Vec::new()
+		}
+	}
+
+	/// Adds a file to the in-memory file set used for paths the plugin doesn't handle itself.
+	pub fn with_file(mut self, path: impl ToString, contents: impl Into<Vec<u8>>) -> Self {
+		self.files.insert(path.to_string(), contents.into());
+		self
+	}
+
+	pub fn errors(&self) -> &[MessageBuilder] {
+		&self.errors
+	}
+
+	pub fn warnings(&self) -> &[MessageBuilder] {
+		&self.warnings
+	}
+
+	pub fn clear_diagnostics(&mut self) {
+		self.errors.clear();
+		self.warnings.clear();
+	}
+
+	fn record(&mut self, errors: &[MessageBuilder], warnings: &[MessageBuilder]) {
+		// like esbuild, attribute messages to the plugin that produced them
+		let attribute = |message: &MessageBuilder| match message.plugin_name() {
+			Some(_) => message.clone(),
+			None => message.clone().with_plugin_name(&self.name)
+		};
+		let errors = errors.iter().map(attribute).collect::<Vec<_>>();
+		let warnings = warnings.iter().map(attribute).collect::<Vec<_>>();
+		self.errors.extend(errors);
+		self.warnings.extend(warnings);
+	}
+
+	/// Fires the plugin's `on_start` callbacks.
+	pub fn start(&mut self) -> Vec<OnStartResult> {
+		let results = self.callbacks.on_start.iter().map(|&cb| unsafe { (*cb)() }).collect::<Vec<_>>();
+		for result in &results {
+			self.record(result.errors(), result.warnings());
+		}
+		results
+	}
+
+	/// Fires the plugin's matching `on_resolve` callbacks until one resolves the path or returns an error. If none do,
+	/// the path is resolved against the in-memory file set.
+	///
+	/// Returns `None` (and records an error) if the path could not be resolved.
+	pub fn resolve(&mut self, event: impl Into<ResolveEvent>) -> Option<Resolved> {
+		let event = event.into();
+		let args = sys::PluginOnResolveArgs {
+			path: event.path.as_ptr().cast(),
+			path_len: event.path.len(),
+			importer: event.importer.as_ptr().cast(),
+			importer_len: event.importer.len(),
+			namespace_: event.namespace.as_ptr().cast(),
+			namespace_len: event.namespace.len(),
+			resolve_dir: event.resolve_dir.as_ptr().cast(),
+			resolve_dir_len: event.resolve_dir.len(),
+			kind: event.kind as u8,
+			plugin_data: plugin_data_ptr(&event.plugin_data)
+		};
+
+		for i in 0..self.callbacks.on_resolve.len() {
+			let (filter, cb) = &self.callbacks.on_resolve[i];
+			if !matches(filter, &self.resolve_filters[i], &event.path, &event.namespace) {
+				continue;
+			}
+			let result = unsafe { (**cb)(&OnResolveArgs(&args)) };
+			self.record(&result.errors, &result.warnings);
+			if !result.errors.is_empty() {
+				return None;
+			}
+			if let Some(path) = result.path {
+				return Some(Resolved {
+					path,
+					namespace: result.namespace.unwrap_or_else(|| "file".to_string()),
+					suffix: result.suffix.unwrap_or_default(),
+					external: result.external,
+					side_effects: !result.no_side_effects,
+					plugin_data: result.plugin_data,
+					from_plugin: true
+				});
+			}
+		}
+
+		if event.namespace == "file" {
+			let base = if event.path.starts_with("./") || event.path.starts_with("../") {
+				util::normalize_path(&Path::new(&event.resolve_dir).join(&event.path))
+					.to_string_lossy()
+					.into_owned()
+			} else {
+				event.path.clone()
+			};
+			let candidates = std::iter::once(base.clone()).chain(RESOLVE_EXTENSIONS.iter().map(|ext| format!("{base}{ext}")));
+			for candidate in candidates {
+				if self.files.contains_key(&candidate) {
+					return Some(Resolved {
+						path: candidate,
+						namespace: "file".to_string(),
+						suffix: String::new(),
+						external: false,
+						side_effects: true,
+						plugin_data: None,
+						from_plugin: false
+					});
+				}
+			}
+		}
+		self.errors.push(MessageBuilder::new(format!("Could not resolve \"{}\"", event.path)));
+		None
+	}
+
+	/// Fires the plugin's matching `on_load` callbacks until one returns contents or an error. If none do, the file is
+	/// read from the in-memory file set.
+	///
+	/// Returns `None` (and records an error) if the module could not be loaded.
+	pub fn load(&mut self, event: impl Into<LoadEvent>) -> Option<Loaded> {
+		let event = event.into();
+		let args = sys::PluginOnLoadArgs {
+			path: event.path.as_ptr().cast(),
+			path_len: event.path.len(),
+			namespace_: event.namespace.as_ptr().cast(),
+			namespace_len: event.namespace.len(),
+			suffix: event.suffix.as_ptr().cast(),
+			suffix_len: event.suffix.len(),
+			plugin_data: plugin_data_ptr(&event.plugin_data)
+		};
+
+		for i in 0..self.callbacks.on_load.len() {
+			let (filter, cb) = &self.callbacks.on_load[i];
+			if !matches(filter, &self.load_filters[i], &event.path, &event.namespace) {
+				continue;
+			}
+			let result = unsafe { (**cb)(&OnLoadArgs(&args)) };
+			self.record(&result.errors, &result.warnings);
+			if !result.errors.is_empty() {
+				return None;
+			}
+			if let Some(contents) = result.contents {
+				return Some(Loaded {
+					contents,
+					loader: result.loader,
+					resolve_dir: result.resolve_dir,
+					plugin_data: result.plugin_data,
+					from_plugin: true
+				});
+			}
+		}
+
+		match self.files.get(&event.path) {
+			Some(contents) if event.namespace == "file" => Some(Loaded {
+				contents: contents.clone(),
+				loader: None,
+				resolve_dir: Path::new(&event.path).parent().map(|p| p.to_string_lossy().into_owned()),
+				plugin_data: None,
+				from_plugin: false
+			}),
+			_ => {
+				self.errors.push(MessageBuilder::new(format!("Could not read from file: {}", event.path)));
+				None
+			}
+		}
+	}
+
+	/// Resolves a path, then loads the result, passing along any plugin data returned by `on_resolve` like esbuild
+	/// does.
+	pub fn resolve_and_load(&mut self, event: impl Into<ResolveEvent>) -> Option<Loaded> {
+		let resolved = self.resolve(event)?;
+		if resolved.external {
+			return None;
+		}
+		self.load(LoadEvent {
+			path: resolved.path,
+			namespace: resolved.namespace,
+			suffix: resolved.suffix,
+			plugin_data: resolved.plugin_data
+		})
+	}
+
+	/// Fires the plugin's `on_end` callbacks with a [`BuildResult`] containing `outputs` and every error & warning
+	/// recorded so far.
+	pub fn end(&mut self, outputs: &[OwnedOutputFile]) -> Vec<OnEndResult> {
+		let output_files = outputs
+			.iter()
+			.map(|output| sys::OutputFile {
+				path: output.path().as_ptr().cast(),
+				path_len: output.path().len(),
+				hash: output.hash().as_ptr().cast(),
+				hash_len: output.hash().len(),
+				contents: output.contents().as_ptr().cast(),
+				contents_len: output.contents().len()
+			})
+			.collect::<Vec<_>>();
+		let mut errors = self.errors.iter().cloned().map(MessageBuilder::into_ffi).collect::<Vec<_>>();
+		let mut warnings = self.warnings.iter().cloned().map(MessageBuilder::into_ffi).collect::<Vec<_>>();
+
+		// SAFETY: all fields of the result are pointers, lengths or flags, for which all zeroes is a valid (empty) value.
+		let mut raw: sys::BuildResult = unsafe { mem::zeroed() };
+		raw.output_files = output_files.as_ptr().cast_mut();
+		raw.output_files_len = output_files.len();
+		raw.errors = errors.as_mut_ptr();
+		raw.errors_len = errors.len();
+		raw.warnings = warnings.as_mut_ptr();
+		raw.warnings_len = warnings.len();
+		// the result is backed by Rust memory, so it must not be handed to Go for destruction
+		let result = ManuallyDrop::new(BuildResult::new(&mut raw));
+
+		let results = self.callbacks.on_end.iter().map(|&cb| unsafe { (*cb)(&result) }).collect::<Vec<_>>();
+		for message in errors.into_iter().chain(warnings) {
+			unsafe { MessageBuilder::drop_ffi(message) };
+		}
+		for result in &results {
+			self.record(result.errors(), result.warnings());
+		}
+		results
+	}
+}
+
+fn matches(filter: &Filter, regex: &Regex, path: &str, namespace: &str) -> bool {
+	filter.namespace().map(|ns| ns == namespace).unwrap_or(true) && regex.is_match(path)
+}
+
+fn plugin_data_ptr(data: &Option<PluginData>) -> *mut c_void {
+	match data {
+		Some(data) => data as *const PluginData as *mut c_void,
+		None => ptr::null_mut()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{OnLoadResult, OnResolveResult};
+
+	struct EnvPlugin;
+
+	struct EnvVar(&'static str);
+
+	impl Plugin for EnvPlugin {
+		fn name(&self) -> &str {
+			"env"
+		}
+
+		fn build(&self, builder: &mut PluginBuilder) {
+			builder.on_start(OnStartResult::ok);
+			builder.on_resolve("^env:", |args| match &args.path()["env:".len()..] {
+				"HOME" => OnResolveResult::path(args.path())
+					.with_namespace("env")
+					.with_plugin_data(EnvVar("/home/crab")),
+				name => OnResolveResult::error(MessageBuilder::new(format!("unknown variable {name}")))
+			});
+			builder.on_load(Filter::new(".*").with_namespace("env"), |args| {
+				let value = args.plugin_data_as::<EnvVar>().map(|v| v.0).unwrap_or_default();
+				OnLoadResult::contents(format!("export default {value:?};")).with_loader(Loader::Js)
+			});
+			builder.on_end(|result| {
+				if result.outputs().is_empty() {
+					OnEndResult::ok().with_warning(MessageBuilder::new("no outputs"))
+				} else {
+					OnEndResult::ok()
+				}
+			});
+		}
+	}
+
+	#[test]
+	fn test_plugin_tester() {
+		let mut tester = PluginTester::new(EnvPlugin).with_file("src/util.js", "export {}");
+		assert_eq!(tester.start().len(), 1);
+
+		let loaded = tester.resolve_and_load("env:HOME").unwrap();
+		assert!(loaded.is_from_plugin());
+		assert_eq!(loaded.contents_str().unwrap(), "export default \"/home/crab\";");
+		assert_eq!(loaded.loader(), Some(Loader::Js));
+
+		let resolved = tester.resolve(ResolveEvent::new("./util").with_importer("src/main.js")).unwrap();
+		assert_eq!((resolved.path(), resolved.is_from_plugin()), ("src/util.js", false));
+
+		assert!(tester.resolve("env:PATH").is_none());
+		assert_eq!(tester.errors()[0].text(), "unknown variable PATH");
+		assert_eq!(tester.errors()[0].plugin_name(), Some("env"));
+
+		assert_eq!(tester.end(&[]).len(), 1);
+		assert_eq!(tester.warnings()[0].text(), "no outputs");
+	}
+}
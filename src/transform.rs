@@ -0,0 +1,244 @@
+use std::ptr;
+
+use crate::{
+	build::{
+		options::{Format, Jsx, Loader, SourceMap},
+		tsconfig::TsconfigRaw
+	},
+	error::Message,
+	sys, util
+};
+
+/// Options for [`transform`]. These mirror the options of the same name on [`BuildOptions`](crate::BuildOptions).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TransformOptions {
+	loader: Loader,
+	sourcefile: Option<String>,
+	format: Format,
+	sourcemap: SourceMap,
+	minify_whitespace: bool,
+	minify_identifiers: bool,
+	minify_syntax: bool,
+	tsconfig_raw: Option<String>,
+	jsx: Jsx,
+	jsx_factory: Option<String>,
+	jsx_fragment: Option<String>,
+	jsx_import_source: Option<String>,
+	jsx_dev: bool,
+	jsx_side_effects: bool
+}
+
+impl TransformOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// How to parse the input. Defaults to JavaScript; use [`Loader::Tsx`] for TypeScript with JSX.
+	pub fn loader(mut self, loader: Loader) -> Self {
+		self.loader = loader;
+		self
+	}
+
+	/// The file name to use for the input in messages & source maps.
+	pub fn sourcefile(mut self, sourcefile: impl ToString) -> Self {
+		self.sourcefile = Some(sourcefile.to_string());
+		self
+	}
+
+	pub fn format(mut self, format: Format) -> Self {
+		self.format = format;
+		self
+	}
+
+	/// With [`SourceMap::External`], the source map is returned by [`TransformResult::map`] instead of being inlined
+	/// into the code.
+	pub fn sourcemap(mut self, source_map: SourceMap) -> Self {
+		self.sourcemap = source_map;
+		self
+	}
+
+	pub fn minify(self, enable: bool) -> Self {
+		self.minify_each(enable, enable, enable)
+	}
+
+	pub fn minify_each(mut self, whitespace: bool, identifiers: bool, syntax: bool) -> Self {
+		self.minify_whitespace = whitespace;
+		self.minify_identifiers = identifiers;
+		self.minify_syntax = syntax;
+		self
+	}
+
+	/// Transforms the input as if it were covered by `tsconfig`. No tsconfig is read from disk.
+	pub fn tsconfig_raw(mut self, tsconfig: &TsconfigRaw) -> Self {
+		self.tsconfig_raw = Some(tsconfig.to_json());
+		self
+	}
+
+	pub fn jsx(mut self, mode: Jsx) -> Self {
+		self.jsx = mode;
+		self
+	}
+
+	pub fn jsx_factory(mut self, factory: impl ToString) -> Self {
+		self.jsx_factory = Some(factory.to_string());
+		self
+	}
+
+	pub fn jsx_fragment(mut self, fragment: impl ToString) -> Self {
+		self.jsx_fragment = Some(fragment.to_string());
+		self
+	}
+
+	/// The package the [`Jsx::Automatic`] runtime is imported from. The import is left in the output as-is, so unlike
+	/// when bundling, the package doesn't have to be installed.
+	pub fn jsx_import_source(mut self, source: impl ToString) -> Self {
+		self.jsx_import_source = Some(source.to_string());
+		self
+	}
+
+	pub fn jsx_dev(mut self, enable: bool) -> Self {
+		self.jsx_dev = enable;
+		self
+	}
+
+	pub fn jsx_side_effects(mut self, enable: bool) -> Self {
+		self.jsx_side_effects = enable;
+		self
+	}
+
+	fn as_ffi(&self) -> sys::TransformOptions {
+		fn str_parts(s: &Option<String>) -> (*const i8, usize) {
+			s.as_deref().map(|s| (s.as_ptr().cast(), s.len())).unwrap_or((ptr::null(), 0))
+		}
+
+		let (sourcefile, sourcefile_len) = str_parts(&self.sourcefile);
+		let (tsconfig_raw, tsconfig_raw_len) = str_parts(&self.tsconfig_raw);
+		let (jsx_factory, jsx_factory_len) = str_parts(&self.jsx_factory);
+		let (jsx_fragment, jsx_fragment_len) = str_parts(&self.jsx_fragment);
+		let (jsx_import_source, jsx_import_source_len) = str_parts(&self.jsx_import_source);
+		sys::TransformOptions {
+			loader: self.loader as u8,
+			sourcefile,
+			sourcefile_len,
+			format: self.format as u8,
+			sourcemap: self.sourcemap as u8,
+			minify_whitespace: self.minify_whitespace,
+			minify_identifiers: self.minify_identifiers,
+			minify_syntax: self.minify_syntax,
+			tsconfig_raw,
+			tsconfig_raw_len,
+			jsx: self.jsx as u8,
+			jsx_factory,
+			jsx_factory_len,
+			jsx_fragment,
+			jsx_fragment_len,
+			jsx_import_source,
+			jsx_import_source_len,
+			jsx_dev: self.jsx_dev,
+			jsx_side_effects: self.jsx_side_effects
+		}
+	}
+}
+
+pub struct TransformResult(*mut sys::TransformResult);
+
+unsafe impl Send for TransformResult {}
+
+impl TransformResult {
+	#[inline(always)]
+	fn inner(&self) -> &sys::TransformResult {
+		unsafe { &*self.0 }
+	}
+
+	pub fn is_error(&self) -> bool {
+		self.inner().errors_len > 0
+	}
+
+	pub fn errors(&self) -> &[Message<'_>] {
+		unsafe { util::slice_from_raw_parts_or_empty(self.inner().errors.cast_const().cast::<Message>(), self.inner().errors_len) }
+	}
+
+	pub fn warnings(&self) -> &[Message<'_>] {
+		unsafe { util::slice_from_raw_parts_or_empty(self.inner().warnings.cast_const().cast::<Message>(), self.inner().warnings_len) }
+	}
+
+	/// The transformed code. Empty if the transform failed.
+	pub fn code(&self) -> &str {
+		unsafe { util::as_str_or_empty(self.inner().code, self.inner().code_len) }
+	}
+
+	/// The source map, if [`sourcemap`](TransformOptions::sourcemap) is set to [`SourceMap::External`].
+	pub fn map(&self) -> Option<&str> {
+		unsafe { util::as_str_opt(self.inner().source_map, self.inner().source_map_len) }
+	}
+}
+
+impl Drop for TransformResult {
+	fn drop(&mut self) {
+		tracing::trace!("Dropping TransformResult");
+		unsafe { sys::Zsb_TransformResult_Destroy(self.0) };
+	}
+}
+
+/// Transforms a single file's worth of code, without resolving or bundling any imports, e.g. to compile a `.tsx`
+/// snippet:
+///
+/// ```no_run
+/// # use zsbuild::{transform, Jsx, Loader, TransformOptions};
+/// let options = TransformOptions::new().loader(Loader::Tsx).jsx(Jsx::Automatic);
+/// let res = transform("export const App = () => <h1>Hello</h1>;", &options);
+/// assert!(res.code().contains("react/jsx-runtime"));
+/// ```
+pub fn transform(code: &str, options: &TransformOptions) -> TransformResult {
+	let mut ffi_options = options.as_ffi();
+	let res = unsafe { sys::Zsb_Transform(code.as_ptr() as *mut _, code.len(), &mut ffi_options) };
+	if res.is_null() {
+		panic!("");
+	}
+	TransformResult(res)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{BuildOptions, build};
+
+	#[test]
+	fn test_transform_automatic_runtime() {
+		let code = "export const App = (props: { name: string }) => <>\n\t<h1>Hello {props.name}</h1>\n</>;";
+		let options = TransformOptions::new()
+			.loader(Loader::Tsx)
+			.jsx(Jsx::Automatic)
+			.jsx_import_source("preact");
+		let res = transform(code, &options);
+		if res.is_error() {
+			panic!("{}", &res.errors()[0]);
+		}
+		assert!(res.code().contains("from \"preact/jsx-runtime\""), "{}", res.code());
+		assert!(!res.code().contains("props: {"));
+
+		let res = transform(code, &options.jsx_dev(true));
+		assert!(res.code().contains("from \"preact/jsx-dev-runtime\""), "{}", res.code());
+	}
+
+	#[test]
+	fn test_transform_import_source_errors() {
+		// the pragma only works with the automatic runtime
+		let res = transform("/** @jsxImportSource preact */\nexport const el = <div />;", &TransformOptions::new().loader(Loader::Tsx).sourcefile("el.tsx"));
+		let warning = &res.warnings()[0];
+		assert!(warning.text().starts_with("The JSX import source cannot be set"), "{}", warning.text());
+		let location = warning.location().unwrap();
+		assert_eq!((location.file(), location.line()), (Some("el.tsx"), 1));
+
+		let res = build(
+			&BuildOptions::new()
+				.entry_point("test/jsx/app.tsx", "out.js")
+				.bundle(true)
+				.jsx(Jsx::Automatic)
+				.jsx_import_source("missing-jsx")
+				.write(false)
+		);
+		assert!(res.is_error());
+		assert_eq!(res.errors()[0].text(), "Could not resolve \"missing-jsx/jsx-runtime\"");
+	}
+}
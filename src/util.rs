@@ -1,4 +1,8 @@
-use std::{ffi::c_char, slice, str};
+use std::{
+	ffi::c_char,
+	path::{Component, Path, PathBuf},
+	slice, str
+};
 
 pub unsafe fn as_str<'s>(ptr: *const c_char, len: usize) -> &'s str {
 	unsafe { str::from_utf8_unchecked(slice::from_raw_parts(ptr.cast(), len)) }
@@ -16,6 +20,15 @@ pub unsafe fn slice_from_raw_parts_or_empty<'a, T>(data: *const T, len: usize) -
 	if data.is_null() && len == 0 { &[] } else { std::slice::from_raw_parts(data, len) }
 }
 
+pub unsafe fn take_error(err: *mut crate::sys::Error) -> Option<String> {
+	if err.is_null() {
+		return None;
+	}
+	let message = as_str_or_empty((*err).message, (*err).message_len).to_string();
+	crate::sys::Zsb_Error_Destroy(err);
+	Some(message)
+}
+
 pub fn decompose_string(s: String) -> (usize, *const i8) {
 	let s = s.into_boxed_str();
 	// SAFETY: casting a `*mut str` to `*mut i8` rightfully seems psychopathic, but remember that Box<[T]> (which
@@ -44,3 +57,86 @@ pub trait IntoFFI {
 	fn into_ffi(self) -> Self::FFIType;
 	unsafe fn drop_ffi(ty: Self::FFIType);
 }
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn base64_encode(input: &[u8]) -> String {
+	let mut out = String::with_capacity((input.len() + 2) / 3 * 4);
+	for chunk in input.chunks(3) {
+		let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - i * 8));
+		for i in 0..4 {
+			out.push(if i <= chunk.len() { BASE64_ALPHABET[(n >> (18 - i * 6)) as usize & 63] as char } else { '=' });
+		}
+	}
+	out
+}
+
+pub fn base64_decode(input: &str) -> Option<Vec<u8>> {
+	let input = input.trim_end_matches('=');
+	let mut out = Vec::with_capacity(input.len() * 3 / 4);
+	let mut buffer = 0u32;
+	let mut bits = 0;
+	for c in input.bytes() {
+		let value = BASE64_ALPHABET.iter().position(|&b| b == c)? as u32;
+		buffer = (buffer << 6) | value;
+		bits += 6;
+		if bits >= 8 {
+			bits -= 8;
+			out.push((buffer >> bits) as u8);
+		}
+	}
+	Some(out)
+}
+
+/// Lexically resolves `.` and `..` components without touching the file system.
+pub fn normalize_path(path: &Path) -> PathBuf {
+	let mut out = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir => match out.components().next_back() {
+				Some(Component::Normal(_)) => {
+					out.pop();
+				}
+				// `/..` is `/`
+				Some(Component::RootDir) => {}
+				_ => out.push("..")
+			},
+			c => out.push(c)
+		}
+	}
+	out
+}
+
+/// Matches `path` against a glob `pattern`, where `*` matches within a path segment, `**` matches across segments and
+/// `?` matches a single character other than `/`.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+	fn matches(pattern: &[u8], path: &[u8]) -> bool {
+		match pattern {
+			[] => path.is_empty(),
+			[b'*', b'*', b'/', rest @ ..] => matches(rest, path) || (0..path.len()).any(|i| path[i] == b'/' && matches(rest, &path[i + 1..])),
+			[b'*', b'*', rest @ ..] => (0..=path.len()).any(|i| matches(rest, &path[i..])),
+			[b'*', rest @ ..] => (0..=path.len())
+				.take_while(|&i| i == 0 || path[i - 1] != b'/')
+				.any(|i| matches(rest, &path[i..])),
+			[b'?', rest @ ..] => matches!(path, [c, ..] if *c != b'/') && matches(rest, &path[1..]),
+			[c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..])
+		}
+	}
+	matches(pattern.as_bytes(), path.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_normalize_path() {
+		assert_eq!(normalize_path(Path::new("a/./b/../c")), Path::new("a/c"));
+		assert_eq!(normalize_path(Path::new("../../x")), Path::new("../../x"));
+		assert_eq!(normalize_path(Path::new("a/../../x")), Path::new("../x"));
+		assert_eq!(normalize_path(Path::new("./../a/..")), Path::new(".."));
+		assert_eq!(normalize_path(Path::new("/..")), Path::new("/"));
+		assert_eq!(normalize_path(Path::new("/a/../../b")), Path::new("/b"));
+	}
+}
@@ -0,0 +1,4 @@
+import "./a.css";
+import { shared } from "./shared.js";
+
+console.log("a", shared);
@@ -0,0 +1,2 @@
+console.log(process.env.API_URL, __CONFIG__.debug);
+console.log(import.meta.env.MISSING);
@@ -0,0 +1,2 @@
+const state = { count_: 1, label_: 'x', keep_: true };
+console.log(state.count_, state.label_, state.keep_);
@@ -0,0 +1,2 @@
+import value from "cond-pkg";
+console.log(value);
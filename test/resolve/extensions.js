@@ -0,0 +1,2 @@
+import value from "./ext-target";
+console.log(value);
@@ -0,0 +1,2 @@
+import value from "fields-pkg";
+console.log(value);
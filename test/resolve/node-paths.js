@@ -0,0 +1,2 @@
+import value from "np-pkg";
+console.log(value);
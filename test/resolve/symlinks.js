@@ -0,0 +1,2 @@
+import value from "linked";
+console.log(value);